@@ -1,4 +1,5 @@
 import os
+import socket
 import psutil
 import json
 import time
@@ -10,7 +11,11 @@ import GPUtil
 ROOT_PID = int(os.getenv("MONITOR_PID", "1"))
 
 LOG_FILE = f"monitor_logs/{ROOT_PID}.jsonl"
+# Optional JSON object of application counters, rewritten by the monitored app
+CUSTOM_METRICS_FILE = os.getenv("MONITOR_CUSTOM_METRICS")
 INTERVAL = 1  # seconds
+SCHEMA_VERSION = 1  # bump alongside CURRENT_SCHEMA_VERSION in the viewer
+CLOCK_TICKS = os.sysconf("SC_CLK_TCK")
 os.makedirs(os.path.dirname(LOG_FILE), exist_ok=True)
 
 
@@ -29,6 +34,9 @@ def get_thread_info(pid):
                     "State": None,
                     "CPU_Affinity": None,
                     "Core": None,
+                    "CPU_Time_Ms": None,
+                    "Voluntary_Ctxt_Switches": None,
+                    "Nonvoluntary_Ctxt_Switches": None,
                 }
                 for line in f:
                     if line.startswith("Name:"):
@@ -37,6 +45,10 @@ def get_thread_info(pid):
                         thread_data["State"] = line.split(":")[1].strip()
                     elif line.startswith("Cpus_allowed_list:"):
                         thread_data["CPU_Affinity"] = line.split(":")[1].strip()
+                    elif line.startswith("voluntary_ctxt_switches:"):
+                        thread_data["Voluntary_Ctxt_Switches"] = int(line.split(":")[1])
+                    elif line.startswith("nonvoluntary_ctxt_switches:"):
+                        thread_data["Nonvoluntary_Ctxt_Switches"] = int(line.split(":")[1])
 
                 stat_path = os.path.join(task_dir, tid, "stat")
                 try:
@@ -44,6 +56,10 @@ def get_thread_info(pid):
                         fields = f_stat.read().split()
                         if len(fields) >= 39:
                             thread_data["Core"] = int(fields[38])
+                        if len(fields) >= 15:
+                            # utime + stime, in clock ticks
+                            ticks = int(fields[13]) + int(fields[14])
+                            thread_data["CPU_Time_Ms"] = ticks * 1000 / CLOCK_TICKS
                 except FileNotFoundError:
                     thread_data["Core"] = None
 
@@ -53,8 +69,135 @@ def get_thread_info(pid):
     return threads
 
 
-def get_gpu_info():
+_cpu_procs = {}
+
+
+def get_cpu_percent(proc):
+    # psutil measures CPU usage since the previous call on the same Process object
+    cached = _cpu_procs.setdefault(proc.pid, proc)
+    try:
+        return cached.cpu_percent(interval=None)
+    except psutil.NoSuchProcess:
+        return None
+
+
+def get_rss_mb(proc):
+    try:
+        return round(proc.memory_info().rss / (1024 * 1024), 1)
+    except psutil.NoSuchProcess:
+        return None
+
+
+def get_io_bytes(proc):
+    try:
+        io = proc.io_counters()
+        return {"IO_Read_Bytes": io.read_bytes, "IO_Write_Bytes": io.write_bytes}
+    except (psutil.NoSuchProcess, psutil.AccessDenied, AttributeError):
+        return {"IO_Read_Bytes": None, "IO_Write_Bytes": None}
+
+
+def get_fd_count(proc):
+    try:
+        return proc.num_fds()
+    except (psutil.NoSuchProcess, psutil.AccessDenied, AttributeError):
+        return None
+
+
+def get_cgroup(pid):
+    # Prefer the unified (v2) hierarchy entry, "0::<path>"
+    try:
+        with open(f"/proc/{pid}/cgroup") as f:
+            entries = [line.strip().split(":", 2) for line in f if line.strip()]
+    except (FileNotFoundError, PermissionError):
+        return None
+    for hierarchy, _, path in entries:
+        if hierarchy == "0":
+            return path
+    return entries[0][2] if entries else None
+
+
+def get_owner_and_start(proc):
+    try:
+        return {
+            "User": proc.username(),
+            "UID": proc.uids().real,
+            "Start_Time": datetime.fromtimestamp(proc.create_time()).isoformat(),
+        }
+    except (psutil.NoSuchProcess, psutil.AccessDenied, KeyError):
+        return {"User": None, "UID": None, "Start_Time": None}
+
+
+def get_custom_metrics():
+    if not CUSTOM_METRICS_FILE:
+        return {}
+    try:
+        with open(CUSTOM_METRICS_FILE) as f:
+            metrics = json.load(f)
+    except (OSError, ValueError):
+        return {}
+    if not isinstance(metrics, dict):
+        return {}
+    return {
+        name: value for name, value in metrics.items()
+        if isinstance(value, (int, float)) and not isinstance(value, bool)
+    }
+
+
+def get_load_averages():
+    load1, load5, load15 = os.getloadavg()
+    return {"LoadAvg_1": load1, "LoadAvg_5": load5, "LoadAvg_15": load15}
+
+
+def get_system_memory():
+    mem = psutil.virtual_memory()
+    swap = psutil.swap_memory()
+    mb = 1024 * 1024
+    return {
+        "Memory_Total_MB": round(mem.total / mb, 1),
+        "Memory_Used_MB": round(mem.used / mb, 1),
+        "Memory_Cached_MB": round(getattr(mem, "cached", 0) / mb, 1),
+        "Swap_Total_MB": round(swap.total / mb, 1),
+        "Swap_Used_MB": round(swap.used / mb, 1),
+    }
+
+
+# nvidia-smi query fields not reported by GPUtil, keyed by snapshot field name
+NVIDIA_SMI_GPU_FIELDS = {
+    "Power_Draw_W": "power.draw",
+    "Power_Limit_W": "power.limit",
+    "SM_Clock_MHz": "clocks.sm",
+    "Memory_Clock_MHz": "clocks.mem",
+}
+
+
+def get_nvidia_smi_gpu_stats():
+    try:
+        output = subprocess.check_output([
+            "nvidia-smi",
+            "--query-gpu=index," + ",".join(NVIDIA_SMI_GPU_FIELDS.values()),
+            "--format=csv,noheader,nounits"
+        ], encoding="utf-8")
+    except (subprocess.CalledProcessError, FileNotFoundError):
+        return {}
+    stats = {}
+    for line in output.strip().split("\n"):
+        values = [x.strip() for x in line.split(",")]
+        if len(values) != len(NVIDIA_SMI_GPU_FIELDS) + 1:
+            continue
+        gpu_stats = {}
+        for field, value in zip(NVIDIA_SMI_GPU_FIELDS, values[1:]):
+            try:
+                gpu_stats[field] = float(value)
+            except ValueError:
+                # "[N/A]" readings
+                gpu_stats[field] = None
+        stats[int(values[0])] = gpu_stats
+    return stats
+
+
+def get_gpu_info(gpu_processes):
     gpus = GPUtil.getGPUs()
+    extra_stats = get_nvidia_smi_gpu_stats()
     gpu_data = []
     for gpu in gpus:
         gpu_data.append({
@@ -64,7 +207,13 @@ def get_gpu_info():
             "Memory_Used_MB": gpu.memoryUsed,
             "Memory_Total_MB": gpu.memoryTotal,
             "Temperature_C": gpu.temperature,
-            "Driver": gpu.driver
+            "Driver": gpu.driver,
+            **{field: extra_stats.get(gpu.id, {}).get(field) for field in NVIDIA_SMI_GPU_FIELDS},
+            "GPU_Processes": [
+                {"PID": p["PID"], "Memory_MB": p["GPU_Memory_MB"]}
+                for p in gpu_processes
+                if p["GPU_UUID"] == gpu.uuid
+            ]
         })
     return gpu_data
 
@@ -100,6 +249,12 @@ def snapshot_system_state(root_pid):
             "Name": root_proc.name(),
             "CMD": ' '.join(root_proc.cmdline()) if root_proc.cmdline() else root_proc.name(),
             "Threads": get_thread_info(root_proc.pid),
+            "CPU_Percent": get_cpu_percent(root_proc),
+            "Memory_RSS_MB": get_rss_mb(root_proc),
+            "Container": get_cgroup(root_proc.pid),
+            "FD_Count": get_fd_count(root_proc),
+            **get_io_bytes(root_proc),
+            **get_owner_and_start(root_proc),
             "Children": []
         }
         for child in root_proc.children(recursive=True):
@@ -107,15 +262,28 @@ def snapshot_system_state(root_pid):
                 "PID": child.pid,
                 "Name": child.name(),
                 "CMD": ' '.join(child.cmdline()) if child.cmdline() else child.name(),
-                "Threads": get_thread_info(child.pid)
+                "Threads": get_thread_info(child.pid),
+                "CPU_Percent": get_cpu_percent(child),
+                "Memory_RSS_MB": get_rss_mb(child),
+                "Container": get_cgroup(child.pid),
+                "FD_Count": get_fd_count(child),
+                **get_io_bytes(child),
+                **get_owner_and_start(child)
             }
             proc_info["Children"].append(child_data)
+        gpu_processes = get_gpu_process_details()
         return {
+            "SchemaVersion": SCHEMA_VERSION,
             "Timestamp": datetime.now().isoformat(),
+            "Hostname": socket.gethostname(),
             "CPU_Cores_Total": os.cpu_count(),
+            "CPU_Core_Loads": psutil.cpu_percent(percpu=True),
+            **get_load_averages(),
+            **get_system_memory(),
             "ProcessTree": proc_info,
-            "GPUStatus": get_gpu_info(),
-            "GPUProcesses": get_gpu_process_details()
+            "GPUStatus": get_gpu_info(gpu_processes),
+            "GPUProcesses": gpu_processes,
+            "Custom": get_custom_metrics()
         }
     except psutil.NoSuchProcess:
         return None
@@ -0,0 +1,142 @@
+//! Native counterpart of `monitor/monitor.py`: samples a process tree, the
+//! system and any NVIDIA GPUs at an interval and appends each sample to a
+//! JSONL trace as a `timeline_core` `Snapshot`, so the viewer always reads
+//! what this writes.
+
+mod nvidia;
+mod procfs;
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use timeline_core::model::{micros_to_timestamp, Snapshot};
+use timeline_core::parse::CURRENT_SCHEMA_VERSION;
+
+/// What to sample and where to write it, from the environment variables the
+/// Python monitor reads.
+struct Config {
+    root_pid: u32,
+    interval: Duration,
+    log_file: PathBuf,
+    /// JSON object of application counters, rewritten by the monitored app
+    custom_metrics_file: Option<PathBuf>,
+}
+
+fn env_var<T: FromStr>(name: &str, default: T) -> Result<T, String> {
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("{name} is not valid: {value}")),
+        Err(_) => Ok(default),
+    }
+}
+
+impl Config {
+    fn from_env() -> Result<Self, String> {
+        let root_pid = env_var("MONITOR_PID", 1)?;
+        let interval_secs: f64 = env_var("MONITOR_INTERVAL", 1.0)?;
+        let interval = Duration::try_from_secs_f64(interval_secs)
+            .map_err(|_| format!("MONITOR_INTERVAL is not valid: {interval_secs}"))?;
+        Ok(Config {
+            root_pid,
+            interval,
+            log_file: env_var(
+                "MONITOR_LOG",
+                PathBuf::from(format!("monitor_logs/{root_pid}.jsonl")),
+            )?,
+            custom_metrics_file: std::env::var_os("MONITOR_CUSTOM_METRICS").map(PathBuf::from),
+        })
+    }
+}
+
+/// The numbers in the custom metrics file; anything else in it is ignored.
+fn custom_metrics(path: &Path) -> BTreeMap<String, f64> {
+    let Ok(text) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    let Ok(serde_json::Value::Object(metrics)) = serde_json::from_str(&text) else {
+        return BTreeMap::new();
+    };
+    metrics
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value.as_f64()?)))
+        .collect()
+}
+
+/// One sample, or None once the monitored process has exited.
+fn snapshot(sampler: &mut procfs::Sampler, config: &Config) -> Option<Snapshot> {
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0.0, |since_epoch| since_epoch.as_micros() as f64);
+    let process_tree = sampler.process_tree(config.root_pid)?;
+    let core_loads = sampler.core_loads();
+    let load_averages = procfs::load_averages();
+    let memory = procfs::memory();
+    Some(Snapshot {
+        SchemaVersion: Some(CURRENT_SCHEMA_VERSION),
+        Timestamp: micros_to_timestamp(micros),
+        ProcessTree: process_tree,
+        GPUStatus: nvidia::gpus(),
+        CPU_Cores_Total: sampler.cores(),
+        LoadAvg_1: load_averages.map(|loads| loads[0]),
+        LoadAvg_5: load_averages.map(|loads| loads[1]),
+        LoadAvg_15: load_averages.map(|loads| loads[2]),
+        CPU_Core_Loads: core_loads,
+        Memory_Total_MB: memory.as_ref().map(|memory| memory.total_mb),
+        Memory_Used_MB: memory.as_ref().map(|memory| memory.used_mb),
+        Memory_Cached_MB: memory.as_ref().map(|memory| memory.cached_mb),
+        Swap_Total_MB: memory.as_ref().map(|memory| memory.swap_total_mb),
+        Swap_Used_MB: memory.as_ref().map(|memory| memory.swap_used_mb),
+        Custom: config
+            .custom_metrics_file
+            .as_deref()
+            .map(custom_metrics)
+            .unwrap_or_default(),
+        Hostname: procfs::hostname(),
+        ..Snapshot::default()
+    })
+}
+
+fn capture_loop(config: &Config) -> io::Result<()> {
+    if let Some(dir) = config.log_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut log_file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&config.log_file)?;
+    eprintln!(
+        "[INFO] Logging to {} every {:?}... Press Ctrl+C to stop.",
+        config.log_file.display(),
+        config.interval
+    );
+    let mut sampler = procfs::Sampler::new();
+    loop {
+        let started = Instant::now();
+        let Some(snapshot) = snapshot(&mut sampler, config) else {
+            eprintln!("Process no longer running. Exiting.");
+            return Ok(());
+        };
+        // One write per line, so a reader tailing the trace never sees half of one
+        let line = serde_json::to_string(&snapshot)? + "\n";
+        log_file.write_all(line.as_bytes())?;
+        std::thread::sleep(config.interval.saturating_sub(started.elapsed()));
+    }
+}
+
+fn main() {
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("[ERROR] {error}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(error) = capture_loop(&config) {
+        eprintln!("[ERROR] {error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,120 @@
+//! GPU readings from nvidia-smi. Machines without it report no GPUs.
+
+use std::process::Command;
+use timeline_core::model::{GPUProcess, GPUStatus};
+
+/// Queried per GPU, in the order `parse_gpus` reads them.
+const GPU_QUERY: &str = "index,name,utilization.gpu,memory.used,memory.total,temperature.gpu,driver_version,power.draw,power.limit,clocks.sm,clocks.mem,uuid";
+
+/// Queried per compute process, in the order `parse_compute_apps` reads them.
+const COMPUTE_APPS_QUERY: &str = "pid,gpu_uuid,used_memory";
+
+fn query(query: &str) -> Option<String> {
+    let output = Command::new("nvidia-smi")
+        .arg(query)
+        .arg("--format=csv,noheader,nounits")
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Readings the GPU doesn't support come out as "[N/A]" and are None.
+fn reading(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
+fn csv_rows(csv: &str) -> impl Iterator<Item = Vec<&str>> {
+    csv.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.split(',').map(str::trim).collect())
+}
+
+/// Memory held by each compute process, with the UUID of its GPU.
+fn parse_compute_apps(csv: &str) -> Vec<(String, GPUProcess)> {
+    csv_rows(csv)
+        .filter_map(|row| {
+            let [pid, gpu_uuid, used_memory] = row[..] else {
+                return None;
+            };
+            Some((
+                gpu_uuid.to_string(),
+                GPUProcess {
+                    PID: pid.parse().ok()?,
+                    Memory_MB: reading(used_memory)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn parse_gpus(csv: &str, compute_apps: &[(String, GPUProcess)]) -> Vec<GPUStatus> {
+    csv_rows(csv)
+        .filter_map(|row| {
+            let [index, name, load, memory_used, memory_total, temperature, driver, power_draw, power_limit, sm_clock, memory_clock, uuid] =
+                row[..]
+            else {
+                return None;
+            };
+            Some(GPUStatus {
+                GPU_ID: index.parse().ok()?,
+                Name: name.to_string(),
+                Load_Percent: reading(load).unwrap_or_default(),
+                Memory_Used_MB: reading(memory_used).unwrap_or_default(),
+                Memory_Total_MB: reading(memory_total).unwrap_or_default(),
+                Temperature_C: reading(temperature).unwrap_or_default(),
+                Driver: driver.to_string(),
+                Power_Draw_W: reading(power_draw),
+                Power_Limit_W: reading(power_limit),
+                SM_Clock_MHz: reading(sm_clock),
+                Memory_Clock_MHz: reading(memory_clock),
+                GPU_Processes: compute_apps
+                    .iter()
+                    .filter(|(gpu_uuid, _)| gpu_uuid == uuid)
+                    .map(|(_, process)| process.clone())
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Every GPU nvidia-smi lists, with the compute processes on it.
+pub(crate) fn gpus() -> Vec<GPUStatus> {
+    let Some(gpus) = query(&format!("--query-gpu={GPU_QUERY}")) else {
+        return Vec::new();
+    };
+    let compute_apps = query(&format!("--query-compute-apps={COMPUTE_APPS_QUERY}"))
+        .map(|csv| parse_compute_apps(&csv))
+        .unwrap_or_default();
+    parse_gpus(&gpus, &compute_apps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assigns_compute_processes_to_their_gpu() {
+        let apps = parse_compute_apps("4242, GPU-aaa, 1500\n17, GPU-bbb, [N/A]\n");
+        let gpus = parse_gpus(
+            "0, NVIDIA A100, 87, 20000, 40960, 65, 550.54, 250.5, 400.00, 1410, 1215, GPU-aaa\n\
+             1, NVIDIA A100, 0, 0, 40960, 30, 550.54, [N/A], [N/A], 210, 1215, GPU-bbb\n",
+            &apps,
+        );
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].Load_Percent, 87.0);
+        assert_eq!(gpus[0].Power_Draw_W, Some(250.5));
+        assert_eq!(
+            gpus[0].GPU_Processes,
+            [GPUProcess {
+                PID: 4242,
+                Memory_MB: 1500.0
+            }]
+        );
+        assert_eq!(gpus[1].GPU_ID, 1);
+        assert_eq!(gpus[1].Power_Draw_W, None);
+        assert!(gpus[1].GPU_Processes.is_empty());
+    }
+}
@@ -0,0 +1,381 @@
+//! Process, thread and system readings from /proc.
+
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+use timeline_core::model::{micros_to_timestamp, Process, Thread};
+
+/// Clock ticks per second of the CPU times in /proc (USER_HZ), which Linux
+/// fixes at 100 for userspace.
+const CLOCK_TICKS: f64 = 100.0;
+
+fn read(path: &str) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+/// Value of a `Key: value` line of a /proc status or meminfo file.
+fn field<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .map(str::trim)
+}
+
+/// Leading number of a value such as "1234 kB".
+fn number(value: &str) -> Option<f64> {
+    value.split_whitespace().next()?.parse().ok()
+}
+
+/// kB to MB, to one decimal like the Python monitor.
+fn kb_to_mb(kb: f64) -> f64 {
+    (kb / 1024.0 * 10.0).round() / 10.0
+}
+
+/// Fields of a /proc stat line after the command name, which is in
+/// parentheses and may contain spaces itself. Index 0 is the state, field 3
+/// in proc(5).
+fn stat_fields(stat: &str) -> Vec<&str> {
+    stat.rsplit_once(')')
+        .map_or_else(Vec::new, |(_, rest)| rest.split_whitespace().collect())
+}
+
+fn stat_field(stat: &[&str], proc5_field: usize) -> Option<f64> {
+    stat.get(proc5_field - 3)?.parse().ok()
+}
+
+/// User plus system CPU time from `stat_fields`, in clock ticks.
+fn cpu_ticks(stat: &[&str]) -> Option<f64> {
+    Some(stat_field(stat, 14)? + stat_field(stat, 15)?)
+}
+
+/// Busy and total ticks of each core from /proc/stat.
+fn core_ticks(stat: &str) -> Vec<(f64, f64)> {
+    stat.lines()
+        .filter(|line| {
+            line.strip_prefix("cpu")
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        })
+        .map(|line| {
+            // Guest time is already counted in user time, so it is left out
+            let ticks: Vec<f64> = line
+                .split_whitespace()
+                .skip(1)
+                .take(8)
+                .filter_map(|ticks| ticks.parse().ok())
+                .collect();
+            let total: f64 = ticks.iter().sum();
+            let idle = ticks.iter().skip(3).take(2).sum::<f64>();
+            (total - idle, total)
+        })
+        .collect()
+}
+
+fn thread(tid: u32, status: &str, stat: &str) -> Thread {
+    Thread {
+        TID: tid,
+        Name: field(status, "Name").map(str::to_string),
+        State: field(status, "State").map(str::to_string),
+        CPU_Time_Ms: cpu_ticks(&stat_fields(stat)).map(|ticks| ticks * 1000.0 / CLOCK_TICKS),
+        Voluntary_Ctxt_Switches: field(status, "voluntary_ctxt_switches").and_then(number),
+        Nonvoluntary_Ctxt_Switches: field(status, "nonvoluntary_ctxt_switches").and_then(number),
+    }
+}
+
+fn threads(pid: u32) -> Vec<Thread> {
+    let Ok(tasks) = fs::read_dir(format!("/proc/{pid}/task")) else {
+        return Vec::new();
+    };
+    let mut threads: Vec<Thread> = tasks
+        .flatten()
+        .filter_map(|task| {
+            let tid: u32 = task.file_name().to_str()?.parse().ok()?;
+            // Threads exiting mid-sample are skipped
+            let status = read(&format!("/proc/{pid}/task/{tid}/status"))?;
+            let stat = read(&format!("/proc/{pid}/task/{tid}/stat")).unwrap_or_default();
+            Some(thread(tid, &status, &stat))
+        })
+        .collect();
+    threads.sort_by_key(|thread| thread.TID);
+    threads
+}
+
+/// The unified (v2) hierarchy entry of /proc/<pid>/cgroup, `0::<path>`, or
+/// else the first one.
+fn cgroup(cgroups: &str) -> Option<String> {
+    let entries: Vec<(&str, &str)> = cgroups
+        .lines()
+        .filter_map(|line| {
+            let (hierarchy, rest) = line.split_once(':')?;
+            Some((hierarchy, rest.split_once(':')?.1))
+        })
+        .collect();
+    entries
+        .iter()
+        .find(|(hierarchy, _)| *hierarchy == "0")
+        .or(entries.first())
+        .map(|(_, path)| path.to_string())
+}
+
+/// User names by UID from /etc/passwd.
+fn users() -> HashMap<u32, String> {
+    read("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+/// Boot time in seconds since the epoch, from /proc/stat.
+fn boot_time(stat: &str) -> Option<f64> {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// System memory and swap, in MB.
+pub(crate) struct Memory {
+    pub(crate) total_mb: f64,
+    pub(crate) used_mb: f64,
+    pub(crate) cached_mb: f64,
+    pub(crate) swap_total_mb: f64,
+    pub(crate) swap_used_mb: f64,
+}
+
+/// Used memory is what's neither free nor buffers or page cache, as psutil
+/// reports it.
+fn parse_meminfo(meminfo: &str) -> Option<Memory> {
+    let kb = |key| field(meminfo, key).and_then(number);
+    let total = kb("MemTotal")?;
+    let cached = kb("Cached").unwrap_or_default() + kb("SReclaimable").unwrap_or_default();
+    let used = total - kb("MemFree")? - kb("Buffers").unwrap_or_default() - cached;
+    let swap_total = kb("SwapTotal").unwrap_or_default();
+    Some(Memory {
+        total_mb: kb_to_mb(total),
+        used_mb: kb_to_mb(used.max(0.0)),
+        cached_mb: kb_to_mb(cached),
+        swap_total_mb: kb_to_mb(swap_total),
+        swap_used_mb: kb_to_mb(swap_total - kb("SwapFree").unwrap_or(swap_total)),
+    })
+}
+
+pub(crate) fn memory() -> Option<Memory> {
+    parse_meminfo(&read("/proc/meminfo")?)
+}
+
+/// The 1, 5 and 15 minute load averages.
+pub(crate) fn load_averages() -> Option<[f64; 3]> {
+    let loadavg = read("/proc/loadavg")?;
+    let mut loads = loadavg.split_whitespace().map(|load| load.parse().ok());
+    Some([loads.next()??, loads.next()??, loads.next()??])
+}
+
+pub(crate) fn hostname() -> Option<String> {
+    read("/proc/sys/kernel/hostname").map(|hostname| hostname.trim().to_string())
+}
+
+/// Reads /proc, keeping the counters of the previous sample to turn them
+/// into loads.
+#[derive(Default)]
+pub(crate) struct Sampler {
+    last_sample: Option<Instant>,
+    /// CPU time of each process at the last sample, in clock ticks
+    process_ticks: HashMap<u32, f64>,
+    /// Busy and total ticks of each core at the last sample
+    core_ticks: Vec<(f64, f64)>,
+    boot_time: Option<f64>,
+    users: HashMap<u32, String>,
+}
+
+impl Sampler {
+    pub(crate) fn new() -> Self {
+        Sampler {
+            boot_time: read("/proc/stat").as_deref().and_then(boot_time),
+            users: users(),
+            ..Sampler::default()
+        }
+    }
+
+    /// The process `root_pid` with all its descendants, or None once it has
+    /// exited.
+    pub(crate) fn process_tree(&mut self, root_pid: u32) -> Option<Process> {
+        let now = Instant::now();
+        let elapsed = self
+            .last_sample
+            .map(|last| now.duration_since(last).as_secs_f64());
+        self.last_sample = Some(now);
+
+        let mut stats = HashMap::new();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for entry in fs::read_dir("/proc").ok()?.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let Some(stat) = read(&format!("/proc/{pid}/stat")) else {
+                continue;
+            };
+            if let Some(ppid) = stat_field(&stat_fields(&stat), 4) {
+                children.entry(ppid as u32).or_default().push(pid);
+            }
+            stats.insert(pid, stat);
+        }
+
+        let mut ticks = HashMap::new();
+        let tree = self.process(root_pid, &stats, &children, elapsed, &mut ticks);
+        self.process_ticks = ticks;
+        tree
+    }
+
+    fn process(
+        &self,
+        pid: u32,
+        stats: &HashMap<u32, String>,
+        children: &HashMap<u32, Vec<u32>>,
+        elapsed: Option<f64>,
+        ticks: &mut HashMap<u32, f64>,
+    ) -> Option<Process> {
+        let stat = stat_fields(stats.get(&pid)?);
+        let status = read(&format!("/proc/{pid}/status")).unwrap_or_default();
+        let name = read(&format!("/proc/{pid}/comm"))
+            .map(|comm| comm.trim().to_string())
+            .unwrap_or_default();
+        let cmdline = read(&format!("/proc/{pid}/cmdline"))
+            .map(|cmdline| cmdline.split('\0').collect::<Vec<_>>().join(" "))
+            .unwrap_or_default();
+
+        let cpu_percent = cpu_ticks(&stat).and_then(|process_ticks| {
+            ticks.insert(pid, process_ticks);
+            let busy = process_ticks - self.process_ticks.get(&pid)?;
+            let elapsed = elapsed.filter(|&elapsed| elapsed > 0.0)?;
+            Some((busy / CLOCK_TICKS / elapsed * 1000.0).round() / 10.0)
+        });
+        let io = read(&format!("/proc/{pid}/io")).unwrap_or_default();
+        let uid = field(&status, "Uid").and_then(number).map(|uid| uid as u32);
+        let start_time = self
+            .boot_time
+            .zip(stat_field(&stat, 22))
+            .map(|(boot, start)| micros_to_timestamp((boot + start / CLOCK_TICKS) * 1e6));
+
+        let mut child_pids = children.get(&pid).cloned().unwrap_or_default();
+        child_pids.sort_unstable();
+        let child_processes: Vec<Process> = child_pids
+            .into_iter()
+            .filter_map(|child| self.process(child, stats, children, elapsed, ticks))
+            .collect();
+
+        Some(Process {
+            PID: pid,
+            CMD: Some(if cmdline.trim().is_empty() {
+                name.clone()
+            } else {
+                cmdline.trim().to_string()
+            }),
+            Name: name,
+            Threads: Some(threads(pid)),
+            Children: Some(child_processes),
+            CPU_Percent: cpu_percent,
+            IO_Read_Bytes: field(&io, "read_bytes").and_then(number),
+            IO_Write_Bytes: field(&io, "write_bytes").and_then(number),
+            Container: read(&format!("/proc/{pid}/cgroup")).and_then(|cgroups| cgroup(&cgroups)),
+            FD_Count: fs::read_dir(format!("/proc/{pid}/fd"))
+                .ok()
+                .map(|fds| fds.count() as f64),
+            User: uid.and_then(|uid| self.users.get(&uid).cloned()),
+            UID: uid,
+            Start_Time: start_time,
+            Memory_RSS_MB: field(&status, "VmRSS").and_then(number).map(kb_to_mb),
+        })
+    }
+
+    /// Load of each core in percent since the last call; empty on the first.
+    pub(crate) fn core_loads(&mut self) -> Vec<f64> {
+        let ticks = read("/proc/stat")
+            .as_deref()
+            .map(core_ticks)
+            .unwrap_or_default();
+        let loads = if ticks.len() == self.core_ticks.len() {
+            ticks
+                .iter()
+                .zip(&self.core_ticks)
+                .map(|(&(busy, total), &(last_busy, last_total))| {
+                    let total = total - last_total;
+                    if total > 0.0 {
+                        ((busy - last_busy) / total * 1000.0).round() / 10.0
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.core_ticks = ticks;
+        loads
+    }
+
+    /// Logical cores counted by the last `core_loads`.
+    pub(crate) fn cores(&self) -> u32 {
+        self.core_ticks.len() as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_fields_follow_the_command_name() {
+        let stat = "42 (tokio (worker) 1) S 7 42 42 0 -1 4194560 0 0 0 0 150 50 0 0 20 0 3 0 1234";
+        let fields = stat_fields(stat);
+        assert_eq!(fields[0], "S");
+        assert_eq!(stat_field(&fields, 4), Some(7.0));
+        assert_eq!(cpu_ticks(&fields), Some(200.0));
+        assert_eq!(stat_field(&fields, 22), Some(1234.0));
+    }
+
+    #[test]
+    fn reads_thread_status() {
+        let status = "Name:\tworker\nState:\tR (running)\nvoluntary_ctxt_switches:\t12\nnonvoluntary_ctxt_switches:\t3\n";
+        let thread = thread(5, status, "5 (worker) R 1 1 1 0 -1 0 0 0 0 0 30 20");
+        assert_eq!(thread.Name.as_deref(), Some("worker"));
+        assert_eq!(thread.State.as_deref(), Some("R (running)"));
+        assert_eq!(thread.CPU_Time_Ms, Some(500.0));
+        assert_eq!(thread.Voluntary_Ctxt_Switches, Some(12.0));
+        assert_eq!(thread.Nonvoluntary_Ctxt_Switches, Some(3.0));
+    }
+
+    #[test]
+    fn memory_used_leaves_out_the_page_cache() {
+        let meminfo = "MemTotal: 8192000 kB\nMemFree: 1024000 kB\nBuffers: 102400 kB\nCached: 2048000 kB\nSReclaimable: 51200 kB\nSwapTotal: 2048000 kB\nSwapFree: 1024000 kB\n";
+        let memory = parse_meminfo(meminfo).unwrap();
+        assert_eq!(memory.total_mb, 8000.0);
+        assert_eq!(memory.cached_mb, 2050.0);
+        assert_eq!(memory.used_mb, 4850.0);
+        assert_eq!(memory.swap_used_mb, 1000.0);
+    }
+
+    #[test]
+    fn prefers_the_unified_cgroup() {
+        assert_eq!(
+            cgroup("12:cpu:/docker/abc\n0::/system.slice/app.service\n").as_deref(),
+            Some("/system.slice/app.service")
+        );
+        assert_eq!(cgroup("4:memory:/job\n").as_deref(), Some("/job"));
+    }
+
+    #[test]
+    fn core_ticks_skip_the_total_line() {
+        let stat = "cpu  10 0 10 80 0 0 0 0 0 0\ncpu0 5 0 5 40 0 0 0 0 3 0\ncpu1 5 0 5 30 10 0 0 0 0 0\nintr 1\nbtime 1700000000\n";
+        assert_eq!(core_ticks(stat), [(10.0, 50.0), (10.0, 50.0)]);
+        assert_eq!(boot_time(stat), Some(1700000000.0));
+    }
+}
@@ -0,0 +1,475 @@
+use crate::columns::TraceColumns;
+use crate::matrix::{
+    count_running_threads, host_prefix, process_label, state_value, thread_label, visit_threads,
+    STATE_NAMES,
+};
+use crate::model::{
+    micros_to_timestamp, timestamp_micros, Process, Snapshot, Thread, TimelineEvent,
+};
+use indexmap::{IndexMap, IndexSet};
+use std::collections::HashSet;
+
+/// Upper bound on resampled columns, guarding against a tiny interval over a long trace.
+const RESAMPLE_MAX_BUCKETS: usize = 100_000;
+
+/// How empty buckets are filled when resampling onto a uniform time grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GapFill {
+    /// Leave the column empty
+    Missing,
+    /// Repeat the previous snapshot
+    RepeatLast,
+}
+
+/// Bucket snapshots by wall-clock `interval_secs` so each column covers the same
+/// span of time. Each bucket keeps its latest snapshot; empty buckets are
+/// filled according to `fill`. Snapshots must already be sorted by timestamp.
+pub fn resample_snapshots(
+    snapshots: &[Snapshot],
+    interval_secs: f64,
+    fill: GapFill,
+) -> Vec<Snapshot> {
+    let timed: Vec<(f64, &Snapshot)> = snapshots
+        .iter()
+        .filter_map(|snap| Some((timestamp_micros(&snap.Timestamp)?, snap)))
+        .collect();
+    if timed.len() < snapshots.len() {
+        log::warn!(
+            "Resampling dropped {} snapshot(s) with unparseable timestamps",
+            snapshots.len() - timed.len()
+        );
+    }
+    let (Some(&(start, _)), Some(&(end, _))) = (timed.first(), timed.last()) else {
+        return Vec::new();
+    };
+    let interval = interval_secs * 1e6;
+    let buckets = ((end - start) / interval) as usize + 1;
+    if buckets > RESAMPLE_MAX_BUCKETS {
+        log::warn!(
+            "Resampling interval too small ({} buckets); showing raw snapshots",
+            buckets
+        );
+        return snapshots.to_vec();
+    }
+
+    let mut latest: Vec<Option<&Snapshot>> = vec![None; buckets];
+    // Events are kept from every snapshot in a bucket, not just the latest
+    let mut events: Vec<Vec<TimelineEvent>> = vec![Vec::new(); buckets];
+    for (micros, snap) in timed {
+        let bucket = ((micros - start) / interval) as usize;
+        latest[bucket] = Some(snap);
+        events[bucket].extend(snap.Events.iter().cloned());
+    }
+    let mut previous: Option<&Snapshot> = None;
+    latest
+        .into_iter()
+        .enumerate()
+        .map(|(bucket, snap)| {
+            let timestamp = micros_to_timestamp(start + bucket as f64 * interval);
+            let snap = snap.or(match fill {
+                GapFill::Missing => None,
+                GapFill::RepeatLast => previous,
+            });
+            previous = snap.or(previous);
+            match snap {
+                Some(snap) => Snapshot {
+                    Timestamp: timestamp,
+                    Events: std::mem::take(&mut events[bucket]),
+                    ..snap.clone()
+                },
+                None => Snapshot {
+                    Timestamp: timestamp,
+                    Events: std::mem::take(&mut events[bucket]),
+                    ..Default::default()
+                },
+            }
+        })
+        .collect()
+}
+
+/// Gap columns inserted by `resample_snapshots` carry only a timestamp.
+pub fn is_gap(snap: &Snapshot) -> bool {
+    snap.ProcessTree == Process::default()
+}
+
+/// Samples of a run as (seconds since its first snapshot, value).
+type RelativePoints = Vec<(f64, f64)>;
+
+/// CPU load (running threads per core, %) and mean GPU load of a run on
+/// relative time, so runs can be overlaid.
+pub fn relative_utilization(columns: &TraceColumns) -> (RelativePoints, RelativePoints) {
+    let start = columns.micros.iter().flatten().copied().reduce(f64::min);
+    let running = state_value(Some("R")) as usize;
+    let mut cpu = Vec::new();
+    let mut gpu = Vec::new();
+    for index in (0..columns.len()).filter(|&index| !columns.gaps[index]) {
+        let (Some(start), Some(micros)) = (start, columns.micros[index]) else {
+            continue;
+        };
+        let secs = (micros - start) / 1e6;
+        let running_threads = columns.state_counts[index][running];
+        let total_cores = columns.cores[index].max(1);
+        cpu.push((secs, running_threads as f64 / total_cores as f64 * 100.0));
+        let loads: Vec<f64> = columns
+            .gpus
+            .values()
+            .filter_map(|gpu| Some(gpu.at(index)?.load_percent))
+            .collect();
+        if !loads.is_empty() {
+            gpu.push((secs, loads.iter().sum::<f64>() / loads.len() as f64));
+        }
+    }
+    (cpu, gpu)
+}
+
+/// Load and memory use of one GPU over the whole trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuSummary {
+    pub label: String,
+    pub mean_load: f64,
+    pub max_load: f64,
+    pub mean_memory_mb: f64,
+    pub memory_total_mb: f64,
+}
+
+/// Headline numbers of a loaded trace, shown above the charts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceSummary {
+    pub duration_secs: Option<f64>,
+    pub snapshots: usize,
+    pub gpus: Vec<GpuSummary>,
+    /// Mean of the per-core loads reported by the collector
+    pub mean_core_load: Option<f64>,
+    /// Mean of running threads per core, as in the CPU trace
+    pub mean_running_per_core: Option<f64>,
+    pub processes: usize,
+    pub threads: usize,
+}
+
+impl TraceSummary {
+    pub fn new(snapshots: &[Snapshot]) -> Self {
+        let mean = |values: &[f64]| {
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+        let times: Vec<f64> = snapshots
+            .iter()
+            .filter_map(|snap| timestamp_micros(&snap.Timestamp))
+            .collect();
+        let duration_secs = match (
+            times.iter().copied().reduce(f64::min),
+            times.iter().copied().reduce(f64::max),
+        ) {
+            (Some(start), Some(end)) => Some((end - start) / 1e6),
+            _ => None,
+        };
+
+        let mut gpu_samples: IndexMap<String, (Vec<f64>, Vec<f64>, f64)> = IndexMap::new();
+        let mut core_loads = Vec::new();
+        let mut running_per_core = Vec::new();
+        let mut processes = HashSet::new();
+        let mut threads = HashSet::new();
+        for snap in snapshots.iter().filter(|snap| !is_gap(snap)) {
+            let prefix = host_prefix(snap);
+            for gpu in &snap.GPUStatus {
+                let samples = gpu_samples
+                    .entry(format!("{prefix}GPU #{} ({})", gpu.GPU_ID, gpu.Name))
+                    .or_default();
+                samples.0.push(gpu.Load_Percent);
+                samples.1.push(gpu.Memory_Used_MB);
+                samples.2 = samples.2.max(gpu.Memory_Total_MB);
+            }
+            if !snap.CPU_Core_Loads.is_empty() {
+                core_loads.push(
+                    snap.CPU_Core_Loads.iter().sum::<f64>() / snap.CPU_Core_Loads.len() as f64,
+                );
+            }
+            let running_threads = count_running_threads(&snap.ProcessTree);
+            running_per_core
+                .push(running_threads as f64 / snap.CPU_Cores_Total.max(1) as f64 * 100.0);
+            fn collect_ids(
+                proc: &Process,
+                prefix: &str,
+                processes: &mut HashSet<(String, u32)>,
+                threads: &mut HashSet<(String, u32)>,
+            ) {
+                processes.insert((prefix.to_string(), proc.PID));
+                for t in proc.Threads.iter().flatten() {
+                    threads.insert((prefix.to_string(), t.TID));
+                }
+                for child in proc.Children.iter().flatten() {
+                    collect_ids(child, prefix, processes, threads);
+                }
+            }
+            collect_ids(&snap.ProcessTree, &prefix, &mut processes, &mut threads);
+        }
+
+        TraceSummary {
+            duration_secs,
+            snapshots: snapshots.len(),
+            gpus: gpu_samples
+                .into_iter()
+                .map(|(label, (loads, memory, memory_total_mb))| GpuSummary {
+                    label,
+                    mean_load: mean(&loads).unwrap_or_default(),
+                    max_load: loads.iter().copied().fold(0.0, f64::max),
+                    mean_memory_mb: mean(&memory).unwrap_or_default(),
+                    memory_total_mb,
+                })
+                .collect(),
+            mean_core_load: mean(&core_loads),
+            mean_running_per_core: mean(&running_per_core),
+            processes: processes.len(),
+            threads: threads.len(),
+        }
+    }
+}
+
+/// Minimum, mean and maximum of one chart series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeriesStats {
+    pub label: String,
+    pub unit: &'static str,
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+/// Statistics of a time window brushed on the CPU and GPU charts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeStats {
+    pub series: Vec<SeriesStats>,
+    /// Thread samples in each state (`STATE_NAMES` index), most common first
+    pub states: Vec<(usize, usize)>,
+    pub thread_samples: usize,
+}
+
+impl RangeStats {
+    pub fn new(snapshots: &[Snapshot]) -> Self {
+        let mut samples: IndexMap<String, (&'static str, Vec<f64>)> = IndexMap::new();
+        let mut push = |label: String, unit: &'static str, value: f64| {
+            samples
+                .entry(label)
+                .or_insert((unit, Vec::new()))
+                .1
+                .push(value);
+        };
+        let mut state_counts = [0; STATE_NAMES.len()];
+        for snap in snapshots.iter().filter(|snap| !is_gap(snap)) {
+            let prefix = host_prefix(snap);
+            let running_threads = count_running_threads(&snap.ProcessTree);
+            push(
+                "CPU Utilization".to_string(),
+                "%",
+                running_threads as f64 / snap.CPU_Cores_Total.max(1) as f64 * 100.0,
+            );
+            if !snap.CPU_Core_Loads.is_empty() {
+                push(
+                    "Mean core load".to_string(),
+                    "%",
+                    snap.CPU_Core_Loads.iter().sum::<f64>() / snap.CPU_Core_Loads.len() as f64,
+                );
+            }
+            for gpu in &snap.GPUStatus {
+                let label = format!("{prefix}GPU #{} ({})", gpu.GPU_ID, gpu.Name);
+                push(format!("{label} load"), "%", gpu.Load_Percent);
+                push(format!("{label} memory"), " MB", gpu.Memory_Used_MB);
+            }
+            visit_threads(&snap.ProcessTree, &mut |t| {
+                state_counts[state_value(t.State.as_deref()) as usize] += 1;
+            });
+        }
+
+        let mut states: Vec<(usize, usize)> = state_counts
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        states.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        RangeStats {
+            series: samples
+                .into_iter()
+                .map(|(label, (unit, values))| SeriesStats {
+                    label,
+                    unit,
+                    min: values.iter().copied().fold(f64::INFINITY, f64::min),
+                    mean: values.iter().sum::<f64>() / values.len() as f64,
+                    max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                })
+                .collect(),
+            states,
+            thread_samples: state_counts.iter().sum(),
+        }
+    }
+}
+
+/// A process in the busiest processes table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusyProcess {
+    /// Heatmap row label
+    pub label: String,
+    pub name: String,
+    pub pid: u32,
+    /// Fraction of the snapshots with at least one of its threads running
+    pub running_fraction: f64,
+    pub mean_cpu_percent: Option<f64>,
+}
+
+/// Column the busiest processes table is ranked by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BusySort {
+    Running,
+    Cpu,
+    Name,
+}
+
+/// Rows of the busiest processes table shown at once.
+pub const BUSY_PROCESS_ROWS: usize = 20;
+
+/// Every process in the snapshots, busiest (by running fraction) first.
+pub fn busiest_processes(snapshots: &[Snapshot]) -> Vec<BusyProcess> {
+    #[derive(Default)]
+    struct Tally {
+        name: String,
+        pid: u32,
+        running: usize,
+        cpu: Vec<f64>,
+    }
+    fn tally(proc: &Process, depth: usize, prefix: &str, tallies: &mut IndexMap<String, Tally>) {
+        let entry = tallies
+            .entry(process_label(proc, depth, prefix))
+            .or_insert_with(|| Tally {
+                name: proc.Name.clone(),
+                pid: proc.PID,
+                ..Tally::default()
+            });
+        let running = proc
+            .Threads
+            .iter()
+            .flatten()
+            .any(|t| t.State.as_deref().is_some_and(|s| s.starts_with('R')));
+        if running {
+            entry.running += 1;
+        }
+        entry.cpu.extend(proc.CPU_Percent);
+        for child in proc.Children.iter().flatten() {
+            tally(child, depth + 1, prefix, tallies);
+        }
+    }
+
+    let mut tallies = IndexMap::new();
+    let mut samples = 0;
+    for snap in snapshots.iter().filter(|snap| !is_gap(snap)) {
+        samples += 1;
+        tally(&snap.ProcessTree, 0, &host_prefix(snap), &mut tallies);
+    }
+    let mut busy: Vec<BusyProcess> = tallies
+        .into_iter()
+        .map(|(label, tally)| BusyProcess {
+            label,
+            name: tally.name,
+            pid: tally.pid,
+            running_fraction: tally.running as f64 / samples.max(1) as f64,
+            mean_cpu_percent: (!tally.cpu.is_empty())
+                .then(|| tally.cpu.iter().sum::<f64>() / tally.cpu.len() as f64),
+        })
+        .collect();
+    busy.sort_by(|a, b| b.running_fraction.total_cmp(&a.running_fraction));
+    busy
+}
+
+/// Human-readable length of a trace, e.g. "2h 05m 10s".
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s:02}s"),
+        (h, m, s) => format!("{h}h {m:02}m {s:02}s"),
+    }
+}
+
+/// How a heatmap row differs between two snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowChange {
+    Appeared,
+    Disappeared,
+    /// Thread state letter before and after
+    StateChanged(char, char),
+    /// Unchanged, shown for the changes below it
+    Context,
+}
+
+/// Rows of the processes and threads that appeared, disappeared or changed
+/// state from `before` to `after`, in tree order with their unchanged
+/// ancestors. Processes are matched by PID and threads by TID.
+pub fn diff_process_trees(
+    before: Option<&Process>,
+    after: Option<&Process>,
+    depth: usize,
+    prefix: &str,
+    rows: &mut Vec<(String, RowChange)>,
+) {
+    let Some(proc) = after.or(before) else {
+        return;
+    };
+    let change = match (before, after) {
+        (None, Some(_)) => RowChange::Appeared,
+        (Some(_), None) => RowChange::Disappeared,
+        _ => RowChange::Context,
+    };
+    let start = rows.len();
+    rows.push((process_label(proc, depth, prefix), change.clone()));
+
+    let state = |t: &Thread| t.State.as_deref().and_then(|s| s.chars().next());
+    fn threads(proc: Option<&Process>) -> IndexMap<u32, &Thread> {
+        proc.and_then(|p| p.Threads.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|t| (t.TID, t))
+            .collect()
+    }
+    let (threads_before, threads_after) = (threads(before), threads(after));
+    let tids: IndexSet<u32> = threads_after
+        .keys()
+        .chain(threads_before.keys())
+        .copied()
+        .collect();
+    for tid in tids {
+        let row = match (threads_before.get(&tid), threads_after.get(&tid)) {
+            (None, Some(t)) => (thread_label(t, depth, prefix), RowChange::Appeared),
+            (Some(t), None) => (thread_label(t, depth, prefix), RowChange::Disappeared),
+            (Some(old), Some(new)) if state(old) != state(new) => (
+                thread_label(new, depth, prefix),
+                RowChange::StateChanged(state(old).unwrap_or('-'), state(new).unwrap_or('-')),
+            ),
+            _ => continue,
+        };
+        rows.push(row);
+    }
+
+    fn children(proc: Option<&Process>) -> IndexMap<u32, &Process> {
+        proc.and_then(|p| p.Children.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|child| (child.PID, child))
+            .collect()
+    }
+    let (children_before, children_after) = (children(before), children(after));
+    let pids: IndexSet<u32> = children_after
+        .keys()
+        .chain(children_before.keys())
+        .copied()
+        .collect();
+    for pid in pids {
+        diff_process_trees(
+            children_before.get(&pid).copied(),
+            children_after.get(&pid).copied(),
+            depth + 1,
+            prefix,
+            rows,
+        );
+    }
+
+    if change == RowChange::Context && rows.len() == start + 1 {
+        rows.pop();
+    }
+}
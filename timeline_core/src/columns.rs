@@ -0,0 +1,741 @@
+//! Snapshots compacted into flat arrays, one per metric, built once per trace.
+//! The chart builders read these rather than walking the nested snapshots on
+//! every redraw.
+
+use crate::analysis::is_gap;
+use crate::matrix::{host_prefix, state_value, HostId, RowId, RowIds, STATE_NAMES};
+use crate::model::{timestamp_micros, GPUStatus, Process, Snapshot, TimelineEvent};
+use crate::series::counter_rates;
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::RangeInclusive;
+
+/// State byte of a thread missing from a snapshot.
+pub const NO_STATE: u8 = u8::MAX;
+
+/// A thread row's state (`state_value`, or `NO_STATE`) in each snapshot from
+/// the first it appears in to the last.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowStates {
+    first: usize,
+    states: Vec<u8>,
+}
+
+impl RowStates {
+    fn set(&mut self, index: usize, state: u8) {
+        if self.states.is_empty() {
+            self.first = index;
+        }
+        let offset = index - self.first;
+        if offset >= self.states.len() {
+            self.states.resize(offset + 1, NO_STATE);
+        }
+        self.states[offset] = state;
+    }
+
+    /// The states within `range`; empty when the thread isn't in it.
+    pub fn in_range(&self, range: RangeInclusive<usize>) -> &[u8] {
+        let start = range
+            .start()
+            .saturating_sub(self.first)
+            .min(self.states.len());
+        let end = (range.end() + 1)
+            .saturating_sub(self.first)
+            .clamp(start, self.states.len());
+        &self.states[start..end]
+    }
+
+    /// The states of the snapshots within `range` that have the thread, with
+    /// their indices.
+    pub fn samples(&self, range: RangeInclusive<usize>) -> impl Iterator<Item = (usize, u8)> + '_ {
+        let start = (*range.start()).max(self.first);
+        self.in_range(range)
+            .iter()
+            .enumerate()
+            .filter(|&(_, &state)| state != NO_STATE)
+            .map(move |(offset, &state)| (start + offset, state))
+    }
+}
+
+/// Value type of a `Column`: f32 for readings, f64 for the cumulative
+/// counters whose differences f32 would round away.
+pub trait Sample: Copy {
+    /// Stands for a snapshot without a sample
+    const MISSING: Self;
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+}
+
+impl Sample for f32 {
+    const MISSING: Self = f32::NAN;
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl Sample for f64 {
+    const MISSING: Self = f64::NAN;
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+/// One metric in each snapshot from the first with a sample of it, NaN where
+/// a snapshot has none.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Column<T = f32> {
+    first: usize,
+    values: Vec<T>,
+}
+
+impl<T: Sample> Column<T> {
+    /// Record the sample of the snapshot at `index`. A row listed twice in a
+    /// snapshot keeps its first sample.
+    fn set(&mut self, index: usize, value: Option<f64>) {
+        let Some(value) = value else {
+            return;
+        };
+        if self.values.is_empty() {
+            self.first = index;
+        }
+        let offset = index - self.first;
+        if offset >= self.values.len() {
+            self.values.resize(offset + 1, T::MISSING);
+        }
+        if self.values[offset].to_f64().is_nan() {
+            self.values[offset] = T::from_f64(value);
+        }
+    }
+
+    /// Whether no snapshot has a sample.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn at(&self, index: usize) -> Option<f64> {
+        let value = self.values.get(index.checked_sub(self.first)?)?.to_f64();
+        (!value.is_nan()).then_some(value)
+    }
+
+    /// The samples within `range` with their snapshot indices.
+    pub fn points(&self, range: RangeInclusive<usize>) -> Vec<(usize, f64)> {
+        let start = (*range.start()).max(self.first);
+        let end = (range.end() + 1).min(self.first + self.values.len());
+        (start..end)
+            .filter_map(|index| Some((index, self.at(index)?)))
+            .collect()
+    }
+}
+/// The readings of one GPU in one snapshot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuSample {
+    pub load_percent: f64,
+    pub memory_used_mb: f64,
+    pub memory_total_mb: f64,
+    pub temperature_c: f64,
+    pub power_draw_w: Option<f64>,
+    pub power_limit_w: Option<f64>,
+    pub sm_clock_mhz: Option<f64>,
+    pub memory_clock_mhz: Option<f64>,
+}
+
+/// One GPU's readings in each snapshot from the first it appears in. Readings
+/// a snapshot lacks are NaN, and a NaN load marks the GPU missing from it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuColumns {
+    /// Id and model as first reported
+    pub id: u32,
+    pub name: String,
+    first: usize,
+    load_percent: Vec<f32>,
+    memory_used_mb: Vec<f32>,
+    memory_total_mb: Vec<f32>,
+    temperature_c: Vec<f32>,
+    power_draw_w: Vec<f32>,
+    power_limit_w: Vec<f32>,
+    sm_clock_mhz: Vec<f32>,
+    memory_clock_mhz: Vec<f32>,
+}
+
+impl GpuColumns {
+    fn push(&mut self, index: usize, gpu: &GPUStatus) {
+        if self.load_percent.is_empty() {
+            self.first = index;
+            self.id = gpu.GPU_ID;
+            self.name = gpu.Name.clone();
+        }
+        let offset = index - self.first;
+        // A GPU listed twice in a snapshot keeps its first readings
+        if offset < self.load_percent.len() {
+            return;
+        }
+        let optional = |value: Option<f64>| value.map_or(f32::NAN, |value| value as f32);
+        for (column, value) in [
+            (&mut self.load_percent, gpu.Load_Percent as f32),
+            (&mut self.memory_used_mb, gpu.Memory_Used_MB as f32),
+            (&mut self.memory_total_mb, gpu.Memory_Total_MB as f32),
+            (&mut self.temperature_c, gpu.Temperature_C as f32),
+            (&mut self.power_draw_w, optional(gpu.Power_Draw_W)),
+            (&mut self.power_limit_w, optional(gpu.Power_Limit_W)),
+            (&mut self.sm_clock_mhz, optional(gpu.SM_Clock_MHz)),
+            (&mut self.memory_clock_mhz, optional(gpu.Memory_Clock_MHz)),
+        ] {
+            // Snapshots since the last one with this GPU lack it
+            column.resize(offset, f32::NAN);
+            column.push(value);
+        }
+    }
+
+    /// The readings in the snapshot at `index`, None when the GPU is missing.
+    pub fn at(&self, index: usize) -> Option<GpuSample> {
+        let offset = index.checked_sub(self.first)?;
+        let load_percent = *self.load_percent.get(offset)?;
+        if load_percent.is_nan() {
+            return None;
+        }
+        let optional = |column: &[f32]| Some(column[offset] as f64).filter(|v| !v.is_nan());
+        Some(GpuSample {
+            load_percent: load_percent as f64,
+            memory_used_mb: self.memory_used_mb[offset] as f64,
+            memory_total_mb: self.memory_total_mb[offset] as f64,
+            temperature_c: self.temperature_c[offset] as f64,
+            power_draw_w: optional(&self.power_draw_w),
+            power_limit_w: optional(&self.power_limit_w),
+            sm_clock_mhz: optional(&self.sm_clock_mhz),
+            memory_clock_mhz: optional(&self.memory_clock_mhz),
+        })
+    }
+}
+
+/// A process row's details as first seen, and its metrics in each snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessColumns {
+    pub name: String,
+    pub command: Option<String>,
+    pub container: Option<String>,
+    /// Row of the process it was first seen under, None at the tree root
+    pub parent: Option<RowId>,
+    /// Its running thread count row, once it has had threads
+    pub running_row: Option<RowId>,
+    /// Threads, sampled in every snapshot with the process
+    pub threads: Column,
+    /// Threads in the running state
+    pub running: Column,
+    pub cpu_percent: Column,
+    pub memory_rss_mb: Column,
+    pub fd_count: Column,
+    /// Held on the GPUs, summed over them
+    pub gpu_memory_mb: Column,
+    pub io_read_bytes: Column<f64>,
+    pub io_write_bytes: Column<f64>,
+}
+
+/// A thread row's name as first seen, and its state and counters in each
+/// snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThreadColumns {
+    pub name: String,
+    /// Row of the process it belongs to
+    pub process: RowId,
+    pub states: RowStates,
+    pub cpu_time_ms: Column<f64>,
+    pub voluntary_ctxt_switches: Column<f64>,
+    pub nonvoluntary_ctxt_switches: Column<f64>,
+}
+
+impl ThreadColumns {
+    /// Percent of one core used over the interval before each sample within
+    /// `range`, from the cumulative CPU time.
+    pub fn utilization(
+        &self,
+        micros: &[Option<f64>],
+        range: RangeInclusive<usize>,
+    ) -> Vec<(usize, f64)> {
+        let column = &self.cpu_time_ms;
+        let mut points = column.points(range.clone());
+        // The first sample in the range is measured from the one before it
+        let before = (column.first..*range.start())
+            .rev()
+            .find_map(|index| Some((index, column.at(index)?)));
+        points.splice(0..0, before);
+        counter_rates(&points, micros)
+            .into_iter()
+            .map(|(index, ms_per_sec)| (index, (ms_per_sec / 10.0).clamp(0.0, 100.0)))
+            .collect()
+    }
+}
+
+/// Readings of the whole host in each snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemColumns {
+    pub load_avg_1: Column,
+    pub load_avg_5: Column,
+    pub load_avg_15: Column,
+    pub memory_used_mb: Column,
+    pub memory_cached_mb: Column,
+    pub memory_total_mb: Column,
+    pub swap_used_mb: Column,
+    pub swap_total_mb: Column,
+}
+
+/// Snapshot metrics the charts plot, a value per snapshot.
+#[derive(Debug, Default, PartialEq)]
+pub struct TraceColumns {
+    /// As written in the trace, for axis labels and tooltips
+    pub timestamps: Vec<String>,
+    /// Timestamp in microseconds since the epoch, None where it doesn't parse
+    pub micros: Vec<Option<f64>>,
+    /// Snapshots marking a gap in the recording
+    pub gaps: Vec<bool>,
+    pub cores: Vec<u32>,
+    /// Threads in each state, indexed like `STATE_NAMES`
+    pub state_counts: Vec<[u32; STATE_NAMES.len()]>,
+    pub system: SystemColumns,
+    /// Load of each core in percent, by host prefix and core number
+    pub core_loads: BTreeMap<(String, usize), Column>,
+    /// Custom metrics by name, then by series label (host prefix and name)
+    pub custom: BTreeMap<String, IndexMap<String, Column>>,
+    /// Each event with the index of the snapshot it was logged with
+    pub events: Vec<(usize, TimelineEvent)>,
+    /// By heatmap row label, in order of first appearance
+    pub gpus: IndexMap<String, GpuColumns>,
+    /// Host of each snapshot, to look its rows up by
+    pub hosts: Vec<HostId>,
+    /// Every process, thread and running thread count row of the trace
+    pub rows: RowIds,
+    /// By process row
+    pub processes: HashMap<RowId, ProcessColumns>,
+    /// By thread row
+    pub threads: HashMap<RowId, ThreadColumns>,
+}
+
+impl TraceColumns {
+    pub fn new(snapshots: &[Snapshot]) -> Self {
+        let mut columns = TraceColumns::default();
+        columns.extend(snapshots);
+        columns
+    }
+
+    /// Compacts snapshots following those already in the columns, as they
+    /// arrive in live mode.
+    pub fn extend(&mut self, snapshots: &[Snapshot]) {
+        for snap in snapshots {
+            self.push(snap);
+        }
+    }
+
+    /// Logs events received after the newest snapshot with it.
+    pub fn extend_events(&mut self, events: &[TimelineEvent]) {
+        let Some(index) = self.len().checked_sub(1) else {
+            return;
+        };
+        self.events
+            .extend(events.iter().map(|event| (index, event.clone())));
+    }
+
+    /// Snapshots compacted.
+    pub fn len(&self) -> usize {
+        self.micros.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.micros.is_empty()
+    }
+
+    fn push(&mut self, snap: &Snapshot) {
+        let index = self.len();
+        self.timestamps.push(snap.Timestamp.clone());
+        self.micros.push(timestamp_micros(&snap.Timestamp));
+        self.gaps.push(is_gap(snap));
+        self.cores.push(snap.CPU_Cores_Total);
+        let prefix = host_prefix(snap);
+        let host = self.rows.host(&prefix);
+        self.hosts.push(host);
+
+        let system = &mut self.system;
+        for (column, value) in [
+            (&mut system.load_avg_1, snap.LoadAvg_1),
+            (&mut system.load_avg_5, snap.LoadAvg_5),
+            (&mut system.load_avg_15, snap.LoadAvg_15),
+            (&mut system.memory_used_mb, snap.Memory_Used_MB),
+            (&mut system.memory_cached_mb, snap.Memory_Cached_MB),
+            (&mut system.memory_total_mb, snap.Memory_Total_MB),
+            (&mut system.swap_used_mb, snap.Swap_Used_MB),
+            (&mut system.swap_total_mb, snap.Swap_Total_MB),
+        ] {
+            column.set(index, value);
+        }
+        for (core, &load) in snap.CPU_Core_Loads.iter().enumerate() {
+            self.core_loads
+                .entry((prefix.clone(), core))
+                .or_default()
+                .set(index, Some(load));
+        }
+        for (name, &value) in &snap.Custom {
+            self.custom
+                .entry(name.clone())
+                .or_default()
+                .entry(format!("{prefix}{name}"))
+                .or_default()
+                .set(index, Some(value));
+        }
+        self.events
+            .extend(snap.Events.iter().map(|event| (index, event.clone())));
+
+        let mut gpu_memory: HashMap<u32, f64> = HashMap::new();
+        for gpu in &snap.GPUStatus {
+            self.gpus
+                .entry(format!("{prefix}GPU #{}", gpu.GPU_ID))
+                .or_default()
+                .push(index, gpu);
+            for gpu_proc in &gpu.GPU_Processes {
+                *gpu_memory.entry(gpu_proc.PID).or_default() += gpu_proc.Memory_MB;
+            }
+        }
+
+        let mut counts = [0; STATE_NAMES.len()];
+        if !is_gap(snap) {
+            let tree = TreeSample {
+                index,
+                host,
+                gpu_memory: &gpu_memory,
+            };
+            self.add_process(&snap.ProcessTree, 0, None, &tree, &mut counts);
+        }
+        self.state_counts.push(counts);
+    }
+
+    fn add_process(
+        &mut self,
+        proc: &Process,
+        depth: usize,
+        parent: Option<RowId>,
+        tree: &TreeSample,
+        counts: &mut [u32; STATE_NAMES.len()],
+    ) {
+        let index = tree.index;
+        let row = self.rows.process(proc, depth, tree.host);
+        let threads = proc.Threads.as_deref().unwrap_or_default();
+        let running_row =
+            (!threads.is_empty()).then(|| self.rows.running_threads(proc, depth, tree.host));
+        let running = threads
+            .iter()
+            .filter(|t| t.State.as_deref().is_some_and(|s| s.starts_with('R')))
+            .count();
+
+        let columns = self.processes.entry(row).or_insert_with(|| ProcessColumns {
+            name: proc.Name.clone(),
+            command: proc.CMD.clone(),
+            container: proc.Container.clone(),
+            parent,
+            ..ProcessColumns::default()
+        });
+        columns.running_row = columns.running_row.or(running_row);
+        columns.threads.set(index, Some(threads.len() as f64));
+        columns.running.set(index, Some(running as f64));
+        columns.cpu_percent.set(index, proc.CPU_Percent);
+        columns.memory_rss_mb.set(index, proc.Memory_RSS_MB);
+        columns.fd_count.set(index, proc.FD_Count);
+        columns
+            .gpu_memory_mb
+            .set(index, tree.gpu_memory.get(&proc.PID).copied());
+        columns.io_read_bytes.set(index, proc.IO_Read_Bytes);
+        columns.io_write_bytes.set(index, proc.IO_Write_Bytes);
+
+        for t in threads {
+            let thread_row = self.rows.thread(t, depth, tree.host);
+            let state = state_value(t.State.as_deref());
+            counts[state as usize] += 1;
+            let thread = self
+                .threads
+                .entry(thread_row)
+                .or_insert_with(|| ThreadColumns {
+                    name: t.Name.clone().unwrap_or_default(),
+                    process: row,
+                    ..ThreadColumns::default()
+                });
+            thread.states.set(index, state);
+            thread.cpu_time_ms.set(index, t.CPU_Time_Ms);
+            thread
+                .voluntary_ctxt_switches
+                .set(index, t.Voluntary_Ctxt_Switches);
+            thread
+                .nonvoluntary_ctxt_switches
+                .set(index, t.Nonvoluntary_Ctxt_Switches);
+        }
+        for child in proc.Children.iter().flatten() {
+            self.add_process(child, depth + 1, Some(row), tree, counts);
+        }
+    }
+
+    /// Samples of a process metric within `range`, by process row.
+    pub fn process_series<T: Sample>(
+        &self,
+        range: RangeInclusive<usize>,
+        metric: impl Fn(&ProcessColumns) -> &Column<T>,
+    ) -> BTreeMap<RowId, Vec<(usize, f64)>> {
+        self.processes
+            .iter()
+            .map(|(&row, proc)| (row, metric(proc).points(range.clone())))
+            .filter(|(_, points)| !points.is_empty())
+            .collect()
+    }
+
+    /// Samples of a thread metric within `range`, by thread row.
+    pub fn thread_series<T: Sample>(
+        &self,
+        range: RangeInclusive<usize>,
+        metric: impl Fn(&ThreadColumns) -> &Column<T>,
+    ) -> BTreeMap<RowId, Vec<(usize, f64)>> {
+        self.threads
+            .iter()
+            .map(|(&row, thread)| (row, metric(thread).points(range.clone())))
+            .filter(|(_, points)| !points.is_empty())
+            .collect()
+    }
+
+    /// Snapshots within `range` with each thread row running, and with any
+    /// thread at or below each process row running, summed over the threads.
+    /// Process rows recorded in the range are included even when idle.
+    pub fn running_samples(&self, range: RangeInclusive<usize>) -> HashMap<RowId, usize> {
+        let mut totals = HashMap::new();
+        let running = state_value(Some("R"));
+        for (&row, thread) in &self.threads {
+            let samples = thread
+                .states
+                .in_range(range.clone())
+                .iter()
+                .filter(|&&state| state == running)
+                .count();
+            if samples > 0 {
+                totals.insert(row, samples);
+            }
+        }
+        for (&row, proc) in &self.processes {
+            if proc.threads.points(range.clone()).is_empty() {
+                continue;
+            }
+            totals.entry(row).or_insert(0);
+            let samples: f64 = proc
+                .running
+                .points(range.clone())
+                .iter()
+                .map(|&(_, running)| running)
+                .sum();
+            // Threads count towards every process above them
+            let mut owner = Some(row);
+            while let Some(process) = owner {
+                *totals.entry(process).or_insert(0) += samples as usize;
+                owner = self.processes.get(&process).and_then(|proc| proc.parent);
+            }
+        }
+        totals
+    }
+}
+
+/// What `TraceColumns::add_process` records every process of a snapshot with.
+struct TreeSample<'a> {
+    index: usize,
+    host: HostId,
+    /// GPU memory held by each PID, summed over the GPUs
+    gpu_memory: &'a HashMap<u32, f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::RowKind;
+    use crate::model::{GPUProcess, Thread};
+
+    fn snapshot(gpus: Vec<GPUStatus>, state: Option<&str>) -> Snapshot {
+        Snapshot {
+            ProcessTree: Process {
+                PID: 1,
+                Name: "init".to_string(),
+                Threads: state.map(|state| {
+                    vec![Thread {
+                        TID: 2,
+                        State: Some(state.to_string()),
+                        ..Thread::default()
+                    }]
+                }),
+                ..Process::default()
+            },
+            GPUStatus: gpus,
+            ..Snapshot::default()
+        }
+    }
+
+    fn thread_row(columns: &TraceColumns) -> RowId {
+        let row = columns
+            .rows
+            .rows()
+            .find(|&row| columns.rows.kind(row) == RowKind::Thread);
+        row.unwrap()
+    }
+
+    #[test]
+    fn columns_skip_snapshots_without_a_sample() {
+        let mut column = Column::<f32>::default();
+        column.set(2, Some(1.0));
+        column.set(3, None);
+        column.set(4, Some(3.0));
+        // A row listed twice keeps its first sample
+        column.set(4, Some(5.0));
+        assert_eq!(column.at(1), None);
+        assert_eq!(column.at(3), None);
+        assert_eq!(column.points(0..=9), [(2, 1.0), (4, 3.0)]);
+        assert_eq!(column.points(3..=4), [(4, 3.0)]);
+        assert!(column.points(5..=9).is_empty());
+        assert!(Column::<f64>::default().points(0..=9).is_empty());
+    }
+
+    #[test]
+    fn gpus_missing_from_a_snapshot_have_no_sample() {
+        let gpu = |load| GPUStatus {
+            Load_Percent: load,
+            Power_Draw_W: Some(100.0),
+            ..GPUStatus::default()
+        };
+        let snapshots = [
+            snapshot(Vec::new(), None),
+            snapshot(vec![gpu(10.0)], None),
+            snapshot(Vec::new(), None),
+            snapshot(vec![gpu(30.0)], None),
+        ];
+        let columns = TraceColumns::new(&snapshots);
+        let gpu = &columns.gpus["GPU #0"];
+        let loads: Vec<_> = (0..5)
+            .map(|i| gpu.at(i).map(|sample| sample.load_percent))
+            .collect();
+        assert_eq!(loads, [None, Some(10.0), None, Some(30.0), None]);
+        assert_eq!(
+            gpu.at(1).and_then(|sample| sample.power_draw_w),
+            Some(100.0)
+        );
+        assert_eq!(gpu.at(1).and_then(|sample| sample.sm_clock_mhz), None);
+    }
+
+    #[test]
+    fn thread_states_span_the_thread_lifetime() {
+        let snapshots = [
+            snapshot(Vec::new(), None),
+            snapshot(Vec::new(), Some("R")),
+            snapshot(Vec::new(), None),
+            snapshot(Vec::new(), Some("S")),
+        ];
+        let columns = TraceColumns::new(&snapshots);
+        let states = &columns.threads[&thread_row(&columns)].states;
+        assert_eq!(states.in_range(0..=3), [1, NO_STATE, 2]);
+        assert_eq!(states.in_range(2..=2), [NO_STATE]);
+        assert!(states.in_range(0..=0).is_empty());
+        assert_eq!(states.samples(0..=3).collect::<Vec<_>>(), [(1, 1), (3, 2)]);
+        assert_eq!(columns.state_counts[1][1], 1);
+        assert_eq!(columns.state_counts[3][2], 1);
+        assert_eq!(columns.state_counts[2], [0; STATE_NAMES.len()]);
+    }
+
+    #[test]
+    fn sums_process_gpu_memory_over_gpus() {
+        let gpu = |id, memory_mb| GPUStatus {
+            GPU_ID: id,
+            GPU_Processes: vec![GPUProcess {
+                PID: 1,
+                Memory_MB: memory_mb,
+            }],
+            ..GPUStatus::default()
+        };
+        let snapshots = [
+            snapshot(vec![gpu(0, 100.0), gpu(1, 28.0)], None),
+            // Without GPU processes the snapshot has no sample
+            snapshot(Vec::new(), None),
+        ];
+        let columns = TraceColumns::new(&snapshots);
+        let series = columns.process_series(0..=1, |proc| &proc.gpu_memory_mb);
+        assert_eq!(series.len(), 1);
+        let process = columns.rows.find_process(&snapshots[0].ProcessTree, 0, 0);
+        assert_eq!(series[&process.unwrap()], [(0, 128.0)]);
+    }
+
+    #[test]
+    fn running_threads_count_towards_their_processes() {
+        let mut snapshots = [
+            snapshot(Vec::new(), Some("R")),
+            snapshot(Vec::new(), Some("S")),
+            snapshot(Vec::new(), Some("R")),
+        ];
+        for snap in &mut snapshots {
+            let child = Process {
+                PID: 3,
+                Name: "child".to_string(),
+                Threads: Some(vec![Thread {
+                    TID: 4,
+                    State: Some("R".to_string()),
+                    ..Thread::default()
+                }]),
+                ..Process::default()
+            };
+            snap.ProcessTree.Children = Some(vec![child]);
+        }
+        let columns = TraceColumns::new(&snapshots);
+        let init = columns.rows.find_label("init (PID 1)").unwrap();
+        let child = columns.rows.find_label("    └─ child (PID 3)").unwrap();
+        assert_eq!(columns.processes[&child].parent, Some(init));
+        let totals = columns.running_samples(1..=2);
+        assert_eq!(totals[&child], 2);
+        assert_eq!(totals[&init], 3);
+        assert_eq!(totals[&thread_row(&columns)], 1);
+    }
+
+    #[test]
+    fn thread_utilization_is_measured_from_the_previous_sample() {
+        let snapshots: Vec<Snapshot> = [0.0, 500.0, 1500.0]
+            .into_iter()
+            .enumerate()
+            .map(|(second, cpu_ms)| {
+                let mut snap = snapshot(Vec::new(), Some("R"));
+                snap.Timestamp = format!("2024-05-01T12:00:0{second}");
+                if let Some(threads) = &mut snap.ProcessTree.Threads {
+                    threads[0].CPU_Time_Ms = Some(cpu_ms);
+                }
+                snap
+            })
+            .collect();
+        let columns = TraceColumns::new(&snapshots);
+        let thread = &columns.threads[&thread_row(&columns)];
+        assert_eq!(
+            thread.utilization(&columns.micros, 0..=2),
+            [(1, 50.0), (2, 100.0)]
+        );
+        assert_eq!(thread.utilization(&columns.micros, 2..=2), [(2, 100.0)]);
+    }
+
+    #[test]
+    fn extending_matches_compacting_at_once() {
+        let mut snapshots = vec![
+            snapshot(Vec::new(), Some("S")),
+            snapshot(Vec::new(), Some("R")),
+            snapshot(Vec::new(), None),
+        ];
+        snapshots[1].LoadAvg_1 = Some(0.5);
+        let event = TimelineEvent {
+            Name: "checkpoint".to_string(),
+            Timestamp: String::new(),
+        };
+        let mut columns = TraceColumns::new(&snapshots[..1]);
+        columns.extend(&snapshots[1..]);
+        columns.extend_events(std::slice::from_ref(&event));
+        snapshots[2].Events.push(event);
+        assert_eq!(columns, TraceColumns::new(&snapshots));
+    }
+}
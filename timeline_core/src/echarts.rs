@@ -0,0 +1,694 @@
+//! The part of the ECharts option tree the viewer sets, as serde types so
+//! chart options are built in Rust and serialized once. Functions such as
+//! tooltip formatters can't be serialized and are attached by the page.
+
+use serde::{Serialize, Serializer};
+
+/// Points of a line series on the time axis: (ms since the epoch, value),
+/// with None breaking the line.
+pub type TimePoints = Vec<(f64, Option<f64>)>;
+
+/// Root of a chart's option, with the series data of type `D`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartOption<D = TimePoints> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<Title>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tooltip: Option<Tooltip>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legend: Option<Legend>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid: Option<Grid>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub x_axis: Vec<Axis>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub y_axis: Vec<Axis>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub data_zoom: Vec<DataZoom>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visual_map: Option<VisualMap>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brush: Option<Brush>,
+    pub series: Vec<Series<D>>,
+}
+
+/// Parts of an option merged into a drawn chart, leaving the rest of it as
+/// it is; e.g. the zoom, markers and buttons linking it to the others.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionUpdate {
+    /// Shows times in UTC rather than the browser's time zone
+    #[serde(rename = "useUTC", skip_serializing_if = "Option::is_none")]
+    pub use_utc: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_axis: Option<AxisUpdate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub data_zoom: Vec<DataZoom>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toolbox: Option<Toolbox>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brush: Option<Brush>,
+    /// Merged into the drawn series with the same `id`, or added
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub series: Vec<MarkLineSeries>,
+}
+
+/// A distance in pixels, or a string such as `"10%"` or `"center"`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Length {
+    Px(f64),
+    Percent(f64),
+    Keyword(&'static str),
+}
+
+impl Serialize for Length {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Length::Px(px) => serializer.serialize_f64(*px),
+            Length::Percent(percent) => serializer.collect_str(&format_args!("{percent}%")),
+            Length::Keyword(keyword) => serializer.serialize_str(keyword),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Title {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtext: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtext_style: Option<TextStyle>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TextStyle {
+    pub color: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    Item,
+    Axis,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Tooltip {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<Trigger>,
+}
+
+impl Tooltip {
+    pub fn axis() -> Self {
+        Tooltip {
+            trigger: Some(Trigger::Axis),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Legend {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<Length>,
+    /// Scrolls rather than wraps when there are many series
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<&'static str>,
+}
+
+impl Legend {
+    pub fn at(top: f64) -> Self {
+        Legend {
+            top: Some(Length::Px(top)),
+            kind: None,
+        }
+    }
+
+    pub fn scrolling(top: f64) -> Self {
+        Legend {
+            kind: Some("scroll"),
+            ..Legend::at(top)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Grid {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<Length>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<Length>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Length>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<Length>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AxisType {
+    #[default]
+    Value,
+    Category,
+    Time,
+}
+
+/// Whether an optional part of a component is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Show {
+    pub show: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Axis {
+    #[serde(rename = "type")]
+    pub kind: AxisType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Labels of a category axis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis_label: Option<AxisLabel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_line: Option<Show>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_area: Option<Show>,
+    /// Emits click events for the axis labels
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub trigger_event: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub inverse: bool,
+    /// Hides the whole axis when false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show: Option<bool>,
+}
+
+impl Axis {
+    /// A value axis from 0, labelled with `unit` (e.g. "MB", or "%" which is
+    /// written without a space).
+    pub fn from_zero(unit: &str) -> Self {
+        let formatter = match unit {
+            "" => None,
+            "%" => Some("{value}%".to_string()),
+            unit => Some(format!("{{value}} {unit}")),
+        };
+        Axis {
+            min: Some(0.0),
+            axis_label: formatter.map(|formatter| AxisLabel {
+                formatter: Some(formatter),
+                ..AxisLabel::default()
+            }),
+            ..Axis::default()
+        }
+    }
+
+    /// A 0–100% axis.
+    pub fn percent() -> Self {
+        Axis {
+            max: Some(100.0),
+            ..Axis::from_zero("%")
+        }
+    }
+
+    /// A time axis over [start, end] in ms since the epoch.
+    pub fn time(start: Option<f64>, end: Option<f64>) -> Self {
+        Axis {
+            kind: AxisType::Time,
+            min: start,
+            max: end,
+            ..Axis::default()
+        }
+    }
+
+    pub fn category(labels: Vec<String>) -> Self {
+        Axis {
+            kind: AxisType::Category,
+            data: Some(labels),
+            ..Axis::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisLabel {
+    /// Template such as "{value} MB"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatter: Option<String>,
+    /// Labels skipped between shown ones; 0 shows every label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub margin: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub font_size: Option<usize>,
+}
+
+/// Changes to an axis of a drawn chart.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub axis_pointer: Option<AxisPointer>,
+}
+
+/// A line across the chart at the value under the mouse.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AxisPointer {
+    pub show: bool,
+    /// "line" or "shadow"
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub trigger_tooltip: bool,
+    pub label: Show,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataZoom {
+    /// "slider" or "inside"
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_axis_index: Option<usize>,
+    /// "ctrl" to zoom only with the control key held
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zoom_on_mouse_wheel: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub move_on_mouse_wheel: Option<bool>,
+    /// Zoomed range in axis values (indices on a category axis)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_value: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_value: Option<f64>,
+}
+
+impl DataZoom {
+    /// A slider below the chart.
+    pub fn slider() -> Self {
+        DataZoom {
+            kind: "slider",
+            x_axis_index: None,
+            zoom_on_mouse_wheel: None,
+            move_on_mouse_wheel: None,
+            start_value: None,
+            end_value: None,
+        }
+    }
+
+    /// Zooming with the mouse wheel while the control key is held, so the
+    /// page still scrolls over the chart.
+    pub fn inside() -> Self {
+        DataZoom {
+            kind: "inside",
+            zoom_on_mouse_wheel: Some("ctrl"),
+            move_on_mouse_wheel: Some(false),
+            ..DataZoom::slider()
+        }
+    }
+
+    /// Zoomed to [start, end] of the first x axis.
+    pub fn between(self, start: Option<f64>, end: Option<f64>) -> Self {
+        DataZoom {
+            x_axis_index: Some(0),
+            start_value: start,
+            end_value: end,
+            ..self
+        }
+    }
+}
+
+/// Buttons in the corner of a chart.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Toolbox {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub right: Option<Length>,
+    pub feature: ToolboxFeatures,
+}
+
+/// The buttons of a toolbox; those named `my…` are the page's own, whose
+/// `onclick` functions are attached by the page.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolboxFeatures {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brush: Option<BrushFeature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_export_png: Option<ToolboxButton>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_export_svg: Option<ToolboxButton>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_fullscreen: Option<ToolboxButton>,
+}
+
+/// Buttons choosing the brush type, e.g. "lineX", or "clear" to remove it.
+#[derive(Debug, Clone, Serialize)]
+pub struct BrushFeature {
+    #[serde(rename = "type")]
+    pub kinds: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolboxButton {
+    pub title: &'static str,
+    /// "path://" followed by SVG path data
+    pub icon: &'static str,
+}
+
+/// Selecting a range of the chart by dragging across it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Brush {
+    pub x_axis_index: usize,
+    /// "lineX" for a range of the x axis
+    pub brush_type: &'static str,
+    /// "single" or "multiple"
+    pub brush_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_of_brush: Option<OutOfBrush>,
+}
+
+impl Brush {
+    /// A single range of the first x axis.
+    pub fn x_range() -> Self {
+        Brush {
+            x_axis_index: 0,
+            brush_type: "lineX",
+            brush_mode: "single",
+            out_of_brush: None,
+        }
+    }
+}
+
+/// How the items outside the brushed range are drawn.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutOfBrush {
+    pub color_alpha: f64,
+}
+
+/// Maps the values of one data dimension to colors, either in `pieces` or
+/// along a continuous `in_range` gradient.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualMap {
+    /// "piecewise" or "continuous"
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimension: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    pub calculable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top: Option<Length>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left: Option<Length>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pieces: Vec<Piece>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_range: Option<ColorRange>,
+}
+
+/// Values from `min` to `max` inclusive drawn in `color`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Piece {
+    pub min: f64,
+    pub max: f64,
+    pub label: String,
+    pub color: String,
+}
+
+impl Piece {
+    pub fn new(min: f64, max: f64, label: &str, color: &str) -> Self {
+        Piece {
+            min,
+            max,
+            label: label.to_string(),
+            color: color.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ColorRange {
+    pub color: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeriesType {
+    #[default]
+    Line,
+    Heatmap,
+    /// Drawn by a `renderItem` function attached by the page
+    Custom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineType {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineStyle {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<LineType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemStyle {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opacity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shadow_blur: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shadow_color: Option<String>,
+}
+
+impl ItemStyle {
+    pub fn color(color: &str) -> Self {
+        ItemStyle {
+            color: Some(color.to_string()),
+            ..ItemStyle::default()
+        }
+    }
+}
+
+/// Fill under a line, in the series color.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AreaStyle {}
+
+/// Styles of a hovered item and of the items it dims.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Emphasis {
+    /// "self" to dim everything else
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_style: Option<ItemStyle>,
+}
+
+/// A y value of a mark line, or the top of the axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    Value(f64),
+    Max,
+}
+
+impl Serialize for Level {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Level::Value(value) => serializer.serialize_f64(*value),
+            Level::Max => serializer.serialize_str("max"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YMark {
+    pub y_axis: Level,
+}
+
+/// A named x value of a mark line, e.g. a time on a time axis or a
+/// snapshot index on a category axis.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XMark {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub x_axis: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarkLabel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show: Option<bool>,
+    /// Template such as "{b}" for the mark's name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatter: Option<String>,
+    /// e.g. "insideEndTop" along the line at its end
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<&'static str>,
+}
+
+/// Lines across the chart at the marks in `data`: horizontal at a `YMark`,
+/// e.g. a capacity or threshold, or vertical at an `XMark`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkLine<M = YMark> {
+    /// "none" hides the arrow heads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<&'static str>,
+    pub silent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<MarkLabel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_style: Option<LineStyle>,
+    pub data: Vec<M>,
+}
+
+/// Horizontal bands, each between two levels.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkArea {
+    pub silent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_style: Option<ItemStyle>,
+    pub data: Vec<[YMark; 2]>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Series<D = TimePoints> {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: SeriesType,
+    pub data: D,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_symbol: Option<bool>,
+    /// Series with the same stack name are drawn on top of each other
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub area_style: Option<AreaStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_style: Option<LineStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_style: Option<ItemStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub y_axis_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<Show>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emphasis: Option<Emphasis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blur: Option<Emphasis>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mark_line: Option<MarkLine>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mark_area: Option<MarkArea>,
+    /// Data dimensions a custom series maps to each axis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encode: Option<Encode>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Encode {
+    pub x: Vec<usize>,
+    pub y: Vec<usize>,
+}
+
+/// A series of only vertical mark lines, such as the event markers, merged
+/// into a drawn chart by its `id`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkLineSeries {
+    pub id: &'static str,
+    #[serde(rename = "type")]
+    pub kind: SeriesType,
+    /// Always empty
+    pub data: [f64; 0],
+    pub mark_line: MarkLine<XMark>,
+}
+
+impl MarkLineSeries {
+    pub fn new(id: &'static str, mark_line: MarkLine<XMark>) -> Self {
+        MarkLineSeries {
+            id,
+            kind: SeriesType::Line,
+            data: [],
+            mark_line,
+        }
+    }
+}
+
+impl<D> Series<D> {
+    /// A line without symbols at the data points.
+    pub fn line(name: &str, data: D) -> Self {
+        Series {
+            name: name.to_string(),
+            kind: SeriesType::Line,
+            data,
+            show_symbol: Some(false),
+            stack: None,
+            area_style: None,
+            line_style: None,
+            item_style: None,
+            y_axis_index: None,
+            label: None,
+            emphasis: None,
+            blur: None,
+            mark_line: None,
+            mark_area: None,
+            encode: None,
+        }
+    }
+
+    pub fn with_line_type(self, kind: LineType) -> Self {
+        Series {
+            line_style: Some(LineStyle {
+                kind: Some(kind),
+                ..self.line_style.unwrap_or_default()
+            }),
+            ..self
+        }
+    }
+
+    /// Stacked on the other series of `stack`, with the area below filled.
+    pub fn stacked(self, stack: &str) -> Self {
+        Series {
+            stack: Some(stack.to_string()),
+            area_style: Some(AreaStyle {}),
+            ..self
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! Trace parsing and heatmap/series building for the timeline viewer, free of
+//! any browser dependencies so it can be tested natively and reused outside
+//! the web app.
+
+pub mod analysis;
+pub mod columns;
+pub mod echarts;
+pub mod matrix;
+pub mod model;
+pub mod parse;
+pub mod series;
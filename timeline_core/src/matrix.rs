@@ -0,0 +1,705 @@
+use crate::columns::TraceColumns;
+use crate::model::{Process, Snapshot, Thread};
+use indexmap::IndexSet;
+use regex::Regex;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+/// Heatmap value of a thread state, indexing `STATE_NAMES`.
+pub fn state_value(state: Option<&str>) -> u8 {
+    match state.and_then(|s| s.chars().next()) {
+        Some('R') => 1,
+        Some('S') => 2,
+        Some('Z') => 3,
+        Some('T') => 4,
+        Some('D') => 5,
+        Some('I') => 6,
+        _ => 0,
+    }
+}
+
+pub fn count_running_threads(proc: &Process) -> usize {
+    let mut count = 0;
+
+    if let Some(threads) = &proc.Threads {
+        for t in threads {
+            if let Some(state) = &t.State {
+                if state.starts_with('R') {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    if let Some(children) = &proc.Children {
+        for child in children {
+            count += count_running_threads(child);
+        }
+    }
+
+    count
+}
+
+/// Row label prefix that groups a snapshot's rows under its host, so traces
+/// merged from several machines stay apart. Empty when no host is recorded.
+pub fn host_prefix(snap: &Snapshot) -> String {
+    match snap.Hostname.as_deref() {
+        Some(host) if !host.is_empty() => format!("[{host}] "),
+        _ => String::new(),
+    }
+}
+
+/// Heatmap row label of a process at the given tree depth.
+pub fn process_label(proc: &Process, depth: usize, prefix: &str) -> String {
+    row_label(RowKind::Process, prefix, &proc.Name, proc.PID, depth)
+}
+
+/// Heatmap row label of a thread belonging to a process at the given depth.
+pub fn thread_label(thread: &Thread, depth: usize, prefix: &str) -> String {
+    let name = thread.Name.as_deref().unwrap_or_default();
+    row_label(RowKind::Thread, prefix, name, thread.TID, depth)
+}
+
+/// Heatmap row label of the synthetic running thread count row shown
+/// above a process's threads.
+pub fn running_threads_label(proc: &Process, depth: usize, prefix: &str) -> String {
+    row_label(RowKind::RunningThreads, prefix, "", proc.PID, depth)
+}
+
+fn row_label(kind: RowKind, prefix: &str, name: &str, id: u32, depth: usize) -> String {
+    match kind {
+        RowKind::Process => {
+            let indent = "    ".repeat(depth);
+            let branch = if depth == 0 { "" } else { "└─ " };
+            format!("{prefix}{indent}{branch}{name} (PID {id})")
+        }
+        RowKind::Thread => {
+            let indent = "    ".repeat(depth + 1);
+            format!("{prefix}{indent}└─ {name} (TID {id})")
+        }
+        RowKind::RunningThreads => {
+            let indent = "    ".repeat(depth + 1);
+            format!("{prefix}{indent}└─ running threads (PID {id})")
+        }
+    }
+}
+
+/// Interned id of a process, thread or running thread count row.
+pub type RowId = u32;
+
+/// Interned `host_prefix` of a snapshot.
+pub type HostId = u32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RowKind {
+    Process,
+    Thread,
+    RunningThreads,
+}
+
+/// What a row stands for: a process or thread on one host at one tree depth.
+/// A reused PID or a renamed thread stays on the row it was first seen on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RowKey {
+    kind: RowKind,
+    host: HostId,
+    /// PID, or TID of a thread row
+    id: u32,
+    depth: u32,
+}
+
+/// The process, thread and running thread count rows of a trace as `RowId`s.
+/// A row's label is formatted once when it is interned. Lookups take the
+/// snapshot's `HostId`, resolved once per snapshot, so they only hash integers.
+#[derive(Debug, Default, PartialEq)]
+pub struct RowIds {
+    hosts: IndexSet<String>,
+    keys: IndexSet<RowKey>,
+    labels: Vec<String>,
+}
+
+impl RowIds {
+    /// The id of a snapshot's host, from its `host_prefix`.
+    pub fn host(&mut self, prefix: &str) -> HostId {
+        match self.hosts.get_index_of(prefix) {
+            Some(index) => index as HostId,
+            None => self.hosts.insert_full(prefix.to_string()).0 as HostId,
+        }
+    }
+
+    fn intern(&mut self, kind: RowKind, host: HostId, name: &str, id: u32, depth: usize) -> RowId {
+        let key = RowKey {
+            kind,
+            host,
+            id,
+            depth: depth as u32,
+        };
+        if let Some(index) = self.keys.get_index_of(&key) {
+            return index as RowId;
+        }
+        let prefix = &self.hosts[host as usize];
+        self.labels.push(row_label(kind, prefix, name, id, depth));
+        self.keys.insert_full(key).0 as RowId
+    }
+
+    fn find(&self, kind: RowKind, host: HostId, id: u32, depth: usize) -> Option<RowId> {
+        let key = RowKey {
+            kind,
+            host,
+            id,
+            depth: depth as u32,
+        };
+        self.keys.get_index_of(&key).map(|index| index as RowId)
+    }
+
+    /// The row of a process, labelled as by `process_label`.
+    pub fn process(&mut self, proc: &Process, depth: usize, host: HostId) -> RowId {
+        self.intern(RowKind::Process, host, &proc.Name, proc.PID, depth)
+    }
+
+    /// The row of a thread, labelled as by `thread_label`.
+    pub fn thread(&mut self, thread: &Thread, depth: usize, host: HostId) -> RowId {
+        let name = thread.Name.as_deref().unwrap_or_default();
+        self.intern(RowKind::Thread, host, name, thread.TID, depth)
+    }
+
+    /// The running thread count row of a process, labelled as by
+    /// `running_threads_label`.
+    pub fn running_threads(&mut self, proc: &Process, depth: usize, host: HostId) -> RowId {
+        self.intern(RowKind::RunningThreads, host, "", proc.PID, depth)
+    }
+
+    pub fn find_process(&self, proc: &Process, depth: usize, host: HostId) -> Option<RowId> {
+        self.find(RowKind::Process, host, proc.PID, depth)
+    }
+
+    pub fn find_thread(&self, thread: &Thread, depth: usize, host: HostId) -> Option<RowId> {
+        self.find(RowKind::Thread, host, thread.TID, depth)
+    }
+
+    pub fn find_running_threads(
+        &self,
+        proc: &Process,
+        depth: usize,
+        host: HostId,
+    ) -> Option<RowId> {
+        self.find(RowKind::RunningThreads, host, proc.PID, depth)
+    }
+
+    /// The row with the given label, for rows picked on the chart.
+    pub fn find_label(&self, label: &str) -> Option<RowId> {
+        self.labels
+            .iter()
+            .position(|row_label| row_label == label)
+            .map(|index| index as RowId)
+    }
+
+    pub fn label(&self, row: RowId) -> &str {
+        &self.labels[row as usize]
+    }
+
+    pub fn kind(&self, row: RowId) -> RowKind {
+        self.keys[row as usize].kind
+    }
+
+    /// PID, or TID of a thread row.
+    pub fn id(&self, row: RowId) -> u32 {
+        self.keys[row as usize].id
+    }
+
+    /// Tree depth of the process shown on the row, or owning its thread.
+    pub fn depth(&self, row: RowId) -> usize {
+        self.keys[row as usize].depth as usize
+    }
+
+    pub fn row_host(&self, row: RowId) -> HostId {
+        self.keys[row as usize].host
+    }
+
+    /// The `host_prefix` a host was interned from.
+    pub fn prefix(&self, host: HostId) -> &str {
+        &self.hosts[host as usize]
+    }
+
+    /// The process row of a running thread count row.
+    pub fn running_threads_owner(&self, row: RowId) -> Option<RowId> {
+        let key = self.keys[row as usize];
+        (key.kind == RowKind::RunningThreads)
+            .then(|| self.find(RowKind::Process, key.host, key.id, key.depth as usize))?
+    }
+
+    /// Every interned row.
+    pub fn rows(&self) -> impl Iterator<Item = RowId> {
+        0..self.keys.len() as RowId
+    }
+
+    /// The process shown on a process row, or owning the thread shown on a
+    /// thread row, in the tree of a snapshot of `host`.
+    pub fn row_owner<'a>(
+        &self,
+        root: &'a Process,
+        host: HostId,
+        row: RowId,
+    ) -> Option<&'a Process> {
+        let key = self.keys[row as usize];
+        if key.host != host || key.kind == RowKind::RunningThreads {
+            return None;
+        }
+        let mut owner = None;
+        visit_processes(root, 0, &mut |proc, depth| {
+            let owns_row = depth == key.depth as usize
+                && match key.kind {
+                    RowKind::Thread => proc.Threads.iter().flatten().any(|t| t.TID == key.id),
+                    _ => proc.PID == key.id,
+                };
+            if owns_row && owner.is_none() {
+                owner = Some(proc);
+            }
+        });
+        owner
+    }
+}
+
+/// Call `visit` with every process in the tree and its depth.
+fn visit_processes<'a>(proc: &'a Process, depth: usize, visit: &mut dyn FnMut(&'a Process, usize)) {
+    visit(proc, depth);
+    for child in proc.Children.iter().flatten() {
+        visit_processes(child, depth + 1, visit);
+    }
+}
+
+/// Call `visit` with every thread in the tree.
+pub fn visit_threads(proc: &Process, visit: &mut dyn FnMut(&Thread)) {
+    for t in proc.Threads.iter().flatten() {
+        visit(t);
+    }
+    for child in proc.Children.iter().flatten() {
+        visit_threads(child, visit);
+    }
+}
+
+/// Heatmap row filter typed by the user.
+pub enum RowFilter {
+    /// Case-insensitive substring of the name or command line (lowercased)
+    Text(String),
+    /// Regular expression searched in the row label
+    Pattern(Regex),
+}
+
+impl RowFilter {
+    /// The process and thread rows of the trace matching the filter.
+    pub fn matching_rows(&self, columns: &TraceColumns) -> HashSet<RowId> {
+        let rows = &columns.rows;
+        let processes = columns.processes.iter().filter(|(&row, proc)| {
+            self.matches(&proc.name, proc.command.as_deref(), rows.label(row))
+        });
+        let threads = columns
+            .threads
+            .iter()
+            .filter(|(&row, thread)| self.matches(&thread.name, None, rows.label(row)));
+        processes
+            .map(|(&row, _)| row)
+            .chain(threads.map(|(&row, _)| row))
+            .collect()
+    }
+
+    fn matches(&self, name: &str, cmd: Option<&str>, label: &str) -> bool {
+        match self {
+            RowFilter::Text(query) => {
+                let contains = |text: &str| text.to_lowercase().contains(query.as_str());
+                contains(name) || cmd.is_some_and(contains)
+            }
+            RowFilter::Pattern(pattern) => pattern.is_match(label.trim().trim_start_matches("└─ ")),
+        }
+    }
+}
+
+/// Names of the thread state values, in `Palette::states` order.
+pub const STATE_NAMES: [&str; 7] = [
+    "Unknown",
+    "Running (R)",
+    "Sleeping (S)",
+    "Zombie (Z)",
+    "Stopped (T)",
+    "Uninterruptible (D)",
+    "Idle (I)",
+];
+
+/// Labels of the heatmap GPU load buckets, in `Palette::gpu` order.
+pub const GPU_BUCKET_NAMES: [&str; 5] = [
+    "GPU 0–15%",
+    "GPU 16–35%",
+    "GPU 36–55%",
+    "GPU 56–75%",
+    "GPU 76–100%",
+];
+
+/// PID or TID shown at the end of a heatmap row label.
+pub fn row_id(label: &str) -> Option<u32> {
+    let (_, id) = label
+        .rsplit_once("(PID ")
+        .or_else(|| label.rsplit_once("(TID "))?;
+    id.strip_suffix(')')?.parse().ok()
+}
+
+/// How the heatmap cells are colored, with the inputs of the non-default
+/// `HeatmapColor` modes.
+pub enum CellColoring {
+    ThreadState,
+    Memory {
+        peak_mb: f64,
+    },
+    /// Thread utilization (percent of one core) over the preceding interval
+    CpuTime,
+    /// Processes-only view: the fraction of each process's own threads running
+    RunningThreads,
+}
+
+/// Escape text for the HTML chart tooltips.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Heatmap cells `[timestamp, row, value, detail]`, where `detail` indexes
+/// the deduplicated tooltip context of the cell's row at that timestamp.
+#[derive(Default)]
+pub struct HeatmapCells {
+    pub cells: Vec<(usize, usize, u8, usize)>,
+    pub details: IndexSet<String>,
+}
+
+impl HeatmapCells {
+    pub fn push(&mut self, timestamp: usize, row: usize, value: u8, detail: &str) {
+        let index = match self.details.get_index_of(detail) {
+            Some(index) => index,
+            None => self.details.insert_full(detail.to_string()).0,
+        };
+        self.cells.push((timestamp, row, value, index));
+    }
+
+    /// Add the cells of interned `row` over `range`, on matrix row `shown_row`.
+    pub fn add_row(
+        &mut self,
+        columns: &TraceColumns,
+        row: RowId,
+        shown_row: usize,
+        range: RangeInclusive<usize>,
+        coloring: &CellColoring,
+    ) {
+        match columns.rows.kind(row) {
+            RowKind::Process => {
+                let Some(proc) = columns.processes.get(&row) else {
+                    return;
+                };
+                let detail = process_detail(columns, row);
+                match coloring {
+                    // Use colormap indices 108–207 for the memory gradient
+                    CellColoring::Memory { peak_mb } => {
+                        for (timestamp, rss) in proc.memory_rss_mb.points(range) {
+                            let value =
+                                (rss / peak_mb.max(f64::EPSILON) * 99.0).clamp(0.0, 99.0) as u8;
+                            self.push(timestamp, shown_row, value + 108, &detail);
+                        }
+                    }
+                    CellColoring::RunningThreads => {
+                        for (timestamp, threads) in proc.threads.points(range) {
+                            if threads > 0.0 {
+                                let running = proc.running.at(timestamp).unwrap_or_default();
+                                let value = (running * 99.0 / threads) as u8 + 108;
+                                self.push(timestamp, shown_row, value, &detail);
+                            }
+                        }
+                    }
+                    _ => {
+                        for (timestamp, _) in proc.threads.points(range) {
+                            self.push(timestamp, shown_row, 1, &detail);
+                        }
+                    }
+                }
+            }
+            // Running thread counts use 208–212, binned as 0, 1, 2–3, 4–7 and 8+
+            RowKind::RunningThreads => {
+                let Some(owner) = columns.rows.running_threads_owner(row) else {
+                    return;
+                };
+                let Some(proc) = columns.processes.get(&owner) else {
+                    return;
+                };
+                let proc_detail = process_detail(columns, owner);
+                for (timestamp, threads) in proc.threads.points(range) {
+                    let running = proc.running.at(timestamp).unwrap_or_default() as usize;
+                    let value = match running {
+                        0 => 208,
+                        1 => 209,
+                        2..=3 => 210,
+                        4..=7 => 211,
+                        _ => 212,
+                    };
+                    let detail =
+                        format!("Running: {running} of {threads} threads<br/>{proc_detail}");
+                    self.push(timestamp, shown_row, value, &detail);
+                }
+            }
+            RowKind::Thread => {
+                if matches!(
+                    coloring,
+                    CellColoring::Memory { .. } | CellColoring::RunningThreads
+                ) {
+                    return;
+                }
+                let Some(thread) = columns.threads.get(&row) else {
+                    return;
+                };
+                let detail = format!(
+                    "Thread: {} (TID {})<br/>{}",
+                    escape_html(&thread.name),
+                    columns.rows.id(row),
+                    process_detail(columns, thread.process)
+                );
+                if let CellColoring::CpuTime = coloring {
+                    // Same 108–207 range as memory; the two modes are never shown together
+                    for (timestamp, percent) in thread.utilization(&columns.micros, range) {
+                        self.push(timestamp, shown_row, (percent * 0.99) as u8 + 108, &detail);
+                    }
+                    return;
+                }
+                for (timestamp, state) in thread.states.samples(range) {
+                    self.push(timestamp, shown_row, state, &detail);
+                }
+            }
+        }
+    }
+}
+
+/// Tooltip context of a process row: its name, command line and the path to
+/// it from the root of the tree.
+fn process_detail(columns: &TraceColumns, row: RowId) -> String {
+    let name = |row: RowId| {
+        let proc = columns.processes.get(&row);
+        proc.map_or("", |proc| proc.name.as_str())
+    };
+    let mut path = Vec::new();
+    let mut next = Some(row);
+    while let Some(row) = next {
+        let pid = columns.rows.id(row);
+        path.push(escape_html(&format!("{} (PID {pid})", name(row))));
+        next = columns.processes.get(&row).and_then(|proc| proc.parent);
+    }
+    path.reverse();
+    let command = columns
+        .processes
+        .get(&row)
+        .and_then(|proc| proc.command.as_deref());
+    format!(
+        "Process: {} (PID {})<br/>Command: {}<br/>Path: {}",
+        escape_html(name(row)),
+        columns.rows.id(row),
+        escape_html(command.unwrap_or_default()),
+        path.join(" › "),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(tid: u32, state: &str) -> Thread {
+        Thread {
+            TID: tid,
+            Name: Some(format!("worker-{tid}")),
+            State: Some(state.to_string()),
+            ..Thread::default()
+        }
+    }
+
+    /// A parent process with two threads and a child with one.
+    fn tree() -> Process {
+        Process {
+            PID: 1,
+            Name: "parent".to_string(),
+            Threads: Some(vec![thread(11, "R (running)"), thread(12, "S")]),
+            Children: Some(vec![Process {
+                PID: 2,
+                Name: "child".to_string(),
+                Threads: Some(vec![thread(21, "D")]),
+                Memory_RSS_MB: Some(50.0),
+                ..Process::default()
+            }]),
+            Memory_RSS_MB: Some(100.0),
+            ..Process::default()
+        }
+    }
+
+    /// Every process and thread row of `tree`, interned in tree order.
+    fn row_ids(proc: &Process) -> RowIds {
+        let child = &proc.Children.as_deref().unwrap_or_default()[0];
+        let mut ids = RowIds::default();
+        let host = ids.host("");
+        ids.process(proc, 0, host);
+        for t in proc.Threads.iter().flatten() {
+            ids.thread(t, 0, host);
+        }
+        ids.process(child, 1, host);
+        for t in child.Threads.iter().flatten() {
+            ids.thread(t, 1, host);
+        }
+        ids
+    }
+
+    /// The cells of the rows of `kinds` in a snapshot of `tree`, shown in
+    /// interned order.
+    fn cells(coloring: &CellColoring, kinds: &[RowKind]) -> Vec<(usize, usize, u8)> {
+        let snapshots = [Snapshot {
+            ProcessTree: tree(),
+            ..Snapshot::default()
+        }];
+        let columns = TraceColumns::new(&snapshots);
+        let rows = &columns.rows;
+        let shown = rows.rows().filter(|&row| kinds.contains(&rows.kind(row)));
+        let mut matrix = HeatmapCells::default();
+        for (shown_row, row) in shown.enumerate() {
+            matrix.add_row(&columns, row, shown_row, 0..=0, coloring);
+        }
+        matrix
+            .cells
+            .iter()
+            .map(|&(timestamp, row, value, _)| (timestamp, row, value))
+            .collect()
+    }
+
+    #[test]
+    fn maps_thread_states() {
+        assert_eq!(state_value(Some("R")), 1);
+        assert_eq!(state_value(Some("S (sleeping)")), 2);
+        assert_eq!(state_value(Some("Z")), 3);
+        assert_eq!(state_value(Some("T")), 4);
+        assert_eq!(state_value(Some("D")), 5);
+        assert_eq!(state_value(Some("I")), 6);
+        assert_eq!(state_value(Some("X")), 0);
+        assert_eq!(state_value(Some("")), 0);
+        assert_eq!(state_value(None), 0);
+        assert_eq!(
+            STATE_NAMES[state_value(Some("D")) as usize],
+            "Uninterruptible (D)"
+        );
+    }
+
+    #[test]
+    fn interns_rows_with_their_labels() {
+        let proc = tree();
+        let mut ids = row_ids(&proc);
+        let child = &proc.Children.as_deref().unwrap_or_default()[0];
+        assert_eq!(ids.find_process(child, 1, 0), Some(3));
+        assert_eq!(ids.process(child, 1, 0), 3);
+        assert_eq!(ids.label(3), process_label(child, 1, ""));
+        assert_eq!(ids.kind(3), RowKind::Process);
+        assert_eq!(ids.find_label(&process_label(child, 1, "")), Some(3));
+        // The same process on another host or depth is another row
+        let node2 = ids.host("[node2] ");
+        assert_eq!(ids.find_process(child, 1, node2), None);
+        assert_eq!(ids.find_process(child, 2, 0), None);
+        let running = ids.running_threads(child, 1, 0);
+        assert_eq!(ids.label(running), running_threads_label(child, 1, ""));
+        for t in child.Threads.iter().flatten() {
+            let row = ids.find_thread(t, 1, 0);
+            assert_eq!(
+                row.map(|row| ids.label(row)),
+                Some(thread_label(t, 1, "").as_str())
+            );
+            // A renamed thread keeps its row and first label
+            let renamed = Thread {
+                Name: Some("renamed".to_string()),
+                ..t.clone()
+            };
+            assert_eq!(ids.thread(&renamed, 1, 0), row.unwrap());
+            assert_eq!(ids.label(row.unwrap()), thread_label(t, 1, ""));
+        }
+    }
+
+    #[test]
+    fn finds_the_owner_of_a_row() {
+        let proc = tree();
+        let ids = row_ids(&proc);
+        let owner = |row| ids.row_owner(&proc, 0, row).map(|owner| owner.PID);
+        assert_eq!(owner(0), Some(1));
+        // Thread rows belong to their process
+        assert_eq!(owner(2), Some(1));
+        assert_eq!(owner(4), Some(2));
+        assert_eq!(ids.row_owner(&proc, 1, 0).map(|owner| owner.PID), None);
+    }
+
+    #[test]
+    fn colors_threads_by_state() {
+        assert_eq!(
+            cells(
+                &CellColoring::ThreadState,
+                &[RowKind::Process, RowKind::Thread]
+            ),
+            [(0, 0, 1), (0, 1, 1), (0, 2, 2), (0, 3, 1), (0, 4, 5)]
+        );
+    }
+
+    #[test]
+    fn scales_memory_to_the_peak() {
+        assert_eq!(
+            cells(
+                &CellColoring::Memory { peak_mb: 100.0 },
+                &[RowKind::Process, RowKind::Thread]
+            ),
+            [(0, 0, 207), (0, 3, 157)]
+        );
+    }
+
+    #[test]
+    fn bins_running_thread_rows() {
+        assert_eq!(
+            cells(&CellColoring::ThreadState, &[RowKind::RunningThreads]),
+            [(0, 0, 209), (0, 1, 208)]
+        );
+    }
+
+    #[test]
+    fn details_name_the_path_to_a_row() {
+        let snapshots = [Snapshot {
+            ProcessTree: tree(),
+            ..Snapshot::default()
+        }];
+        let columns = TraceColumns::new(&snapshots);
+        let child = columns.rows.find_label("    └─ child (PID 2)").unwrap();
+        let mut matrix = HeatmapCells::default();
+        matrix.add_row(&columns, child, 0, 0..=0, &CellColoring::ThreadState);
+        assert_eq!(
+            matrix.details[0],
+            "Process: child (PID 2)<br/>Command: <br/>Path: parent (PID 1) › child (PID 2)"
+        );
+    }
+
+    #[test]
+    fn filters_rows_by_name_or_pattern() {
+        let snapshots = [Snapshot {
+            ProcessTree: tree(),
+            ..Snapshot::default()
+        }];
+        let columns = TraceColumns::new(&snapshots);
+        let labels = |filter: RowFilter| {
+            let mut labels: Vec<&str> = filter
+                .matching_rows(&columns)
+                .into_iter()
+                .map(|row| columns.rows.label(row))
+                .collect();
+            labels.sort();
+            labels
+        };
+        assert_eq!(
+            labels(RowFilter::Text("child".to_string())),
+            ["    └─ child (PID 2)"]
+        );
+        assert_eq!(
+            labels(RowFilter::Pattern(Regex::new("^worker-1").unwrap())),
+            ["    └─ worker-11 (TID 11)", "    └─ worker-12 (TID 12)"]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Collector schema version; absent in captures that predate versioning.
+    #[serde(default, alias = "schema_version", alias = "schemaVersion")]
+    pub SchemaVersion: Option<u32>,
+    /// RFC 3339 / ISO 8601 time, or seconds (or milliseconds) since the epoch.
+    #[serde(alias = "timestamp", deserialize_with = "deserialize_timestamp")]
+    pub Timestamp: String,
+    #[serde(alias = "process_tree", alias = "processTree")]
+    pub ProcessTree: Process,
+    #[serde(
+        default,
+        alias = "gpu_status",
+        alias = "gpuStatus",
+        alias = "GPU_Status"
+    )]
+    pub GPUStatus: Vec<GPUStatus>,
+    #[serde(default, alias = "cpu_cores_total", alias = "cpuCoresTotal")]
+    pub CPU_Cores_Total: u32,
+    #[serde(default, alias = "loadavg_1", alias = "loadAvg1")]
+    pub LoadAvg_1: Option<f64>,
+    #[serde(default, alias = "loadavg_5", alias = "loadAvg5")]
+    pub LoadAvg_5: Option<f64>,
+    #[serde(default, alias = "loadavg_15", alias = "loadAvg15")]
+    pub LoadAvg_15: Option<f64>,
+    /// Load of each logical core in percent, indexed by core number.
+    #[serde(default, alias = "cpu_core_loads", alias = "cpuCoreLoads")]
+    pub CPU_Core_Loads: Vec<f64>,
+    #[serde(default, alias = "memory_total_mb", alias = "memoryTotalMB")]
+    pub Memory_Total_MB: Option<f64>,
+    #[serde(default, alias = "memory_used_mb", alias = "memoryUsedMB")]
+    pub Memory_Used_MB: Option<f64>,
+    /// Page cache, reclaimable and not counted in `Memory_Used_MB`.
+    #[serde(default, alias = "memory_cached_mb", alias = "memoryCachedMB")]
+    pub Memory_Cached_MB: Option<f64>,
+    #[serde(default, alias = "swap_total_mb", alias = "swapTotalMB")]
+    pub Swap_Total_MB: Option<f64>,
+    #[serde(default, alias = "swap_used_mb", alias = "swapUsedMB")]
+    pub Swap_Used_MB: Option<f64>,
+    /// Application counters attached by the collector (e.g. queue depth),
+    /// each charted on its own.
+    #[serde(default, alias = "custom")]
+    pub Custom: BTreeMap<String, f64>,
+    /// Events logged with this snapshot; standalone event lines in a JSONL
+    /// trace are attached to the snapshot before them.
+    #[serde(default, alias = "events")]
+    pub Events: Vec<TimelineEvent>,
+    /// Machine the snapshot was taken on, for traces merged from a distributed job.
+    #[serde(default, alias = "hostname", alias = "host")]
+    pub Hostname: Option<String>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Process {
+    #[serde(alias = "pid")]
+    pub PID: u32,
+    #[serde(alias = "name")]
+    pub Name: String,
+    #[serde(alias = "cmd")]
+    pub CMD: Option<String>,
+    #[serde(alias = "threads")]
+    pub Threads: Option<Vec<Thread>>,
+    #[serde(alias = "children")]
+    pub Children: Option<Vec<Process>>,
+    /// CPU usage in percent of one core, as reported by psutil/pidstat.
+    #[serde(default, alias = "cpu_percent", alias = "cpuPercent", alias = "%CPU")]
+    pub CPU_Percent: Option<f64>,
+    /// Cumulative bytes read from storage.
+    #[serde(default, alias = "io_read_bytes", alias = "ioReadBytes")]
+    pub IO_Read_Bytes: Option<f64>,
+    /// Cumulative bytes written to storage.
+    #[serde(default, alias = "io_write_bytes", alias = "ioWriteBytes")]
+    pub IO_Write_Bytes: Option<f64>,
+    /// Container or cgroup the process runs in, e.g. a Docker container or
+    /// Kubernetes pod; heatmap rows are grouped under it.
+    #[serde(default, alias = "container", alias = "Cgroup", alias = "cgroup")]
+    pub Container: Option<String>,
+    /// Number of open file descriptors.
+    #[serde(default, alias = "fd_count", alias = "fdCount", alias = "num_fds")]
+    pub FD_Count: Option<f64>,
+    /// Owner of the process; captures may carry the name, the numeric UID or both.
+    #[serde(default, alias = "user", alias = "username")]
+    pub User: Option<String>,
+    #[serde(default, alias = "uid")]
+    pub UID: Option<u32>,
+    /// When the process started, in the same format as `Snapshot::Timestamp`.
+    #[serde(
+        default,
+        alias = "start_time",
+        alias = "startTime",
+        alias = "create_time"
+    )]
+    pub Start_Time: Option<String>,
+    /// Resident set size in MB.
+    #[serde(
+        default,
+        alias = "memory_rss_mb",
+        alias = "memoryRssMB",
+        alias = "rss_mb"
+    )]
+    pub Memory_RSS_MB: Option<f64>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Thread {
+    #[serde(alias = "tid")]
+    pub TID: u32,
+    #[serde(alias = "name")]
+    pub Name: Option<String>,
+    #[serde(alias = "state")]
+    pub State: Option<String>,
+    /// Cumulative user + system CPU time in milliseconds.
+    #[serde(default, alias = "cpu_time_ms", alias = "cpuTimeMs")]
+    pub CPU_Time_Ms: Option<f64>,
+    /// Cumulative context switches where the thread gave up the CPU itself,
+    /// e.g. to wait on a lock or I/O.
+    #[serde(
+        default,
+        alias = "voluntary_ctxt_switches",
+        alias = "voluntaryCtxtSwitches"
+    )]
+    pub Voluntary_Ctxt_Switches: Option<f64>,
+    /// Cumulative context switches where the thread was preempted.
+    #[serde(
+        default,
+        alias = "nonvoluntary_ctxt_switches",
+        alias = "nonvoluntaryCtxtSwitches"
+    )]
+    pub Nonvoluntary_Ctxt_Switches: Option<f64>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GPUStatus {
+    #[serde(alias = "gpu_id", alias = "gpuId")]
+    pub GPU_ID: u32,
+    #[serde(alias = "name")]
+    pub Name: String,
+    #[serde(alias = "load_percent", alias = "loadPercent")]
+    pub Load_Percent: f64,
+    #[serde(alias = "memory_used_mb", alias = "memoryUsedMB")]
+    pub Memory_Used_MB: f64,
+    #[serde(alias = "memory_total_mb", alias = "memoryTotalMB")]
+    pub Memory_Total_MB: f64,
+    #[serde(alias = "temperature_c", alias = "temperatureC")]
+    pub Temperature_C: f64,
+    #[serde(alias = "driver")]
+    pub Driver: String,
+    #[serde(
+        default,
+        alias = "power_draw_w",
+        alias = "powerDrawW",
+        alias = "power.draw"
+    )]
+    pub Power_Draw_W: Option<f64>,
+    /// Enforced power cap; draw pinned at this limit means the GPU is throttled.
+    #[serde(
+        default,
+        alias = "power_limit_w",
+        alias = "powerLimitW",
+        alias = "power.limit"
+    )]
+    pub Power_Limit_W: Option<f64>,
+    /// Streaming multiprocessor clock; a drop under steady load indicates throttling.
+    #[serde(
+        default,
+        alias = "sm_clock_mhz",
+        alias = "smClockMHz",
+        alias = "clocks.sm"
+    )]
+    pub SM_Clock_MHz: Option<f64>,
+    #[serde(
+        default,
+        alias = "memory_clock_mhz",
+        alias = "memoryClockMHz",
+        alias = "clocks.mem"
+    )]
+    pub Memory_Clock_MHz: Option<f64>,
+    /// Compute processes holding memory on this GPU.
+    #[serde(default, alias = "gpu_processes", alias = "gpuProcesses")]
+    pub GPU_Processes: Vec<GPUProcess>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GPUProcess {
+    #[serde(alias = "pid")]
+    pub PID: u32,
+    #[serde(
+        alias = "memory_mb",
+        alias = "memoryMB",
+        alias = "GPU_Memory_MB",
+        alias = "used_memory"
+    )]
+    pub Memory_MB: f64,
+}
+
+/// Application event such as "checkpoint saved", drawn as a vertical marker
+/// on every chart.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    #[serde(alias = "name", alias = "Event", alias = "event")]
+    pub Name: String,
+    #[serde(alias = "timestamp", deserialize_with = "deserialize_timestamp")]
+    pub Timestamp: String,
+}
+
+/// Accept epoch timestamps written as JSON numbers, keeping them as strings
+/// like every other timestamp.
+fn deserialize_timestamp<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawTimestamp {
+        Text(String),
+        Epoch(f64),
+    }
+    Ok(match RawTimestamp::deserialize(deserializer)? {
+        RawTimestamp::Text(text) => text,
+        RawTimestamp::Epoch(epoch) => epoch.to_string(),
+    })
+}
+
+/// Format a microsecond offset as an ISO 8601 timestamp matching the collector.
+pub fn micros_to_timestamp(micros: f64) -> String {
+    DateTime::from_timestamp_micros(micros.round() as i64)
+        .unwrap_or_default()
+        .naive_utc()
+        .format("%Y-%m-%dT%H:%M:%S%.6f")
+        .to_string()
+}
+
+/// Epoch timestamps above this are taken as milliseconds rather than seconds.
+const EPOCH_MILLIS_THRESHOLD: f64 = 1e11;
+
+/// Parse a snapshot `Timestamp` into microseconds since the epoch. Timestamps
+/// without an offset (as written by the collector) are taken as UTC.
+pub fn timestamp_micros(timestamp: &str) -> Option<f64> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(timestamp) {
+        return Some(datetime.timestamp_micros() as f64);
+    }
+    if let Ok(epoch) = timestamp.trim().parse::<f64>() {
+        return match epoch {
+            epoch if !epoch.is_finite() => None,
+            epoch if epoch.abs() > EPOCH_MILLIS_THRESHOLD => Some(epoch * 1e3),
+            epoch => Some(epoch * 1e6),
+        };
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(timestamp, fmt).ok())
+        .map(|datetime| datetime.and_utc().timestamp_micros() as f64)
+}
+
+/// Ordering key used when merging snapshots: the parsed time (falling back to
+/// the raw string when it doesn't parse), then the host. Snapshots with equal
+/// keys are duplicates, e.g. from overlapping rotated logs.
+pub fn snapshot_order_key(snap: &Snapshot) -> (Option<i64>, String, Option<String>) {
+    let micros = timestamp_micros(&snap.Timestamp).map(|micros| micros as i64);
+    let raw = if micros.is_none() {
+        snap.Timestamp.clone()
+    } else {
+        String::new()
+    };
+    (micros, raw, snap.Hostname.clone())
+}
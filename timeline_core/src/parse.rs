@@ -0,0 +1,674 @@
+mod chrome;
+mod csv_import;
+mod perfetto;
+mod pidstat;
+
+use crate::model::{micros_to_timestamp, Process, Snapshot, Thread, TimelineEvent};
+use chrome::{import_chrome_trace, CHROME_TRACE_KEY};
+use flate2::read::MultiGzDecoder;
+use indexmap::IndexMap;
+use perfetto::import_perfetto_trace;
+use pidstat::import_pidstat;
+use ruzstd::decoding::StreamingDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Read;
+
+pub use csv_import::{CsvField, CsvImport};
+pub use pidstat::is_pidstat_report;
+
+/// Characters of an unparseable record kept for the error panel.
+const PARSE_ERROR_EXCERPT_CHARS: usize = 120;
+
+/// A record that could not be parsed, reported in the parse error panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseError {
+    /// File or URL the record came from
+    pub source: String,
+    /// 1-based line (or array entry) number; 0 when the whole input failed
+    pub line: usize,
+    pub message: String,
+    /// Leading characters of the offending record
+    pub excerpt: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, message: impl ToString, content: &str) -> Self {
+        let mut excerpt: String = content.chars().take(PARSE_ERROR_EXCERPT_CHARS).collect();
+        if excerpt.len() < content.len() {
+            excerpt.push('…');
+        }
+        ParseError {
+            source: String::new(),
+            line,
+            message: message.to_string(),
+            excerpt,
+        }
+    }
+}
+
+pub fn parse_snapshots(content: &str) -> (Vec<Snapshot>, Vec<ParseError>) {
+    if is_pidstat_report(content.as_bytes()) {
+        return (import_pidstat(content), Vec::new());
+    }
+
+    // Some exporters write a single JSON document rather than line-delimited JSON:
+    // either an array of snapshots or a Chrome trace to convert
+    match content.trim_start().as_bytes().first() {
+        Some(b'[') => return parse_json_array(content),
+        Some(b'{') => {
+            // A JSONL capture fails here on its second line, or has no trace events
+            if let Ok(object) = serde_json::from_str::<serde_json::Map<_, _>>(content) {
+                if let Some(events) = object.get(CHROME_TRACE_KEY) {
+                    return match events
+                        .as_array()
+                        .and_then(|events| import_chrome_trace(events))
+                    {
+                        Some(snapshots) => (snapshots, Vec::new()),
+                        None => (
+                            Vec::new(),
+                            vec![ParseError::new(
+                                0,
+                                "no Chrome trace events to convert",
+                                content,
+                            )],
+                        ),
+                    };
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut part = parse_snapshot_part(content);
+    let mut carry = EventCarry::default();
+    carry.carry(&mut part);
+    part.errors.extend(carry.finish());
+    (part.snapshots, part.errors)
+}
+
+/// Part of a JSONL stream parsed on its own, such as a chunk of a large file
+/// or a message of a live stream.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParsedPart {
+    pub snapshots: Vec<Snapshot>,
+    pub errors: Vec<ParseError>,
+    /// Event lines before the part's first snapshot, which belong to the last
+    /// snapshot of the parts before; each with the error reported if none comes
+    pub leading_events: Vec<(TimelineEvent, ParseError)>,
+}
+
+/// Parse JSONL lines, attaching each event line to the snapshot before it.
+pub fn parse_snapshot_part(content: &str) -> ParsedPart {
+    let mut part = ParsedPart::default();
+    for (index, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_snapshot_line(line) {
+            Ok(snapshot) => part.snapshots.push(snapshot),
+            Err(e) => match serde_json::from_str::<TimelineEvent>(line) {
+                Ok(event) => match part.snapshots.last_mut() {
+                    Some(snapshot) => snapshot.Events.push(event),
+                    None => part.leading_events.push((
+                        event,
+                        ParseError::new(
+                            index + 1,
+                            "event without a snapshot to attach it to",
+                            line,
+                        ),
+                    )),
+                },
+                Err(_) => part.errors.push(ParseError::new(index + 1, e, line)),
+            },
+        }
+    }
+    part
+}
+
+/// Carries event lines across the parts of a JSONL stream, so an event
+/// reaches the snapshot before it even when that came in an earlier part.
+/// Events at the very start of the stream go to its first snapshot instead.
+#[derive(Debug, Default)]
+pub struct EventCarry {
+    /// Whether a snapshot has been read, which later leading events belong to
+    started: bool,
+    /// Events at the start of the stream, waiting for its first snapshot
+    pending: Vec<(TimelineEvent, ParseError)>,
+}
+
+impl EventCarry {
+    /// Take the leading events of `part`, returning those belonging to the last
+    /// snapshot of the parts before it.
+    pub fn carry(&mut self, part: &mut ParsedPart) -> Vec<TimelineEvent> {
+        let leading = std::mem::take(&mut part.leading_events);
+        if self.started {
+            return leading.into_iter().map(|(event, _)| event).collect();
+        }
+        self.pending.extend(leading);
+        if let Some(first) = part.snapshots.first_mut() {
+            // They came before the snapshot's line, so before its own events
+            let pending = self.pending.drain(..).map(|(event, _)| event);
+            first.Events.splice(0..0, pending);
+            self.started = true;
+        }
+        Vec::new()
+    }
+
+    /// Errors for the events no snapshot came for.
+    pub fn finish(self) -> Vec<ParseError> {
+        self.pending.into_iter().map(|(_, error)| error).collect()
+    }
+}
+
+/// A JSON array of snapshots, parsed entry by entry so one bad record doesn't
+/// lose the rest, or of Chrome trace events to convert.
+fn parse_json_array(content: &str) -> (Vec<Snapshot>, Vec<ParseError>) {
+    let values = match serde_json::from_str::<Vec<serde_json::Value>>(content) {
+        Ok(values) => values,
+        Err(e) => return (Vec::new(), vec![ParseError::new(0, e, content)]),
+    };
+    if let Some(snapshots) = import_chrome_trace(&values) {
+        return (snapshots, Vec::new());
+    }
+    let mut parsed = Vec::new();
+    let mut errors = Vec::new();
+    for (index, value) in values.into_iter().enumerate() {
+        match parse_snapshot_value(value) {
+            Ok(snapshot) => parsed.push(snapshot),
+            Err((e, value)) => errors.push(ParseError::new(index + 1, e, &value.to_string())),
+        }
+    }
+    (parsed, errors)
+}
+
+/// Schema version written by the bundled collectors.
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Just the schema version of a record, read skipping over everything else.
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+struct RecordVersion {
+    #[serde(default, alias = "schema_version", alias = "schemaVersion")]
+    SchemaVersion: Option<u32>,
+}
+
+impl RecordVersion {
+    fn is_current(version: Result<Self, serde_json::Error>) -> bool {
+        version.is_ok_and(|version| version.SchemaVersion >= Some(CURRENT_SCHEMA_VERSION))
+    }
+}
+
+/// Parse one JSONL line. Current-version records deserialize directly; older
+/// (or malformed) records go through the migration layer.
+fn parse_snapshot_line(line: &str) -> Result<Snapshot, serde_json::Error> {
+    if RecordVersion::is_current(serde_json::from_str(line)) {
+        serde_json::from_str(line)
+    } else {
+        migrate_snapshot(serde_json::from_str(line)?)
+    }
+}
+
+/// Parse one array entry the way `parse_snapshot_line` parses a line. The entry
+/// comes back with the error so it can be quoted.
+fn parse_snapshot_value(
+    value: serde_json::Value,
+) -> Result<Snapshot, (serde_json::Error, serde_json::Value)> {
+    let parsed = if RecordVersion::is_current(RecordVersion::deserialize(&value)) {
+        Snapshot::deserialize(&value)
+    } else {
+        migrate_snapshot(value.clone())
+    };
+    parsed.map_err(|e| (e, value))
+}
+
+/// Upgrade a raw snapshot record to `CURRENT_SCHEMA_VERSION`, one version at a
+/// time, before deserializing it.
+fn migrate_snapshot(mut value: serde_json::Value) -> Result<Snapshot, serde_json::Error> {
+    let version = value
+        .as_object()
+        .and_then(|snapshot| snapshot.get(&field_key(snapshot, "SchemaVersion")?))
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0) as u32;
+    if version > CURRENT_SCHEMA_VERSION {
+        log::warn!(
+            "Snapshot schema version {} is newer than supported ({}); reading known fields only",
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+    if version < 1 {
+        migrate_v0_to_v1(&mut value);
+    }
+    serde_json::from_value(value)
+}
+
+/// Version 0 covers unversioned captures: CPU core counts recorded under other
+/// names and GPU entries using the raw GPUtil/nvidia-smi field names.
+fn migrate_v0_to_v1(value: &mut serde_json::Value) {
+    use serde_json::Value;
+
+    let Some(snapshot) = value.as_object_mut() else {
+        return;
+    };
+    if field_key(snapshot, "CPU_Cores_Total").is_none() {
+        let cores = ["CPU_Count", "cpu_count", "CPU_Cores"]
+            .iter()
+            .find_map(|key| snapshot.get(*key).cloned());
+        snapshot.insert("CPU_Cores_Total".into(), cores.unwrap_or(Value::from(0)));
+    }
+    if field_key(snapshot, "GPUStatus").is_none() {
+        if let Some(gpus) = snapshot.remove("GPUs") {
+            snapshot.insert("GPUStatus".into(), gpus);
+        }
+    }
+
+    let gpu_key = field_key(snapshot, "GPUStatus").unwrap_or_default();
+    if let Some(Value::Array(gpus)) = snapshot.get_mut(&gpu_key) {
+        for gpu in gpus.iter_mut().filter_map(Value::as_object_mut) {
+            let mut rename = |to: &str, from: &[&str], default: Value| {
+                if field_key(gpu, to).is_some() {
+                    return;
+                }
+                let found = from.iter().find_map(|key| gpu.remove(*key));
+                gpu.insert(to.into(), found.unwrap_or(default));
+            };
+            rename("GPU_ID", &["id", "index"], Value::from(0));
+            rename("Name", &[], Value::from(""));
+            rename(
+                "Memory_Used_MB",
+                &["memoryUsed", "memory.used"],
+                Value::from(0.0),
+            );
+            rename(
+                "Memory_Total_MB",
+                &["memoryTotal", "memory.total"],
+                Value::from(0.0),
+            );
+            rename(
+                "Temperature_C",
+                &["temperature", "temperature.gpu"],
+                Value::from(0.0),
+            );
+            rename("Driver", &["driver_version"], Value::from(""));
+            if field_key(gpu, "Load_Percent").is_none() {
+                // GPUtil reports load as a 0-1 fraction
+                let load = match (gpu.remove("load"), gpu.remove("utilization.gpu")) {
+                    (Some(fraction), _) => fraction.as_f64().map(|f| f * 100.0),
+                    (None, Some(percent)) => percent.as_f64(),
+                    (None, None) => None,
+                };
+                gpu.insert("Load_Percent".into(), Value::from(load.unwrap_or(0.0)));
+            }
+        }
+    }
+
+    if let Some(key) = field_key(snapshot, "SchemaVersion") {
+        snapshot.remove(&key);
+    }
+    snapshot.insert("SchemaVersion".into(), Value::from(1));
+}
+
+/// Find the key a record uses for `field`, accepting the same spellings as the
+/// serde aliases on the model types (case-insensitive, underscores optional).
+fn field_key(object: &serde_json::Map<String, serde_json::Value>, field: &str) -> Option<String> {
+    let normalize = |key: &str| -> String {
+        key.chars()
+            .filter(|&c| c != '_')
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let field = normalize(field);
+    object.keys().find(|key| normalize(key) == field).cloned()
+}
+
+/// Whether the content is a single JSON document that must be parsed whole
+/// rather than line by line: an array, or an object spread over several lines
+/// such as a pretty-printed Chrome trace. Only the first line is inspected, so
+/// the head of a file is enough; an object on one line reads as JSONL either way.
+pub fn is_json_document(head: &[u8]) -> bool {
+    let start = head.iter().position(|b| !b.is_ascii_whitespace());
+    match start.map(|start| &head[start..]) {
+        Some([b'[', ..]) => true,
+        Some(object @ [b'{', ..]) => object.iter().position(|&b| b == b'\n').is_some_and(|end| {
+            serde_json::from_slice::<serde::de::IgnoredAny>(&object[..end]).is_err()
+        }),
+        _ => false,
+    }
+}
+
+/// Extensions offered by the file picker and picked up from imported directories.
+pub const TRACE_FILE_EXTENSIONS: &[&str] = &[
+    ".jsonl",
+    ".json",
+    ".csv",
+    ".msgpack",
+    ".mpk",
+    ".pftrace",
+    ".perfetto-trace",
+    ".log",
+    ".txt",
+    ".gz",
+    ".zst",
+];
+
+pub fn is_trace_file_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    !name.starts_with('.') && TRACE_FILE_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+pub const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Encoding of a trace payload, selected by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TraceFormat {
+    /// JSONL, or a single JSON array of snapshots.
+    Json,
+    /// A MessagePack array of snapshots, or a stream of concatenated snapshot maps.
+    MessagePack,
+    /// A Perfetto protobuf trace, converted from scheduling events.
+    Perfetto,
+}
+
+impl TraceFormat {
+    pub fn from_file_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        let name = name
+            .strip_suffix(".gz")
+            .or_else(|| name.strip_suffix(".zst"))
+            .unwrap_or(&name);
+        if name.ends_with(".msgpack") || name.ends_with(".mpk") {
+            TraceFormat::MessagePack
+        } else if name.ends_with(".pftrace") || name.ends_with(".perfetto-trace") {
+            TraceFormat::Perfetto
+        } else {
+            TraceFormat::Json
+        }
+    }
+}
+
+/// Transparently decompress gzip and zstd payloads (detected by magic bytes,
+/// so the file extension is not needed). Other input is returned unchanged.
+fn decompress(bytes: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    if bytes.starts_with(&GZIP_MAGIC) {
+        MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut content)?;
+    } else if bytes.starts_with(&ZSTD_MAGIC) {
+        StreamingDecoder::new(bytes.as_slice())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?
+            .read_to_end(&mut content)?;
+    } else {
+        content = bytes;
+    }
+    Ok(content)
+}
+
+/// Decompress and deserialize a trace payload in the given format, returning
+/// the snapshots alongside any records that failed to parse.
+pub fn parse_trace(
+    format: TraceFormat,
+    bytes: Vec<u8>,
+) -> Result<(Vec<Snapshot>, Vec<ParseError>), String> {
+    let bytes = decompress(bytes).map_err(|e| e.to_string())?;
+    match format {
+        TraceFormat::Json => {
+            let content = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+            Ok(parse_snapshots(&content))
+        }
+        TraceFormat::MessagePack => Ok((parse_msgpack_snapshots(&bytes), Vec::new())),
+        TraceFormat::Perfetto => Ok((import_perfetto_trace(&bytes), Vec::new())),
+    }
+}
+
+pub fn parse_msgpack_snapshots(bytes: &[u8]) -> Vec<Snapshot> {
+    if let Ok(snapshots) = rmp_serde::from_slice::<Vec<Snapshot>>(bytes) {
+        return snapshots;
+    }
+
+    let mut parsed = Vec::new();
+    let mut deserializer = rmp_serde::Deserializer::new(bytes);
+    while !deserializer.get_ref().is_empty() {
+        match Snapshot::deserialize(&mut deserializer) {
+            Ok(snapshot) => parsed.push(snapshot),
+            Err(e) => {
+                // Unlike JSONL there is no line boundary to resynchronise on
+                log::warn!("Failed to decode MessagePack record: {}", e);
+                break;
+            }
+        }
+    }
+    parsed
+}
+
+/// Number of synthetic snapshots sampled across the span of an imported trace.
+const IMPORT_SAMPLES: usize = 500;
+
+/// Process metadata recovered by a trace importer.
+pub struct ImportedProcess {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub cmd: Option<String>,
+    /// `(TID, name)` pairs
+    pub threads: Vec<(u32, String)>,
+}
+
+/// Sample `IMPORT_SAMPLES` evenly spaced snapshots across `span` (microseconds).
+/// Processes are nested under their parent when it is known, otherwise under a
+/// synthetic root called `root_name`; `state_at(pid, tid, t)` gives each
+/// thread's state letter at sample time `t`.
+pub fn synthesize_snapshots(
+    root_name: &str,
+    processes: &[ImportedProcess],
+    span: (f64, f64),
+    state_at: impl Fn(u32, u32, f64) -> &'static str,
+) -> Vec<Snapshot> {
+    fn build(
+        index: usize,
+        processes: &[ImportedProcess],
+        children_of: &IndexMap<u32, Vec<usize>>,
+        t: f64,
+        state_at: &impl Fn(u32, u32, f64) -> &'static str,
+    ) -> Process {
+        let proc = &processes[index];
+        let children: Vec<Process> = children_of
+            .get(&proc.pid)
+            .into_iter()
+            .flatten()
+            .map(|&child| build(child, processes, children_of, t, state_at))
+            .collect();
+        Process {
+            PID: proc.pid,
+            Name: proc.name.clone(),
+            CMD: proc.cmd.clone(),
+            Threads: Some(
+                proc.threads
+                    .iter()
+                    .map(|(tid, name)| Thread {
+                        TID: *tid,
+                        Name: Some(name.clone()),
+                        State: Some(state_at(proc.pid, *tid, t).to_string()),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            Children: (!children.is_empty()).then_some(children),
+            ..Default::default()
+        }
+    }
+
+    let known: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    let mut roots = Vec::new();
+    let mut children_of: IndexMap<u32, Vec<usize>> = IndexMap::new();
+    for (index, proc) in processes.iter().enumerate() {
+        match proc
+            .ppid
+            .filter(|ppid| known.contains(ppid) && *ppid != proc.pid)
+        {
+            Some(ppid) => children_of.entry(ppid).or_default().push(index),
+            None => roots.push(index),
+        }
+    }
+
+    let (start, end) = span;
+    let samples = if end > start { IMPORT_SAMPLES } else { 1 };
+    let step = (end - start) / (samples.max(2) - 1) as f64;
+    (0..samples)
+        .map(|i| {
+            let t = start + step * i as f64;
+            let children = roots
+                .iter()
+                .map(|&index| build(index, processes, &children_of, t, &state_at))
+                .collect();
+            Snapshot {
+                Timestamp: micros_to_timestamp(t),
+                ProcessTree: Process {
+                    PID: 0,
+                    Name: root_name.to_string(),
+                    Children: Some(children),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = r#"{"SchemaVersion": 1, "Timestamp": "2024-05-01T12:00:00", "ProcessTree": {"PID": 1, "Name": "init"}}"#;
+
+    #[test]
+    fn skips_blank_lines_and_reports_bad_ones() {
+        let content = format!("{SNAPSHOT}\n\n{{not json\n{SNAPSHOT}\n");
+        let (parsed, errors) = parse_snapshots(&content);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 3);
+        assert_eq!(errors[0].excerpt, "{not json");
+    }
+
+    #[test]
+    fn truncates_long_excerpts() {
+        let line = "x".repeat(PARSE_ERROR_EXCERPT_CHARS * 2);
+        let error = ParseError::new(1, "bad", &line);
+        assert_eq!(error.excerpt.chars().count(), PARSE_ERROR_EXCERPT_CHARS + 1);
+        assert!(error.excerpt.ends_with('…'));
+    }
+
+    #[test]
+    fn accepts_aliases_and_epoch_timestamps() {
+        let line = r#"{"schema_version": 1, "timestamp": 1714564800, "process_tree": {"pid": 7, "name": "python", "threads": [{"tid": 8, "state": "R"}]}}"#;
+        let (parsed, errors) = parse_snapshots(line);
+        assert!(errors.is_empty());
+        assert_eq!(parsed[0].Timestamp, "1714564800");
+        assert_eq!(parsed[0].ProcessTree.Name, "python");
+        let threads = parsed[0].ProcessTree.Threads.as_deref().unwrap_or_default();
+        assert_eq!(threads[0].State.as_deref(), Some("R"));
+    }
+
+    #[test]
+    fn migrates_unversioned_records() {
+        let line = r#"{"Timestamp": "2024-05-01T12:00:00", "ProcessTree": {"PID": 1, "Name": "init"}, "CPU_Count": 8, "GPUs": [{"id": 1, "load": 0.5, "memoryUsed": 100, "memoryTotal": 400}]}"#;
+        let (parsed, errors) = parse_snapshots(line);
+        assert!(errors.is_empty());
+        let snap = &parsed[0];
+        assert_eq!(snap.SchemaVersion, Some(CURRENT_SCHEMA_VERSION));
+        assert_eq!(snap.CPU_Cores_Total, 8);
+        let gpu = &snap.GPUStatus[0];
+        assert_eq!(gpu.GPU_ID, 1);
+        assert_eq!(gpu.Load_Percent, 50.0);
+        assert_eq!(gpu.Memory_Used_MB, 100.0);
+        assert_eq!(gpu.Memory_Total_MB, 400.0);
+    }
+
+    #[test]
+    fn migrates_unversioned_array_entries() {
+        let content = r#"[{"Timestamp": "2024-05-01T12:00:00", "ProcessTree": {"PID": 1, "Name": "init"}, "CPU_Count": 8, "GPUs": [{"id": 1, "load": 0.5}]}]"#;
+        let (parsed, errors) = parse_snapshots(content);
+        assert!(errors.is_empty());
+        let snap = &parsed[0];
+        assert_eq!(snap.SchemaVersion, Some(CURRENT_SCHEMA_VERSION));
+        assert_eq!(snap.CPU_Cores_Total, 8);
+        assert_eq!(snap.GPUStatus[0].GPU_ID, 1);
+        assert_eq!(snap.GPUStatus[0].Load_Percent, 50.0);
+    }
+
+    #[test]
+    fn attaches_events_to_snapshots() {
+        let event = r#"{"Event": "checkpoint", "Timestamp": "2024-05-01T12:00:01"}"#;
+        let content = format!("{event}\n{SNAPSHOT}\n{event}\n");
+        let (parsed, errors) = parse_snapshots(&content);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].Events.len(), 2);
+        assert_eq!(parsed[0].Events[0].Name, "checkpoint");
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn reports_events_without_a_snapshot() {
+        let event = r#"{"Event": "checkpoint", "Timestamp": "2024-05-01T12:00:01"}"#;
+        let (parsed, errors) = parse_snapshots(event);
+        assert!(parsed.is_empty());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 1);
+    }
+
+    #[test]
+    fn carries_events_to_the_snapshot_of_an_earlier_part() {
+        let event = r#"{"Event": "checkpoint", "Timestamp": "2024-05-01T12:00:01"}"#;
+        let mut carry = EventCarry::default();
+        let mut first = parse_snapshot_part(&format!("{event}\n{SNAPSHOT}\n"));
+        assert!(carry.carry(&mut first).is_empty());
+        assert_eq!(first.snapshots[0].Events.len(), 1);
+
+        let mut second = parse_snapshot_part(event);
+        assert!(second.errors.is_empty());
+        assert_eq!(carry.carry(&mut second).len(), 1);
+        assert!(carry.finish().is_empty());
+    }
+
+    #[test]
+    fn keeps_jsonl_mentioning_trace_events() {
+        let line = r#"{"SchemaVersion": 1, "Timestamp": "2024-05-01T12:00:00", "ProcessTree": {"PID": 1, "Name": "init", "CMD": "dump \"traceEvents\""}}"#;
+        let content = format!("{line}\n{SNAPSHOT}\n");
+        assert!(!is_json_document(content.as_bytes()));
+        let (parsed, errors) = parse_snapshots(&content);
+        assert_eq!(parsed.len(), 2);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn converts_chrome_traces_with_leading_metadata() {
+        let content = r#"{
+            "displayTimeUnit": "ms",
+            "otherData": {"version": "1"},
+            "traceEvents": [
+                {"name": "step", "ph": "X", "ts": 0, "dur": 2000000, "pid": 1, "tid": 2}
+            ]
+        }"#;
+        assert!(is_json_document(content.as_bytes()));
+        let (parsed, errors) = parse_snapshots(content);
+        assert!(errors.is_empty());
+        assert!(!parsed.is_empty());
+        let processes = parsed[0]
+            .ProcessTree
+            .Children
+            .as_deref()
+            .unwrap_or_default();
+        let threads = processes[0].Threads.as_deref().unwrap_or_default();
+        assert_eq!(threads[0].State.as_deref(), Some("R"));
+    }
+
+    #[test]
+    fn parses_json_arrays_entry_by_entry() {
+        let content = format!("[{SNAPSHOT}, {{\"Timestamp\": 5}}]");
+        let (parsed, errors) = parse_snapshots(&content);
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 2);
+    }
+}
@@ -0,0 +1,176 @@
+use super::{synthesize_snapshots, ImportedProcess};
+use crate::model::Snapshot;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+/// Key of the event array in a Chrome trace object.
+pub const CHROME_TRACE_KEY: &str = "traceEvents";
+
+/// A record in the Chrome Trace Event Format (about://tracing JSON).
+#[derive(Debug, Deserialize)]
+struct ChromeTraceEvent {
+    #[serde(default)]
+    name: String,
+    ph: String,
+    /// Microseconds
+    #[serde(default)]
+    ts: f64,
+    #[serde(default)]
+    dur: f64,
+    #[serde(default)]
+    pid: u32,
+    #[serde(default)]
+    tid: u32,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+/// Convert a Chrome trace into synthetic snapshots sampled at a regular
+/// interval. A thread is shown as running (`R`) while any of its complete
+/// (`X`) or begin/end (`B`/`E`) events spans the sample time, and sleeping
+/// (`S`) otherwise. Returns `None` if `events` are not Chrome trace events.
+pub fn import_chrome_trace(events: &[serde_json::Value]) -> Option<Vec<Snapshot>> {
+    let events = events
+        .iter()
+        .map(ChromeTraceEvent::deserialize)
+        .collect::<Result<Vec<_>, _>>()
+        .ok()?;
+
+    let mut process_names: IndexMap<u32, String> = IndexMap::new();
+    let mut thread_names: IndexMap<(u32, u32), String> = IndexMap::new();
+    let mut intervals: IndexMap<(u32, u32), Vec<(f64, f64)>> = IndexMap::new();
+    let mut open: IndexMap<(u32, u32), Vec<f64>> = IndexMap::new();
+    for event in &events {
+        let key = (event.pid, event.tid);
+        let arg_name = || event.args["name"].as_str().map(str::to_string);
+        match event.ph.as_str() {
+            "X" => intervals
+                .entry(key)
+                .or_default()
+                .push((event.ts, event.ts + event.dur)),
+            "B" => open.entry(key).or_default().push(event.ts),
+            "E" => {
+                if let Some(start) = open.get_mut(&key).and_then(Vec::pop) {
+                    intervals.entry(key).or_default().push((start, event.ts));
+                }
+            }
+            "M" if event.name == "process_name" => {
+                if let Some(name) = arg_name() {
+                    process_names.insert(event.pid, name);
+                }
+                // Naming a process doesn't give it a thread
+                continue;
+            }
+            "M" if event.name == "thread_name" => {
+                if let Some(name) = arg_name() {
+                    thread_names.insert(key, name);
+                }
+            }
+            _ => continue,
+        }
+        process_names.entry(event.pid).or_default();
+        thread_names.entry(key).or_default();
+    }
+
+    let start = intervals.values().flatten().map(|i| i.0).reduce(f64::min)?;
+    let end = intervals.values().flatten().map(|i| i.1).reduce(f64::max)?;
+    let processes: Vec<ImportedProcess> = process_names
+        .into_iter()
+        .map(|(pid, name)| ImportedProcess {
+            pid,
+            ppid: None,
+            name,
+            cmd: None,
+            threads: thread_names
+                .iter()
+                .filter(|((p, _), _)| *p == pid)
+                .map(|(&(_, tid), name)| (tid, name.clone()))
+                .collect(),
+        })
+        .collect();
+
+    Some(synthesize_snapshots(
+        "Chrome trace",
+        &processes,
+        (start, end),
+        |pid, tid, t| {
+            let running = intervals
+                .get(&(pid, tid))
+                .is_some_and(|iv| iv.iter().any(|&(a, b)| a <= t && t <= b));
+            if running {
+                "R"
+            } else {
+                "S"
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_run_while_an_event_spans_the_sample() {
+        let events: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                {"name": "process_name", "ph": "M", "pid": 1, "args": {"name": "trainer"}},
+                {"name": "thread_name", "ph": "M", "pid": 1, "tid": 2, "args": {"name": "loader"}},
+                {"name": "load", "ph": "B", "ts": 0, "pid": 1, "tid": 2},
+                {"name": "load", "ph": "E", "ts": 1000, "pid": 1, "tid": 2},
+                {"name": "idle", "ph": "X", "ts": 1000, "dur": 1000, "pid": 1, "tid": 3}
+            ]"#,
+        )
+        .unwrap();
+        let snapshots = import_chrome_trace(&events).unwrap();
+        let thread = |snap: &Snapshot, tid: u32| {
+            let process = &snap.ProcessTree.Children.as_deref().unwrap_or_default()[0];
+            let threads = process.Threads.as_deref().unwrap_or_default();
+            threads.iter().find(|t| t.TID == tid).cloned().unwrap()
+        };
+        let state = |snap: &Snapshot, tid: u32| thread(snap, tid).State;
+        let (first, last) = (&snapshots[0], snapshots.last().unwrap());
+        assert_eq!(
+            first.ProcessTree.Children.as_deref().unwrap_or_default()[0].Name,
+            "trainer"
+        );
+        assert_eq!(thread(first, 2).Name.as_deref(), Some("loader"));
+        assert_eq!(state(first, 2).as_deref(), Some("R"));
+        assert_eq!(state(first, 3).as_deref(), Some("S"));
+        assert_eq!(state(last, 2).as_deref(), Some("S"));
+        assert_eq!(state(last, 3).as_deref(), Some("R"));
+    }
+
+    #[test]
+    fn naming_a_process_adds_no_thread() {
+        let events: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                {"name": "process_name", "ph": "M", "pid": 1, "args": {"name": "trainer"}},
+                {"name": "step", "ph": "X", "ts": 0, "dur": 1000, "pid": 1, "tid": 2}
+            ]"#,
+        )
+        .unwrap();
+        let snapshots = import_chrome_trace(&events).unwrap();
+        let process = &snapshots[0]
+            .ProcessTree
+            .Children
+            .as_deref()
+            .unwrap_or_default()[0];
+        let tids: Vec<_> = process
+            .Threads
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .map(|thread| thread.TID)
+            .collect();
+        assert_eq!(process.Name, "trainer");
+        assert_eq!(tids, [2]);
+    }
+
+    #[test]
+    fn rejects_other_json() {
+        let snapshots: Vec<serde_json::Value> =
+            serde_json::from_str(r#"[{"Timestamp": "2024-05-01T12:00:00"}]"#).unwrap();
+        assert!(import_chrome_trace(&snapshots).is_none());
+    }
+}
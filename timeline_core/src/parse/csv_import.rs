@@ -0,0 +1,237 @@
+use crate::model::{GPUStatus, Process, Snapshot, Thread};
+use indexmap::IndexMap;
+use std::rc::Rc;
+
+/// Fields that can be mapped from CSV columns when importing a flat trace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvField {
+    Timestamp,
+    Pid,
+    ProcessName,
+    Tid,
+    ThreadName,
+    State,
+    CpuCores,
+    GpuId,
+    GpuLoad,
+    GpuMemoryUsed,
+    GpuMemoryTotal,
+}
+
+impl CsvField {
+    pub const ALL: [CsvField; 11] = [
+        CsvField::Timestamp,
+        CsvField::Pid,
+        CsvField::ProcessName,
+        CsvField::Tid,
+        CsvField::ThreadName,
+        CsvField::State,
+        CsvField::CpuCores,
+        CsvField::GpuId,
+        CsvField::GpuLoad,
+        CsvField::GpuMemoryUsed,
+        CsvField::GpuMemoryTotal,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CsvField::Timestamp => "Timestamp",
+            CsvField::Pid => "PID",
+            CsvField::ProcessName => "Process name",
+            CsvField::Tid => "TID",
+            CsvField::ThreadName => "Thread name",
+            CsvField::State => "Thread state",
+            CsvField::CpuCores => "CPU cores",
+            CsvField::GpuId => "GPU ID",
+            CsvField::GpuLoad => "GPU load %",
+            CsvField::GpuMemoryUsed => "GPU memory used (MB)",
+            CsvField::GpuMemoryTotal => "GPU memory total (MB)",
+        }
+    }
+
+    /// Header names (lowercase) used to pre-select a column for this field.
+    fn aliases(self) -> &'static [&'static str] {
+        match self {
+            CsvField::Timestamp => &["timestamp", "time", "ts"],
+            CsvField::Pid => &["pid"],
+            CsvField::ProcessName => &["name", "process", "process_name", "command"],
+            CsvField::Tid => &["tid"],
+            CsvField::ThreadName => &["thread", "thread_name"],
+            CsvField::State => &["state", "status"],
+            CsvField::CpuCores => &["cpu_cores", "cpu_cores_total", "cores"],
+            CsvField::GpuId => &["gpu", "gpu_id"],
+            CsvField::GpuLoad => &["gpu_load", "load_percent", "gpu_util"],
+            CsvField::GpuMemoryUsed => &["memory_used_mb", "gpu_memory_used"],
+            CsvField::GpuMemoryTotal => &["memory_total_mb", "gpu_memory_total"],
+        }
+    }
+}
+
+/// A CSV file awaiting column mapping before it is converted into snapshots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvImport {
+    pub file_name: String,
+    pub content: Rc<String>,
+    pub headers: Vec<String>,
+    /// Selected column index for each entry of `CsvField::ALL`.
+    pub mapping: Vec<Option<usize>>,
+}
+
+impl CsvImport {
+    pub fn new(file_name: String, content: String) -> Result<Self, csv::Error> {
+        let headers: Vec<String> = csv::Reader::from_reader(content.as_bytes())
+            .headers()?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+        let mapping = CsvField::ALL
+            .iter()
+            .map(|field| {
+                headers
+                    .iter()
+                    .position(|h| field.aliases().contains(&h.to_lowercase().as_str()))
+            })
+            .collect();
+        Ok(Self {
+            file_name,
+            content: Rc::new(content),
+            headers,
+            mapping,
+        })
+    }
+
+    fn column(&self, field: CsvField) -> Option<usize> {
+        let index = CsvField::ALL.iter().position(|&f| f == field)?;
+        self.mapping[index]
+    }
+
+    /// Build one snapshot per distinct timestamp. Processes are attached as
+    /// children of a synthetic root named after the file, since a flat CSV
+    /// carries no parent/child relationships.
+    pub fn to_snapshots(&self) -> Result<Vec<Snapshot>, String> {
+        let timestamp_col = self
+            .column(CsvField::Timestamp)
+            .ok_or("A timestamp column is required")?;
+        let pid_col = self
+            .column(CsvField::Pid)
+            .ok_or("A PID column is required")?;
+
+        let mut snapshots: IndexMap<String, (IndexMap<u32, Process>, Snapshot)> = IndexMap::new();
+        let mut reader = csv::Reader::from_reader(self.content.as_bytes());
+        for (line, record) in reader.records().enumerate() {
+            let record = record.map_err(|e| e.to_string())?;
+            let get = |field: CsvField| {
+                self.column(field)
+                    .and_then(|col| record.get(col))
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+            };
+            let Some(timestamp) = record.get(timestamp_col).map(str::trim) else {
+                continue;
+            };
+            let (processes, snapshot) =
+                snapshots.entry(timestamp.to_string()).or_insert_with(|| {
+                    (
+                        IndexMap::new(),
+                        Snapshot {
+                            Timestamp: timestamp.to_string(),
+                            ..Default::default()
+                        },
+                    )
+                });
+            if let Some(cores) = get(CsvField::CpuCores).and_then(|v| v.parse().ok()) {
+                snapshot.CPU_Cores_Total = cores;
+            }
+
+            if let Some(pid) = record
+                .get(pid_col)
+                .and_then(|v| v.trim().parse::<u32>().ok())
+            {
+                let process = processes.entry(pid).or_insert_with(|| Process {
+                    PID: pid,
+                    Name: get(CsvField::ProcessName).unwrap_or_default().to_string(),
+                    ..Default::default()
+                });
+                if let Some(tid) = get(CsvField::Tid).and_then(|v| v.parse::<u32>().ok()) {
+                    process.Threads.get_or_insert_with(Vec::new).push(Thread {
+                        TID: tid,
+                        Name: get(CsvField::ThreadName).map(str::to_string),
+                        State: get(CsvField::State).map(str::to_string),
+                        ..Default::default()
+                    });
+                }
+            } else {
+                log::warn!("CSV row {}: missing or invalid PID", line + 2);
+            }
+
+            if let Some(gpu_id) = get(CsvField::GpuId).and_then(|v| v.parse::<u32>().ok()) {
+                if !snapshot.GPUStatus.iter().any(|gpu| gpu.GPU_ID == gpu_id) {
+                    let number = |field| get(field).and_then(|v| v.parse().ok()).unwrap_or(0.0);
+                    snapshot.GPUStatus.push(GPUStatus {
+                        GPU_ID: gpu_id,
+                        Load_Percent: number(CsvField::GpuLoad),
+                        Memory_Used_MB: number(CsvField::GpuMemoryUsed),
+                        Memory_Total_MB: number(CsvField::GpuMemoryTotal),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        Ok(snapshots
+            .into_values()
+            .map(|(processes, mut snapshot)| {
+                snapshot.ProcessTree = Process {
+                    PID: 0,
+                    Name: self.file_name.clone(),
+                    Children: Some(processes.into_values().collect()),
+                    ..Default::default()
+                };
+                snapshot
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_rows_into_snapshots_by_timestamp() {
+        let content = "timestamp,pid,name,tid,state,gpu,gpu_load\n\
+                       2024-05-01T12:00:00,7,python,7,R,0,80\n\
+                       2024-05-01T12:00:00,7,python,8,S,0,80\n\
+                       2024-05-01T12:00:01,7,python,7,S,0,10\n";
+        let import = CsvImport::new("run.csv".to_string(), content.to_string()).unwrap();
+        assert_eq!(import.column(CsvField::State), Some(4));
+        assert_eq!(import.column(CsvField::CpuCores), None);
+
+        let snapshots = import.to_snapshots().unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].ProcessTree.Name, "run.csv");
+        let process = &snapshots[0]
+            .ProcessTree
+            .Children
+            .as_deref()
+            .unwrap_or_default()[0];
+        assert_eq!(process.Name, "python");
+        let states: Vec<_> = process
+            .Threads
+            .iter()
+            .flatten()
+            .map(|t| (t.TID, t.State.as_deref()))
+            .collect();
+        assert_eq!(states, [(7, Some("R")), (8, Some("S"))]);
+        assert_eq!(snapshots[0].GPUStatus.len(), 1);
+        assert_eq!(snapshots[1].GPUStatus[0].Load_Percent, 10.0);
+    }
+
+    #[test]
+    fn requires_a_pid_column() {
+        let mut import =
+            CsvImport::new("run.csv".to_string(), "timestamp,pid\n1,2\n".to_string()).unwrap();
+        import.mapping[1] = None;
+        assert!(import.to_snapshots().is_err());
+    }
+}
@@ -0,0 +1,480 @@
+use super::{synthesize_snapshots, ImportedProcess};
+use crate::model::Snapshot;
+use indexmap::IndexMap;
+
+/// A decoded protobuf field value.
+enum ProtoValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+/// Minimal protobuf wire-format reader, enough to walk the Perfetto messages
+/// used by the importer without generated bindings.
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self.buf.split_first()?;
+            self.buf = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < len {
+            return None;
+        }
+        let (head, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Some(head)
+    }
+}
+
+impl<'a> Iterator for ProtoReader<'a> {
+    type Item = (u32, ProtoValue<'a>);
+
+    /// Yields `(field number, value)` pairs, stopping at the end of the
+    /// buffer or at the first malformed field.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let key = self.varint()?;
+        let field = (key >> 3) as u32;
+        let value = match key & 0x7 {
+            0 => ProtoValue::Varint(self.varint()?),
+            1 => ProtoValue::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().ok()?)),
+            2 => {
+                let len = self.varint()? as usize;
+                ProtoValue::Bytes(self.take(len)?)
+            }
+            5 => ProtoValue::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().ok()?)),
+            _ => {
+                self.buf = &[];
+                return None;
+            }
+        };
+        Some((field, value))
+    }
+}
+
+impl ProtoValue<'_> {
+    fn as_u64(&self) -> u64 {
+        match *self {
+            ProtoValue::Varint(v) | ProtoValue::Fixed64(v) => v,
+            ProtoValue::Fixed32(v) => u64::from(v),
+            ProtoValue::Bytes(_) => 0,
+        }
+    }
+
+    fn as_str(&self) -> String {
+        match self {
+            ProtoValue::Bytes(b) => String::from_utf8_lossy(b).into_owned(),
+            _ => String::new(),
+        }
+    }
+
+    /// Values of a repeated scalar field, which may be packed or not.
+    fn repeated_varints(&self) -> Vec<u64> {
+        match self {
+            ProtoValue::Bytes(b) => {
+                let mut reader = ProtoReader::new(b);
+                std::iter::from_fn(|| reader.varint()).collect()
+            }
+            other => vec![other.as_u64()],
+        }
+    }
+}
+
+/// Per-thread scheduling state changes and names collected from a Perfetto trace.
+#[derive(Default)]
+struct PerfettoSched {
+    /// TID -> `(timestamp ns, state letter)`
+    transitions: IndexMap<u32, Vec<(u64, &'static str)>>,
+    comms: IndexMap<u32, String>,
+    /// CPU -> TID currently running on it
+    running: IndexMap<u32, u32>,
+}
+
+impl PerfettoSched {
+    fn switch(&mut self, cpu: u32, ts: u64, prev: Option<(u32, i64)>, next: (u32, String)) {
+        let prev = prev.or_else(|| self.running.get(&cpu).map(|&tid| (tid, 0)));
+        if let Some((prev_tid, prev_state)) = prev {
+            if prev_tid != 0 {
+                let state = linux_task_state(prev_state);
+                self.transitions
+                    .entry(prev_tid)
+                    .or_default()
+                    .push((ts, state));
+            }
+        }
+        let (next_tid, next_comm) = next;
+        if next_tid != 0 {
+            self.transitions
+                .entry(next_tid)
+                .or_default()
+                .push((ts, "R"));
+            if !next_comm.is_empty() {
+                self.comms.insert(next_tid, next_comm);
+            }
+        }
+        self.running.insert(cpu, next_tid);
+    }
+}
+
+/// Map a kernel `prev_state` bitmask to the `/proc` state letter.
+fn linux_task_state(state: i64) -> &'static str {
+    match state {
+        0 => "R",
+        // Preempted while runnable
+        s if s & 0x800 != 0 => "R",
+        s if s & 0x402 == 0x402 => "I",
+        s if s & 0x01 != 0 => "S",
+        s if s & 0x02 != 0 => "D",
+        s if s & 0x0c != 0 => "T",
+        s if s & 0x20 != 0 => "Z",
+        _ => "S",
+    }
+}
+
+/// Import scheduling slices and the process tree from a Perfetto protobuf
+/// trace. Only `sched_switch` (regular and compact encodings) and
+/// `process_tree` packets are read; everything else is skipped.
+pub fn import_perfetto_trace(bytes: &[u8]) -> Vec<Snapshot> {
+    const TRACE_PACKET: u32 = 1;
+    const PACKET_FTRACE_EVENTS: u32 = 1;
+    const PACKET_PROCESS_TREE: u32 = 2;
+
+    let mut sched = PerfettoSched::default();
+    // PID -> (PPID, cmdline)
+    let mut processes: IndexMap<u32, (Option<u32>, Vec<String>)> = IndexMap::new();
+    // TID -> (TGID, name)
+    let mut threads: IndexMap<u32, (u32, String)> = IndexMap::new();
+
+    for (field, packet) in ProtoReader::new(bytes) {
+        let ProtoValue::Bytes(packet) = packet else {
+            continue;
+        };
+        if field != TRACE_PACKET {
+            continue;
+        }
+        for (field, value) in ProtoReader::new(packet) {
+            let ProtoValue::Bytes(body) = value else {
+                continue;
+            };
+            match field {
+                PACKET_FTRACE_EVENTS => read_ftrace_bundle(body, &mut sched),
+                PACKET_PROCESS_TREE => {
+                    for (field, value) in ProtoReader::new(body) {
+                        let ProtoValue::Bytes(entry) = value else {
+                            continue;
+                        };
+                        let mut fields: IndexMap<u32, Vec<ProtoValue>> = IndexMap::new();
+                        for (f, v) in ProtoReader::new(entry) {
+                            fields.entry(f).or_default().push(v);
+                        }
+                        let get = |f: u32| fields.get(&f).and_then(|v| v.first());
+                        match field {
+                            // ProcessTree.Process { pid = 1; ppid = 2; cmdline = 3 }
+                            1 => {
+                                let Some(pid) = get(1).map(|v| v.as_u64() as u32) else {
+                                    continue;
+                                };
+                                let cmdline = fields
+                                    .get(&3)
+                                    .into_iter()
+                                    .flatten()
+                                    .map(ProtoValue::as_str)
+                                    .collect();
+                                processes.insert(pid, (get(2).map(|v| v.as_u64() as u32), cmdline));
+                            }
+                            // ProcessTree.Thread { tid = 1; name = 2; tgid = 3 }
+                            2 => {
+                                let Some(tid) = get(1).map(|v| v.as_u64() as u32) else {
+                                    continue;
+                                };
+                                let tgid = get(3).map_or(tid, |v| v.as_u64() as u32);
+                                let name = get(2).map(ProtoValue::as_str).unwrap_or_default();
+                                threads.insert(tid, (tgid, name));
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for transitions in sched.transitions.values_mut() {
+        transitions.sort_by_key(|&(ts, _)| ts);
+    }
+    let Some(start) = sched
+        .transitions
+        .values()
+        .filter_map(|t| t.first())
+        .map(|t| t.0)
+        .min()
+    else {
+        log::warn!("Perfetto trace contains no scheduling events");
+        return Vec::new();
+    };
+    let end = sched
+        .transitions
+        .values()
+        .filter_map(|t| t.last())
+        .map(|t| t.0)
+        .max()
+        .unwrap_or(start);
+
+    // Threads seen only in scheduling events are treated as their own process
+    for (&tid, comm) in &sched.comms {
+        threads.entry(tid).or_insert_with(|| (tid, comm.clone()));
+    }
+    for (&tid, &(tgid, _)) in &threads {
+        if sched.transitions.contains_key(&tid) {
+            processes.entry(tgid).or_insert_with(|| (None, Vec::new()));
+        }
+    }
+
+    let imported: Vec<ImportedProcess> = processes
+        .iter()
+        .map(|(&pid, (ppid, cmdline))| {
+            let main_thread = threads.get(&pid).map(|(_, name)| name.clone());
+            ImportedProcess {
+                pid,
+                ppid: *ppid,
+                name: cmdline
+                    .first()
+                    .and_then(|c| c.rsplit('/').next())
+                    .filter(|c| !c.is_empty())
+                    .map(str::to_string)
+                    .or(main_thread)
+                    .unwrap_or_default(),
+                cmd: (!cmdline.is_empty()).then(|| cmdline.join(" ")),
+                threads: threads
+                    .iter()
+                    .filter(|(_, (tgid, _))| *tgid == pid)
+                    .map(|(&tid, (_, name))| (tid, name.clone()))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    synthesize_snapshots(
+        "Perfetto trace",
+        &imported,
+        (start as f64 / 1000.0, end as f64 / 1000.0),
+        |_, tid, t| {
+            let ts = (t * 1000.0) as u64;
+            sched
+                .transitions
+                .get(&tid)
+                .and_then(|tr| {
+                    let idx = tr.partition_point(|&(at, _)| at <= ts);
+                    idx.checked_sub(1).map(|i| tr[i].1)
+                })
+                .unwrap_or("S")
+        },
+    )
+}
+
+/// Read `sched_switch` events from an `FtraceEventBundle`, in either the
+/// per-event encoding or the columnar `compact_sched` encoding.
+fn read_ftrace_bundle(bundle: &[u8], sched: &mut PerfettoSched) {
+    let mut cpu = 0;
+    let mut events = Vec::new();
+    let mut compact = None;
+    for (field, value) in ProtoReader::new(bundle) {
+        match (field, value) {
+            (1, v) => cpu = v.as_u64() as u32,
+            (2, ProtoValue::Bytes(event)) => events.push(event),
+            (4, ProtoValue::Bytes(c)) => compact = Some(c),
+            _ => {}
+        }
+    }
+
+    for event in events {
+        // FtraceEvent { timestamp = 1; sched_switch = 4 }
+        let mut ts = 0;
+        let mut switch = None;
+        for (field, value) in ProtoReader::new(event) {
+            match (field, value) {
+                (1, v) => ts = v.as_u64(),
+                (4, ProtoValue::Bytes(s)) => switch = Some(s),
+                _ => {}
+            }
+        }
+        let Some(switch) = switch else {
+            continue;
+        };
+        // SchedSwitchFtraceEvent { prev_comm = 1; prev_pid = 2; prev_state = 4;
+        //                          next_comm = 5; next_pid = 6 }
+        let (mut prev_comm, mut prev_pid, mut prev_state) = (String::new(), 0, 0);
+        let (mut next_comm, mut next_pid) = (String::new(), 0);
+        for (field, value) in ProtoReader::new(switch) {
+            match field {
+                1 => prev_comm = value.as_str(),
+                2 => prev_pid = value.as_u64() as u32,
+                4 => prev_state = value.as_u64() as i64,
+                5 => next_comm = value.as_str(),
+                6 => next_pid = value.as_u64() as u32,
+                _ => {}
+            }
+        }
+        if prev_pid != 0 && !prev_comm.is_empty() {
+            sched.comms.insert(prev_pid, prev_comm);
+        }
+        sched.switch(cpu, ts, Some((prev_pid, prev_state)), (next_pid, next_comm));
+    }
+
+    if let Some(compact) = compact {
+        // CompactSched { switch_timestamp = 1 (delta encoded); switch_prev_state = 2;
+        //                switch_next_pid = 3; intern_table = 5; switch_next_comm_index = 6 }
+        let mut timestamps = Vec::new();
+        let mut prev_states = Vec::new();
+        let mut next_pids = Vec::new();
+        let mut intern_table = Vec::new();
+        let mut comm_indices = Vec::new();
+        for (field, value) in ProtoReader::new(compact) {
+            match field {
+                1 => timestamps.extend(value.repeated_varints()),
+                2 => prev_states.extend(value.repeated_varints()),
+                3 => next_pids.extend(value.repeated_varints()),
+                5 => intern_table.push(value.as_str()),
+                6 => comm_indices.extend(value.repeated_varints()),
+                _ => {}
+            }
+        }
+        let mut ts = 0u64;
+        for (i, delta) in timestamps.into_iter().enumerate() {
+            ts = ts.wrapping_add(delta);
+            let prev_state = prev_states.get(i).copied().unwrap_or(0) as i64;
+            let prev = sched.running.get(&cpu).map(|&tid| (tid, prev_state));
+            let next_pid = next_pids.get(i).copied().unwrap_or(0) as u32;
+            let comm = comm_indices
+                .get(i)
+                .and_then(|&idx| intern_table.get(idx as usize))
+                .cloned()
+                .unwrap_or_default();
+            sched.switch(cpu, ts, prev, (next_pid, comm));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64, out: &mut Vec<u8>) {
+        while value >= 0x80 {
+            out.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn int_field(field: u32, value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint(u64::from(field) << 3, &mut out);
+        varint(value, &mut out);
+        out
+    }
+
+    fn bytes_field(field: u32, bytes: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        varint(u64::from(field) << 3 | 2, &mut out);
+        varint(bytes.len() as u64, &mut out);
+        out.extend_from_slice(bytes);
+        out
+    }
+
+    fn sched_switch(
+        ts: u64,
+        prev_pid: u64,
+        prev_state: u64,
+        next_pid: u64,
+        next_comm: &str,
+    ) -> Vec<u8> {
+        let switch = [
+            int_field(2, prev_pid),
+            int_field(4, prev_state),
+            bytes_field(5, next_comm.as_bytes()),
+            int_field(6, next_pid),
+        ]
+        .concat();
+        bytes_field(2, &[int_field(1, ts), bytes_field(4, &switch)].concat())
+    }
+
+    fn trace_packet(field: u32, body: &[u8]) -> Vec<u8> {
+        bytes_field(1, &bytes_field(field, body))
+    }
+
+    #[test]
+    fn preempted_tasks_stay_runnable() {
+        assert_eq!(linux_task_state(0x800), "R");
+        assert_eq!(linux_task_state(0x01), "S");
+        assert_eq!(linux_task_state(0x02), "D");
+    }
+
+    #[test]
+    fn imports_sched_switches_and_the_process_tree() {
+        let bundle = [
+            int_field(1, 0),
+            sched_switch(1000, 0, 0, 10, "app"),
+            sched_switch(2000, 10, 0x800, 11, "helper"),
+            sched_switch(3000, 11, 0x01, 10, "app"),
+        ]
+        .concat();
+        let tree = [
+            bytes_field(
+                1,
+                &[int_field(1, 10), bytes_field(3, b"/usr/bin/app")].concat(),
+            ),
+            bytes_field(
+                2,
+                &[
+                    int_field(1, 11),
+                    bytes_field(2, b"helper"),
+                    int_field(3, 10),
+                ]
+                .concat(),
+            ),
+        ]
+        .concat();
+        let trace = [trace_packet(2, &tree), trace_packet(1, &bundle)].concat();
+
+        let snapshots = import_perfetto_trace(&trace);
+        let last = snapshots.last().unwrap();
+        let processes = last.ProcessTree.Children.as_deref().unwrap_or_default();
+        assert_eq!(processes.len(), 1);
+        assert_eq!(processes[0].PID, 10);
+        assert_eq!(processes[0].Name, "app");
+        assert_eq!(processes[0].CMD.as_deref(), Some("/usr/bin/app"));
+        let states = |snap: &Snapshot| -> Vec<(u32, String)> {
+            snap.ProcessTree.Children.as_deref().unwrap_or_default()[0]
+                .Threads
+                .iter()
+                .flatten()
+                .map(|t| (t.TID, t.State.clone().unwrap_or_default()))
+                .collect()
+        };
+        let middle = &snapshots[snapshots.len() / 2 + 1];
+        assert_eq!(states(middle), [(11, "R".into()), (10, "R".into())]);
+        assert_eq!(states(last), [(11, "S".into()), (10, "R".into())]);
+    }
+}
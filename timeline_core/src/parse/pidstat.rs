@@ -0,0 +1,213 @@
+use crate::model::{Process, Snapshot, Thread};
+use chrono::{NaiveDate, NaiveTime};
+use indexmap::IndexMap;
+
+/// Threads above this `%CPU` in a pidstat interval are shown as running.
+const PIDSTAT_RUNNING_CPU_PERCENT: f64 = 50.0;
+
+/// pidstat reports start with the `uname` banner, e.g.
+/// `Linux 6.1.0 (host)  10/16/2026  _x86_64_  (8 CPU)`.
+pub fn is_pidstat_report(content: &[u8]) -> bool {
+    content.starts_with(b"Linux ")
+}
+
+/// Import the text output of `pidstat -t -p ALL <interval>` (with or without
+/// `-t`). Each reporting interval becomes one snapshot, with every process
+/// nested under a synthetic root and each thread's `%CPU` mapped to a running
+/// (`R`) or sleeping (`S`) state.
+pub fn import_pidstat(content: &str) -> Vec<Snapshot> {
+    let mut lines = content.lines();
+    let banner = lines.next().unwrap_or_default();
+    let cores = banner
+        .rsplit_once('(')
+        .and_then(|(_, cpus)| cpus.split_whitespace().next())
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(0);
+    let mut date = banner
+        .split_whitespace()
+        .find_map(|token| {
+            ["%m/%d/%Y", "%m/%d/%y", "%Y-%m-%d", "%d/%m/%Y"]
+                .iter()
+                .find_map(|fmt| NaiveDate::parse_from_str(token, fmt).ok())
+        })
+        .unwrap_or_default();
+
+    let mut columns: Vec<String> = Vec::new();
+    let mut snapshots: IndexMap<String, Vec<Process>> = IndexMap::new();
+    let mut last_time = None;
+    for line in lines {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() || tokens[0].ends_with(':') {
+            // Blank separators and the trailing "Average:" summary
+            continue;
+        }
+        // The time is `HH:MM:SS`, optionally followed by AM/PM
+        let time_tokens = if matches!(tokens.get(1), Some(&"AM") | Some(&"PM")) {
+            2
+        } else {
+            1
+        };
+        let fields = &tokens[time_tokens.min(tokens.len())..];
+        if fields.contains(&"TID") || fields.contains(&"PID") {
+            columns = fields.iter().map(|f| f.to_string()).collect();
+            continue;
+        }
+        let column = |name: &str| {
+            columns
+                .iter()
+                .position(|c| c == name)
+                .and_then(|i| fields.get(i).copied())
+        };
+        let time_str = tokens[..time_tokens].join(" ");
+        let Ok(time) = NaiveTime::parse_from_str(&time_str, "%I:%M:%S %p")
+            .or_else(|_| NaiveTime::parse_from_str(&time_str, "%H:%M:%S"))
+        else {
+            continue;
+        };
+        if last_time.is_some_and(|last| time < last) {
+            // Crossed midnight
+            date = date.succ_opt().unwrap_or(date);
+        }
+        last_time = Some(time);
+        let timestamp = date.and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string();
+
+        // The command comes last, and with `-l` its arguments contain spaces
+        let Some(command) = fields
+            .get(columns.len().saturating_sub(1)..)
+            .filter(|command| !command.is_empty())
+            .map(|command| command.join(" "))
+        else {
+            continue;
+        };
+        let cpu = column("%CPU")
+            .and_then(|v| v.parse::<f64>().ok())
+            .unwrap_or(0.0);
+        // Present with `-r`, in kB
+        let rss_mb = column("RSS")
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|kb| kb / 1024.0);
+        let state = if cpu >= PIDSTAT_RUNNING_CPU_PERCENT {
+            "R"
+        } else {
+            "S"
+        };
+        let pid = column("TGID")
+            .or_else(|| column("PID"))
+            .and_then(|v| v.parse::<u32>().ok());
+        let tid = column("TID").and_then(|v| v.parse::<u32>().ok());
+
+        let processes = snapshots.entry(timestamp).or_default();
+        match (pid, tid) {
+            (Some(pid), _) => processes.push(Process {
+                PID: pid,
+                Name: command.clone(),
+                CPU_Percent: Some(cpu),
+                Memory_RSS_MB: rss_mb,
+                // Without `-t` there are no thread rows, so the process stands in
+                Threads: Some(vec![Thread {
+                    TID: pid,
+                    Name: Some(command.clone()),
+                    State: Some(state.to_string()),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+            (None, Some(tid)) => {
+                if let Some(process) = processes.last_mut() {
+                    let threads = process.Threads.get_or_insert_with(Vec::new);
+                    // Replace the stand-in thread once real thread rows appear
+                    threads.retain(|t| t.TID != tid);
+                    threads.push(Thread {
+                        TID: tid,
+                        Name: Some(command.trim_start_matches("|__").to_string()),
+                        State: Some(state.to_string()),
+                        ..Default::default()
+                    });
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    snapshots
+        .into_iter()
+        .map(|(timestamp, processes)| Snapshot {
+            Timestamp: timestamp,
+            ProcessTree: Process {
+                PID: 0,
+                Name: "pidstat".to_string(),
+                Children: Some(processes),
+                ..Default::default()
+            },
+            CPU_Cores_Total: cores,
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "Linux 6.1.0 (host) \t10/16/2026 \t_x86_64_\t(8 CPU)
+
+11:59:59 PM   UID      TGID       TID    %usr %system  %guest   %wait    %CPU   CPU  Command
+11:59:59 PM  1000      4242         -   90.00    5.00    0.00    0.00   95.00     1  python
+11:59:59 PM  1000         -      4242   90.00    5.00    0.00    0.00   95.00     1  |__python
+11:59:59 PM  1000         -      4243    1.00    0.00    0.00    0.00    1.00     2  |__loader
+
+12:00:00 AM   UID      TGID       TID    %usr %system  %guest   %wait    %CPU   CPU  Command
+12:00:00 AM  1000      4242         -    2.00    0.00    0.00    0.00    2.00     1  python
+12:00:00 AM  1000         -      4242    2.00    0.00    0.00    0.00    2.00     1  |__python
+
+Average:     1000      4242         -   46.00    2.50    0.00    0.00   48.50     -  python
+";
+
+    #[test]
+    fn reads_each_interval_as_a_snapshot() {
+        assert!(is_pidstat_report(REPORT.as_bytes()));
+        let snapshots = import_pidstat(REPORT);
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].Timestamp, "2026-10-16T23:59:59");
+        // Crossing midnight moves to the next day
+        assert_eq!(snapshots[1].Timestamp, "2026-10-17T00:00:00");
+        assert_eq!(snapshots[0].CPU_Cores_Total, 8);
+
+        let process = &snapshots[0]
+            .ProcessTree
+            .Children
+            .as_deref()
+            .unwrap_or_default()[0];
+        assert_eq!(process.PID, 4242);
+        assert_eq!(process.CPU_Percent, Some(95.0));
+        let threads: Vec<_> = process
+            .Threads
+            .iter()
+            .flatten()
+            .map(|t| (t.TID, t.Name.as_deref(), t.State.as_deref()))
+            .collect();
+        assert_eq!(
+            threads,
+            [
+                (4242, Some("python"), Some("R")),
+                (4243, Some("loader"), Some("S"))
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_arguments_of_long_commands() {
+        let report = "Linux 6.1.0 (host) \t10/16/2026 \t_x86_64_\t(8 CPU)
+
+12:00:00     UID       PID    %usr %system  %guest   %wait    %CPU   CPU  Command
+12:00:00    1000      4242   90.00    5.00    0.00    0.00   95.00     1  python train.py --epochs 3
+";
+        let snapshots = import_pidstat(report);
+        let process = &snapshots[0]
+            .ProcessTree
+            .Children
+            .as_deref()
+            .unwrap_or_default()[0];
+        assert_eq!(process.Name, "python train.py --epochs 3");
+    }
+}
@@ -0,0 +1,169 @@
+use crate::echarts::{LineType, Series, TimePoints};
+
+/// Convert samples of a cumulative counter into per-second rates between
+/// consecutive samples, using the snapshot times in `micros`.
+pub fn counter_rates(points: &[(usize, f64)], micros: &[Option<f64>]) -> Vec<(usize, f64)> {
+    points
+        .windows(2)
+        .filter_map(|pair| {
+            let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+            let elapsed_secs = (micros[t1]? - micros[t0]?) / 1e6;
+            // Counters reset when a PID is reused; skip rather than go negative
+            (elapsed_secs > 0.0 && v1 >= v0).then(|| (t1, (v1 - v0) / elapsed_secs))
+        })
+        .collect()
+}
+
+/// Map [snapshot index, value] points onto the time axis (ms since the
+/// epoch, as in `times`), breaking the line where samples are missing.
+fn at_times(points: &[(usize, f64)], times: &[Option<f64>]) -> TimePoints {
+    let timed: Vec<(f64, f64)> = points
+        .iter()
+        .filter_map(|&(i, value)| Some((times.get(i).copied().flatten()?, value)))
+        .collect();
+    let mut steps: Vec<f64> = timed
+        .windows(2)
+        .map(|pair| pair[1].0 - pair[0].0)
+        .filter(|&step| step > 0.0)
+        .collect();
+    steps.sort_by(f64::total_cmp);
+    // More than one and a half typical sampling intervals counts as a gap
+    let gap = steps
+        .get(steps.len() / 2)
+        .map_or(f64::INFINITY, |step| step * 1.5);
+    let mut mapped = Vec::with_capacity(timed.len());
+    for (k, &(t, value)) in timed.iter().enumerate() {
+        if k > 0 && t - timed[k - 1].0 > gap {
+            mapped.push(((t + timed[k - 1].0) / 2.0, None));
+        }
+        mapped.push((t, Some(value)));
+    }
+    mapped
+}
+
+/// ECharts line series on the time axis, named without any heatmap tree indentation.
+pub fn line_series(
+    label: &str,
+    suffix: &str,
+    points: &[(usize, f64)],
+    times: &[Option<f64>],
+) -> Series {
+    let name = format!("{}{suffix}", label.trim().trim_start_matches("└─ "));
+    Series::line(&name, at_times(points, times))
+}
+
+/// Like `line_series`, drawn dashed for reference values such as limits.
+pub fn dashed_line_series(
+    label: &str,
+    suffix: &str,
+    points: &[(usize, f64)],
+    times: &[Option<f64>],
+) -> Series {
+    line_series(label, suffix, points, times).with_line_type(LineType::Dashed)
+}
+
+/// Trailing moving average over the last `window` samples; 1 leaves the
+/// points as they are.
+pub fn moving_average<X: Copy>(points: &[(X, f64)], window: usize) -> Vec<(X, f64)> {
+    let mut sum = 0.0;
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, &(x, value))| {
+            sum += value;
+            if i >= window {
+                sum -= points[i - window].1;
+            }
+            (x, sum / (i + 1).min(window) as f64)
+        })
+        .collect()
+}
+
+/// Fewest samples before a steadily rising descriptor count is flagged.
+const FD_LEAK_MIN_SAMPLES: usize = 5;
+
+/// Whether a series never decreases and ends higher than it started, as an
+/// fd leak does.
+pub fn grows_monotonically(points: &[(usize, f64)]) -> bool {
+    points.len() >= FD_LEAK_MIN_SAMPLES
+        && points.windows(2).all(|w| w[1].1 >= w[0].1)
+        && points.last().map(|p| p.1) > points.first().map(|p| p.1)
+}
+
+/// Largest-triangle-three-buckets downsampling of an unbroken line to at most
+/// `threshold` points, keeping the ends and the points that shape it most.
+fn lttb(points: &[(f64, f64)], threshold: usize) -> Vec<(f64, f64)> {
+    if threshold >= points.len() || threshold < 3 {
+        return points.to_vec();
+    }
+    let bucket_size = (points.len() - 2) as f64 / (threshold - 2) as f64;
+    let bucket_start = |bucket: usize| (bucket as f64 * bucket_size) as usize + 1;
+    let mut sampled = Vec::with_capacity(threshold);
+    sampled.push(points[0]);
+    let mut previous = points[0];
+    for bucket in 0..threshold - 2 {
+        // The third corner of the triangle is the mean of the next bucket
+        let next = &points[bucket_start(bucket + 1)..bucket_start(bucket + 2).min(points.len())];
+        let mean_x = next.iter().map(|p| p.0).sum::<f64>() / next.len() as f64;
+        let mean_y = next.iter().map(|p| p.1).sum::<f64>() / next.len() as f64;
+        let (ax, ay) = previous;
+        previous = points[bucket_start(bucket)..bucket_start(bucket + 1)]
+            .iter()
+            .copied()
+            .max_by(|&(bx, by), &(cx, cy)| {
+                let area_b = ((ax - mean_x) * (by - ay) - (ax - bx) * (mean_y - ay)).abs();
+                let area_c = ((ax - mean_x) * (cy - ay) - (ax - cx) * (mean_y - ay)).abs();
+                area_b.total_cmp(&area_c)
+            })
+            .unwrap_or(previous);
+        sampled.push(previous);
+    }
+    sampled.push(points[points.len() - 1]);
+    sampled
+}
+
+/// Downsample a line on the time axis to about `max_points` points with
+/// `lttb`, sharing them out between the stretches between gaps.
+pub fn downsample(points: &[(f64, Option<f64>)], max_points: usize) -> TimePoints {
+    if points.len() <= max_points {
+        return points.to_vec();
+    }
+    let total = points.iter().filter(|p| p.1.is_some()).count().max(1);
+    let mut sampled = Vec::new();
+    for stretch in points.split_inclusive(|p| p.1.is_none()) {
+        let (line, gap) = match stretch.split_last() {
+            Some((&gap @ (_, None), line)) => (line, Some(gap)),
+            _ => (stretch, None),
+        };
+        let line: Vec<(f64, f64)> = line.iter().filter_map(|&(x, y)| Some((x, y?))).collect();
+        let budget = (max_points * line.len() / total).max(3);
+        sampled.extend(lttb(&line, budget).into_iter().map(|(x, y)| (x, Some(y))));
+        sampled.extend(gap);
+    }
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downsample_keeps_short_lines() {
+        let points = vec![(0.0, Some(1.0)), (1.0, None), (2.0, Some(3.0))];
+        assert_eq!(downsample(&points, 10), points);
+    }
+
+    #[test]
+    fn downsample_keeps_ends_peaks_and_gaps() {
+        let mut points: Vec<(f64, Option<f64>)> =
+            (0..1000).map(|i| (i as f64, Some(0.0))).collect();
+        points[300].1 = Some(100.0);
+        points[500].1 = None;
+        let sampled = downsample(&points, 100);
+        assert!(sampled.len() <= 100);
+        assert_eq!(sampled.first(), points.first());
+        assert_eq!(sampled.last(), points.last());
+        assert!(sampled.contains(&(300.0, Some(100.0))));
+        assert!(sampled.contains(&(500.0, None)));
+    }
+}
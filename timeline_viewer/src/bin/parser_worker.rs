@@ -0,0 +1,3 @@
+fn main() {
+    timeline_viewer::start_parser_worker();
+}
@@ -0,0 +1,607 @@
+use crate::components::report_error;
+use crate::panels::{panel, PANELS};
+use crate::settings::Theme;
+use gloo::storage::{LocalStorage, Storage};
+use js_sys::Function;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::BTreeSet;
+use timeline_core::columns::TraceColumns;
+use timeline_core::echarts::{Axis, ChartOption};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, HtmlElement};
+
+#[wasm_bindgen]
+extern "C" {
+    /// A chart instance of the ECharts library loaded by index.html.
+    #[derive(Clone, PartialEq)]
+    pub(crate) type ECharts;
+
+    #[wasm_bindgen(catch, js_namespace = echarts, js_name = init)]
+    fn echarts_init(dom: &Element, theme: JsValue) -> Result<ECharts, JsValue>;
+
+    /// A chart without a div, rendered to a string with `opts.renderer`.
+    #[wasm_bindgen(catch, js_namespace = echarts, js_name = init)]
+    pub(crate) fn echarts_init_detached(
+        dom: JsValue,
+        theme: JsValue,
+        opts: &JsValue,
+    ) -> Result<ECharts, JsValue>;
+
+    #[wasm_bindgen(catch, js_namespace = echarts, js_name = getInstanceByDom)]
+    fn echarts_instance_by_dom(dom: &Element) -> Result<Option<ECharts>, JsValue>;
+
+    #[wasm_bindgen(js_namespace = echarts, js_name = dispose)]
+    fn echarts_dispose(dom: &Element);
+
+    /// Relays the actions of the charts in `group`, such as zooming and
+    /// moving the crosshair, to the others in it.
+    #[wasm_bindgen(js_namespace = echarts, js_name = connect)]
+    pub(crate) fn echarts_connect(group: &str);
+
+    #[wasm_bindgen(method, js_name = setOption)]
+    pub(crate) fn set_option(this: &ECharts, option: &JsValue);
+
+    #[wasm_bindgen(method, js_name = setOption)]
+    fn update_option(this: &ECharts, option: &JsValue, settings: &JsValue);
+
+    /// Adds points to the end of a series, leaving the rest of the chart as
+    /// it is.
+    #[wasm_bindgen(method, js_name = appendData)]
+    fn append_data(this: &ECharts, params: &JsValue);
+
+    #[wasm_bindgen(method, js_name = getOption)]
+    pub(crate) fn get_option(this: &ECharts) -> JsValue;
+
+    #[wasm_bindgen(method)]
+    pub(crate) fn resize(this: &ECharts);
+
+    #[wasm_bindgen(method)]
+    pub(crate) fn dispose(this: &ECharts);
+
+    /// Charts of the same group are kept in step once connected
+    #[wasm_bindgen(method, getter)]
+    pub(crate) fn group(this: &ECharts) -> Option<String>;
+
+    #[wasm_bindgen(method, setter)]
+    pub(crate) fn set_group(this: &ECharts, group: &str);
+
+    #[wasm_bindgen(method)]
+    fn on(this: &ECharts, event: &str, handler: &Function);
+
+    #[wasm_bindgen(method)]
+    fn off(this: &ECharts, event: &str);
+
+    #[wasm_bindgen(method, js_name = dispatchAction)]
+    fn dispatch_action(this: &ECharts, action: &JsValue);
+
+    /// Pixel position of `value` on the axis or series found by `finder`.
+    #[wasm_bindgen(method, js_name = convertToPixel)]
+    pub(crate) fn convert_to_pixel(this: &ECharts, finder: &JsValue, value: &JsValue) -> JsValue;
+
+    #[wasm_bindgen(method, js_name = convertFromPixel)]
+    pub(crate) fn convert_from_pixel(this: &ECharts, finder: &JsValue, point: &JsValue) -> JsValue;
+
+    #[wasm_bindgen(method, js_name = getWidth)]
+    pub(crate) fn width(this: &ECharts) -> f64;
+
+    #[wasm_bindgen(method, js_name = getHeight)]
+    pub(crate) fn height(this: &ECharts) -> f64;
+
+    #[wasm_bindgen(method, js_name = getDom)]
+    pub(crate) fn dom(this: &ECharts) -> Element;
+
+    #[wasm_bindgen(method, js_name = getDataURL)]
+    pub(crate) fn data_url(this: &ECharts, opts: &JsValue) -> String;
+
+    #[wasm_bindgen(method, js_name = renderToSVGString)]
+    pub(crate) fn render_to_svg_string(this: &ECharts) -> String;
+
+    /// Renderer of a chart, which reports events anywhere on its canvas.
+    #[wasm_bindgen(method, js_name = getZr)]
+    fn zrender(this: &ECharts) -> ZRender;
+
+    type ZRender;
+
+    #[wasm_bindgen(method, js_name = on)]
+    fn zr_on(this: &ZRender, event: &str, handler: &Function);
+
+    #[wasm_bindgen(method, js_name = off)]
+    fn zr_off(this: &ZRender, event: &str);
+}
+
+/// Closures handed to the charts, which live as long as the `Callbacks`
+/// holding them. Each draw keeps those of its charts until the next one.
+#[derive(Default)]
+pub(crate) struct Callbacks(Vec<Box<dyn Any>>);
+
+impl Callbacks {
+    /// A JS function calling `closure`.
+    pub(crate) fn function<F: ?Sized + 'static>(&mut self, closure: Closure<F>) -> Function {
+        let function = closure.as_ref().unchecked_ref::<Function>().clone();
+        self.0.push(Box::new(closure));
+        function
+    }
+}
+
+impl ECharts {
+    /// Call `handler` with the parameters of each `event`, replacing the
+    /// handlers bound by earlier draws.
+    pub(crate) fn bind(
+        &self,
+        event: &str,
+        callbacks: &mut Callbacks,
+        handler: impl FnMut(JsValue) + 'static,
+    ) {
+        self.off(event);
+        self.on(
+            event,
+            &callbacks.function(Closure::<dyn FnMut(JsValue)>::new(handler)),
+        );
+    }
+
+    /// Like `bind`, for the events anywhere on the canvas (e.g. "dblclick"),
+    /// whose parameters carry the pixel position rather than an item.
+    pub(crate) fn bind_canvas(
+        &self,
+        event: &str,
+        callbacks: &mut Callbacks,
+        handler: impl FnMut(JsValue) + 'static,
+    ) {
+        let zrender = self.zrender();
+        zrender.zr_off(event);
+        zrender.zr_on(
+            event,
+            &callbacks.function(Closure::<dyn FnMut(JsValue)>::new(handler)),
+        );
+    }
+
+    pub(crate) fn dispatch(&self, action: &Action) {
+        if let Some(action) = to_js(action) {
+            self.dispatch_action(&action);
+        }
+    }
+
+    /// Merge `option` into the chart's.
+    pub(crate) fn merge_option(&self, option: &impl Serialize) {
+        if let Some(option) = to_js(option) {
+            self.set_option(&option);
+        }
+    }
+
+    /// Parts of the chart's option the charts are linked by.
+    pub(crate) fn drawn_option(&self) -> DrawnOption {
+        serde_wasm_bindgen::from_value(self.get_option()).unwrap_or_default()
+    }
+}
+
+/// Actions dispatched to a chart, as if the user had taken them.
+#[derive(Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub(crate) enum Action {
+    /// Move the crosshair to pixel (x, y), or hide it on `Leave`
+    UpdateAxisPointer {
+        curr_trigger: PointerTrigger,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        x: Option<f64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        y: Option<f64>,
+    },
+    HideTip,
+    /// Zoom the x axis to [start_value, end_value]
+    DataZoom {
+        start_value: f64,
+        end_value: f64,
+    },
+    /// Emphasize items of a series, dimming the rest
+    Highlight {
+        series_index: usize,
+        data_index: Vec<usize>,
+        /// Not relayed to the charts of the same group, whose items differ
+        escape_connect: bool,
+    },
+    Downplay {
+        series_index: usize,
+        data_index: Vec<usize>,
+        escape_connect: bool,
+    },
+    /// Have dragging draw a brush rather than pan
+    TakeGlobalCursor {
+        key: &'static str,
+        brush_option: BrushOption,
+    },
+    /// Draw the brush areas
+    Brush {
+        areas: Vec<BrushArea>,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PointerTrigger {
+    Mousemove,
+    Leave,
+}
+
+/// How dragging across a chart brushes it.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrushOption {
+    pub(crate) brush_type: &'static str,
+    /// "single" replaces the brushed range rather than adding another
+    pub(crate) brush_mode: &'static str,
+}
+
+/// A range brushed across the x axis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrushArea {
+    #[serde(default)]
+    pub(crate) brush_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) x_axis_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) coord_range: Option<(f64, f64)>,
+}
+
+/// Parameters of a "brushEnd" event, with no areas once the brush is cleared.
+#[derive(Debug, Deserialize)]
+pub(crate) struct BrushEvent {
+    #[serde(default)]
+    pub(crate) areas: Vec<BrushArea>,
+}
+
+/// Picks the x or y axis to convert a value on.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum AxisFinder {
+    XAxisIndex(usize),
+    YAxisIndex(usize),
+}
+
+/// Options of `echarts_init_detached`, e.g. to render SVG offscreen.
+#[derive(Serialize)]
+pub(crate) struct InitOptions {
+    /// "canvas" or "svg"
+    pub(crate) renderer: &'static str,
+    /// Renders to a string rather than into an element
+    pub(crate) ssr: bool,
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+}
+
+/// Options of `ECharts::data_url`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataUrlOptions<'a> {
+    /// "png" or "jpeg"
+    #[serde(rename = "type")]
+    pub(crate) kind: &'a str,
+    pub(crate) pixel_ratio: f64,
+    pub(crate) background_color: &'a str,
+    /// Components left out of the image, e.g. "toolbox"
+    pub(crate) exclude_components: &'a [&'a str],
+}
+
+/// The parts of a drawn chart's option read back from it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DrawnOption {
+    #[serde(default)]
+    pub(crate) x_axis: Vec<DrawnAxis>,
+    #[serde(default)]
+    pub(crate) tooltip: Vec<DrawnTooltip>,
+    #[serde(default)]
+    pub(crate) data_zoom: Vec<DrawnZoom>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct DrawnAxis {
+    #[serde(rename = "type", default)]
+    pub(crate) kind: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct DrawnTooltip {
+    #[serde(default)]
+    pub(crate) trigger: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DrawnZoom {
+    #[serde(default)]
+    pub(crate) start_value: Option<f64>,
+    #[serde(default)]
+    pub(crate) end_value: Option<f64>,
+}
+
+impl DrawnOption {
+    pub(crate) fn has_time_axis(&self) -> bool {
+        self.x_axis.first().is_some_and(|axis| axis.kind == "time")
+    }
+}
+
+/// `value` as a plain JS object for ECharts, which doesn't read Maps.
+pub(crate) fn to_js(value: &impl Serialize) -> Option<JsValue> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    match value.serialize(&serializer) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            report_error(format!("Failed to convert the chart options: {e}"));
+            None
+        }
+    }
+}
+
+/// Set the property at `path` of a serialized option, e.g. a formatter
+/// function at `["tooltip", "formatter"]`. Missing objects on the way are
+/// added.
+pub(crate) fn set_path(option: &JsValue, path: &[&str], value: &JsValue) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut object = option.clone();
+    for key in parents {
+        let key = JsValue::from_str(key);
+        let mut child = js_sys::Reflect::get(&object, &key).unwrap_or_default();
+        if !child.is_object() {
+            child = js_sys::Object::new().into();
+            let _ = js_sys::Reflect::set(&object, &key, &child);
+        }
+        // Series and axes may be arrays; functions go on their first item
+        if let Some(array) = child.dyn_ref::<js_sys::Array>() {
+            child = array.get(0);
+        }
+        object = child;
+    }
+    let _ = js_sys::Reflect::set(&object, &JsValue::from_str(last), value);
+}
+
+/// The chart drawn in `dom`, if any.
+pub(crate) fn chart_in(dom: &Element) -> Option<ECharts> {
+    echarts_instance_by_dom(dom).ok().flatten()
+}
+
+/// Dispose of the chart drawn in `dom`, if any.
+pub(crate) fn dispose_chart(dom: &Element) {
+    if chart_in(dom).is_some() {
+        echarts_dispose(dom);
+    }
+}
+
+/// Every chart on the page.
+pub(crate) fn page_charts() -> Vec<ECharts> {
+    let Ok(divs) = gloo::utils::document().query_selector_all("[_echarts_instance_]") else {
+        return Vec::new();
+    };
+    (0..divs.length())
+        .filter_map(|i| divs.get(i)?.dyn_into::<Element>().ok())
+        .filter_map(|div| chart_in(&div))
+        .collect()
+}
+
+/// Whether the chart's div is laid out, i.e. neither it nor a parent is hidden.
+pub(crate) fn is_shown(chart: &ECharts) -> bool {
+    chart
+        .dom()
+        .dyn_into::<HtmlElement>()
+        .is_ok_and(|div| div.offset_width() > 0)
+}
+
+/// Lay the shown charts out again at the size of their divs.
+pub(crate) fn resize_charts() {
+    for chart in page_charts().iter().filter(|chart| is_shown(chart)) {
+        chart.resize();
+    }
+}
+
+/// Attribute of a chart div holding the `chart_key` it was drawn for.
+const CHART_KEY_ATTRIBUTE: &str = "data-chart-key";
+
+/// What a chart is drawn for. Charts drawn for the same trace and theme are
+/// updated in place, so they neither flicker nor lose their legend selection;
+/// anything else starts over.
+pub(crate) fn chart_key(theme: Theme, trace_id: u32) -> String {
+    format!("{theme:?}-{trace_id}")
+}
+
+/// `setOption` settings updating a chart: series missing from the new option
+/// are removed rather than kept, everything else is merged.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MergeSettings {
+    replace_merge: [&'static str; 1],
+}
+
+/// Draw `option` in the chart div `id`, updating the chart already there when
+/// it was drawn for the same `chart_key`. Does nothing when the div isn't shown.
+pub(crate) fn draw_chart(
+    id: &str,
+    theme: Theme,
+    trace_id: u32,
+    option: &impl Serialize,
+) -> Option<ECharts> {
+    let dom = gloo::utils::document().get_element_by_id(id)?;
+    let option = to_js(option)?;
+    draw_chart_in(&dom, theme, trace_id, &option)
+}
+
+/// Draw an option already converted by `to_js` (e.g. to add functions to it)
+/// in `dom`, like `draw_chart`.
+pub(crate) fn draw_chart_in(
+    dom: &Element,
+    theme: Theme,
+    trace_id: u32,
+    option: &JsValue,
+) -> Option<ECharts> {
+    let id = dom.id();
+    // Throws when ECharts failed to load
+    let chart = match echarts_instance_by_dom(dom) {
+        Ok(chart) => chart,
+        Err(e) => {
+            report_error(format!("Failed to draw the {id} chart: {e:?}"));
+            return None;
+        }
+    };
+    let key = chart_key(theme, trace_id);
+    match chart {
+        Some(chart) if dom.get_attribute(CHART_KEY_ATTRIBUTE).as_ref() == Some(&key) => {
+            let settings = MergeSettings {
+                replace_merge: ["series"],
+            };
+            // The div may have been resized while hidden
+            chart.resize();
+            chart.update_option(option, &to_js(&settings).unwrap_or_default());
+            Some(chart)
+        }
+        chart => {
+            if chart.is_some() {
+                echarts_dispose(dom);
+            }
+            let _ = dom.set_attribute(CHART_KEY_ATTRIBUTE, &key);
+            match echarts_init(dom, theme.chart_theme()) {
+                Ok(chart) => {
+                    chart.set_option(option);
+                    Some(chart)
+                }
+                Err(e) => {
+                    report_error(format!("Failed to draw the {id} chart: {e:?}"));
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// `appendData` parameters: points added to the series at `series_index`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppendedPoints<'a> {
+    series_index: usize,
+    data: &'a [(f64, Option<f64>)],
+}
+
+/// The axes of an option, merged into a drawn chart without its series.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AxesUpdate<'a> {
+    x_axis: &'a [Axis],
+    y_axis: &'a [Axis],
+}
+
+/// Add the points of `option` after `after` (ms) to the chart in div `id`,
+/// as live mode streams in snapshots, and take its axes. The chart has to
+/// have been drawn for the same `chart_key` with the same series, in order;
+/// false when it wasn't, and so has to be drawn in full.
+pub(crate) fn append_to_chart(
+    id: &str,
+    theme: Theme,
+    trace_id: u32,
+    option: &ChartOption,
+    drawn_series: &[String],
+    after: f64,
+) -> bool {
+    let Some(dom) = gloo::utils::document().get_element_by_id(id) else {
+        return false;
+    };
+    let Some(chart) = chart_in(&dom) else {
+        return false;
+    };
+    let names = option.series.iter().map(|series| &series.name);
+    if dom.get_attribute(CHART_KEY_ATTRIBUTE) != Some(chart_key(theme, trace_id))
+        || !names.eq(drawn_series)
+    {
+        return false;
+    }
+    for (series_index, series) in option.series.iter().enumerate() {
+        let new = series.data.partition_point(|&(time, _)| time <= after);
+        let data = &series.data[new..];
+        if data.is_empty() {
+            continue;
+        }
+        if let Some(params) = to_js(&AppendedPoints { series_index, data }) {
+            chart.append_data(&params);
+        }
+    }
+    // Also redraws the chart with the points appended
+    let axes = AxesUpdate {
+        x_axis: &option.x_axis,
+        y_axis: &option.y_axis,
+    };
+    if let Some(axes) = to_js(&axes) {
+        chart.set_option(&axes);
+    }
+    true
+}
+
+/// Moving-average windows (in samples) offered for the GPU and CPU traces.
+pub(crate) const SMOOTHING_WINDOWS: [usize; 4] = [1, 5, 15, 60];
+
+/// Initial GPU temperature above which the temperature chart is shaded.
+pub(crate) const DEFAULT_GPU_TEMP_WARNING_C: f64 = 80.0;
+
+pub(crate) const CHART_LAYOUT_STORAGE_KEY: &str = "timeline-viewer-chart-layout";
+
+/// Order and visibility of the line charts, remembered in localStorage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ChartLayout {
+    /// Ids from `PANELS`, top to bottom
+    pub(crate) order: Vec<String>,
+    pub(crate) hidden: BTreeSet<String>,
+}
+
+impl Default for ChartLayout {
+    fn default() -> Self {
+        ChartLayout {
+            order: PANELS.iter().map(|panel| panel.id().to_string()).collect(),
+            hidden: BTreeSet::new(),
+        }
+    }
+}
+
+impl ChartLayout {
+    pub(crate) fn load() -> Self {
+        let mut layout: ChartLayout =
+            LocalStorage::get(CHART_LAYOUT_STORAGE_KEY).unwrap_or_default();
+        // Forget charts that no longer exist and append ones added since
+        layout.order.retain(|id| panel(id).is_some());
+        for panel in PANELS {
+            if !layout.order.iter().any(|id| id == panel.id()) {
+                layout.order.push(panel.id().to_string());
+            }
+        }
+        layout
+    }
+
+    /// Swap a chart with its neighbour `offset` places away, if there is one.
+    pub(crate) fn moved(&self, index: usize, offset: isize) -> Self {
+        let mut layout = self.clone();
+        if let Some(other) = index
+            .checked_add_signed(offset)
+            .filter(|&i| i < layout.order.len())
+        {
+            layout.order.swap(index, other);
+        }
+        layout
+    }
+
+    /// Ids of the charts drawn, top to bottom: those not hidden, less any
+    /// needing data the trace never recorded.
+    pub(crate) fn shown(&self, columns: &TraceColumns) -> Vec<String> {
+        self.order
+            .iter()
+            .filter(|id| {
+                !self.hidden.contains(*id)
+                    && panel(id).is_none_or(|panel| panel.missing_fields(columns).is_empty())
+            })
+            .cloned()
+            .collect()
+    }
+}
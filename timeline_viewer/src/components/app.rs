@@ -0,0 +1,353 @@
+use crate::charts::{resize_charts, CHART_LAYOUT_STORAGE_KEY};
+use crate::components::{
+    report_error, BookmarkList, BusiestProcesses, ComparedRun, ErrorToasts, FileLoader,
+    HeatmapPanel, LineChartPanel, PlaybackPanel, ProcessDetail, RangeStatsPanel, RawJsonModal,
+    RowPager, SnapshotDiff, TimeRangeControls, TraceSummaryCard, ViewSettings,
+};
+use crate::export::{download_file, DataExport};
+use crate::links::ChartLinks;
+use crate::settings::{
+    load_bookmarks, save_bookmarks, Bookmark, TimelineView, ViewState, PALETTE_STORAGE_KEY,
+    THEME_STORAGE_KEY,
+};
+use crate::state::{dispatch_with, RowOptions, ViewerAction, ViewerContext, ViewerState};
+use gloo::storage::{LocalStorage, Storage};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, ResizeObserver, ResizeObserverEntry};
+use yew::prelude::*;
+
+#[function_component(App)]
+pub(crate) fn app() -> Html {
+    let viewer_ref = use_node_ref();
+    // Trace, time range, row filters and settings, shared through a ViewerContext
+    let state = use_reducer(ViewerState::load);
+    let trace = &state.trace;
+    // Rows before windowing, reported back by the heatmap panel
+    let row_count = use_state_eq(|| 0usize);
+    // Every heatmap row label before windowing, to page to a row on request
+    let heatmap_labels = use_mut_ref(Vec::<String>::new);
+    // Zoom, crosshair and markers shared by the charts of the panels below
+    let links = use_memo((), |_| ChartLinks::default());
+    // Snapshot index and row label of the process shown in the detail panel
+    let process_detail = use_state(|| None::<(usize, String)>);
+    // View restored from the URL fragment when the next trace loads
+    let pending_view = use_mut_ref(ViewState::from_fragment);
+    // Snapshot indices compared in the diff panel
+    let snapshot_diff = use_state(|| None::<(usize, usize)>);
+    // Snapshot indices of the window brushed on the CPU and GPU charts
+    let range_stats = use_state(|| None::<(usize, usize)>);
+    // Snapshot index of the playback cursor, None when playback is off
+    let playback = use_state(|| None::<usize>);
+    // Snapshot index and row label (None for the whole snapshot) shown as raw JSON
+    let raw_json = use_state(|| None::<(usize, Option<String>)>);
+    // File name (or URL) of the loaded trace, which bookmarks are stored under
+    let trace_name = use_state(String::new);
+    let bookmarks = use_state(Vec::<Bookmark>::new);
+    // Second run (file name, columns) overlaid on the loaded one in compare mode
+    let comparison = use_state(|| None::<ComparedRun>);
+
+    {
+        let bookmarks = bookmarks.clone();
+        use_effect_with((*trace_name).clone(), move |trace_name| {
+            bookmarks.set(load_bookmarks(trace_name));
+        });
+    }
+
+    let on_download_data = {
+        let trace = trace.clone();
+        let range = (state.min_time, state.max_time);
+        Callback::from(move |format: &'static str| {
+            let snapshots = trace.snapshots();
+            let end = (range.1 + 1).min(snapshots.len());
+            let export = DataExport::new(&snapshots[range.0.min(end)..end]);
+            let content = match format {
+                "csv" => export.to_csv().map_err(|e| e.to_string()),
+                _ => serde_json::to_string_pretty(&export).map_err(|e| e.to_string()),
+            };
+            match content {
+                Ok(content) => download_file(
+                    &format!("timeline-{}-{}.{format}", range.0, range.1),
+                    if format == "csv" {
+                        "text/csv"
+                    } else {
+                        "application/json"
+                    },
+                    &content,
+                ),
+                Err(e) => report_error(format!("Failed to export data: {e}")),
+            }
+        })
+    };
+
+    let on_rows = {
+        let row_count = row_count.setter();
+        let heatmap_labels = heatmap_labels.clone();
+        Callback::from(move |labels: Vec<String>| {
+            row_count.set(labels.len());
+            *heatmap_labels.borrow_mut() = labels;
+        })
+    };
+
+    let on_bookmark = {
+        let bookmarks = bookmarks.clone();
+        let trace_name = trace_name.clone();
+        let trace = trace.clone();
+        Callback::from(move |(index, name): (usize, String)| {
+            let Some(timestamp) = trace.columns().timestamps.get(index).cloned() else {
+                return;
+            };
+            let mut updated = (*bookmarks).clone();
+            updated.push(Bookmark { name, timestamp });
+            save_bookmarks(&trace_name, &updated);
+            bookmarks.set(updated);
+        })
+    };
+
+    // Charts are laid out at the width they were drawn at; resize them when the
+    // window or a surrounding split pane changes the viewer's width
+    {
+        let viewer_ref = viewer_ref.clone();
+        use_effect_with((), move |_| {
+            let mut last_width = 0.0;
+            let on_resize =
+                Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+                    let Ok(entry) = entries.get(0).dyn_into::<ResizeObserverEntry>() else {
+                        return;
+                    };
+                    let width = entry.content_rect().width();
+                    if width == last_width {
+                        return;
+                    }
+                    last_width = width;
+                    resize_charts();
+                });
+            let observer = ResizeObserver::new(on_resize.as_ref().unchecked_ref()).ok();
+            if let (Some(observer), Some(viewer)) = (&observer, viewer_ref.cast::<Element>()) {
+                observer.observe(&viewer);
+            }
+            move || {
+                if let Some(observer) = observer {
+                    observer.disconnect();
+                }
+                drop(on_resize);
+            }
+        });
+    }
+
+    // The page colors come from CSS variables switched by the body class
+    use_effect_with(state.theme, |theme| {
+        gloo::utils::body().set_class_name(theme.name());
+        let _ = LocalStorage::set(THEME_STORAGE_KEY, theme.name());
+    });
+
+    // Restore the view of a shared link once its trace has loaded
+    {
+        let pending_view = pending_view.clone();
+        let viewer = state.dispatcher();
+        use_effect_with(state.trace_len, move |&len| {
+            if len == 0 {
+                return;
+            }
+            if let Some(view) = pending_view.borrow_mut().take() {
+                viewer.dispatch(ViewerAction::RestoreView(view));
+            }
+        });
+    }
+
+    // Keep the URL fragment in step with the view, once one is loaded
+    {
+        let pending_view = pending_view.clone();
+        use_effect_with(
+            (state.view(), state.trace_len > 0),
+            move |(view, loaded)| {
+                if *loaded && pending_view.borrow().is_none() {
+                    view.save_to_fragment();
+                }
+            },
+        );
+    }
+    use_effect_with(state.custom_palette.clone(), |palette| match palette {
+        Some(palette) => {
+            let _ = LocalStorage::set(PALETTE_STORAGE_KEY, palette);
+        }
+        None => LocalStorage::delete(PALETTE_STORAGE_KEY),
+    });
+
+    use_effect_with(state.chart_layout.clone(), |layout| {
+        let _ = LocalStorage::set(CHART_LAYOUT_STORAGE_KEY, layout);
+    });
+
+    // Page to the row if needed; the chart scrolls to it once drawn
+    let on_show_row = {
+        let heatmap_labels = heatmap_labels.clone();
+        let viewer = state.dispatcher();
+        let links = links.clone();
+        let RowOptions {
+            row_offset,
+            row_window,
+            ..
+        } = state.rows;
+        Callback::from(move |label: String| {
+            viewer.dispatch(ViewerAction::SetTimelineView(TimelineView::Heatmap));
+            let index = heatmap_labels.borrow().iter().position(|l| *l == label);
+            if let Some(index) = index {
+                if index < row_offset || index >= row_offset + row_window {
+                    viewer.dispatch(ViewerAction::SetRowOffset(
+                        index.saturating_sub(row_window / 2),
+                    ));
+                }
+            }
+            links.scroll_to_row(label);
+        })
+    };
+
+    let on_remove_bookmark = {
+        let bookmarks = bookmarks.clone();
+        let trace_name = trace_name.clone();
+        Callback::from(move |index: usize| {
+            let mut updated = (*bookmarks).clone();
+            updated.remove(index);
+            save_bookmarks(&trace_name, &updated);
+            bookmarks.set(updated);
+        })
+    };
+
+    let shown_charts = state.chart_layout.shown(&trace.columns());
+
+    html! {
+        <ContextProvider<ViewerContext> context={state.clone()}>
+            <ErrorToasts />
+            <div class="viewer" ref={viewer_ref.clone()}>
+                <FileLoader
+                    on_trace_name={{
+                        let trace_name = trace_name.clone();
+                        Callback::from(move |name| trace_name.set(name))
+                    }}
+                    comparing={comparison.as_ref().map(|other| other.name().to_string())}
+                    on_compare={{
+                        let comparison = comparison.clone();
+                        Callback::from(move |other| comparison.set(other))
+                    }}
+                    drop_target={viewer_ref}
+                />
+                <ViewSettings />
+                <RowPager row_count={*row_count} />
+                <TimeRangeControls
+                    playback={*playback}
+                    on_playback={{
+                        let playback = playback.clone();
+                        Callback::from(move |index| playback.set(index))
+                    }}
+                    on_diff={{
+                        let snapshot_diff = snapshot_diff.clone();
+                        Callback::from(move |range| snapshot_diff.set(Some(range)))
+                    }}
+                    on_download={on_download_data}
+                    links={links.clone()}
+                />
+                <TraceSummaryCard />
+                <BusiestProcesses on_select={on_show_row} />
+                <BookmarkList bookmarks={(*bookmarks).clone()} on_remove={on_remove_bookmark} />
+                <HeatmapPanel
+                    min={state.min_time}
+                    max={state.max_time}
+                    heatmap_color={state.heatmap_color}
+                    timeline_view={state.timeline_view}
+                    collapsed_rows={state.collapsed_rows.clone()}
+                    rows={state.rows.clone()}
+                    theme={state.theme}
+                    palette={state.palette()}
+                    row_layout={state.row_layout}
+                    smoothing={state.smoothing}
+                    comparison={(*comparison).clone()}
+                    bookmarks={(*bookmarks).clone()}
+                    charts={shown_charts.clone()}
+                    {on_rows}
+                    on_toggle_row={dispatch_with(&state, ViewerAction::ToggleRow)}
+                    on_process_detail={{
+                        let process_detail = process_detail.clone();
+                        Callback::from(move |detail| process_detail.set(Some(detail)))
+                    }}
+                    on_raw_json={{
+                        let raw_json = raw_json.clone();
+                        Callback::from(move |(index, label)| raw_json.set(Some((index, Some(label)))))
+                    }}
+                    {on_bookmark}
+                    on_time_range={dispatch_with(&state, |(min, max)| ViewerAction::SetTimeRange(min, max))}
+                    on_range_stats={{
+                        let range_stats = range_stats.clone();
+                        Callback::from(move |range| range_stats.set(Some(range)))
+                    }}
+                    links={links.clone()}
+                >
+                    <LineChartPanel
+                        min={state.min_time}
+                        max={state.max_time}
+                        theme={state.theme}
+                        palette={state.palette()}
+                        smoothing={state.smoothing}
+                        gpu_temp_warning={state.gpu_temp_warning}
+                        charts={shown_charts}
+                        links={links.clone()}
+                    />
+                </HeatmapPanel>
+                if let Some((index, label)) = (*process_detail).clone() {
+                    <ProcessDetail
+                        {index}
+                        label={label.clone()}
+                        on_raw_json={{
+                            let raw_json = raw_json.clone();
+                            Callback::from(move |_| raw_json.set(Some((index, Some(label.clone())))))
+                        }}
+                        onclose={{
+                            let process_detail = process_detail.clone();
+                            Callback::from(move |_| process_detail.set(None))
+                        }}
+                    />
+                }
+                if let Some((index, label)) = (*raw_json).clone() {
+                    <RawJsonModal
+                        {index}
+                        {label}
+                        on_whole_snapshot={{
+                            let raw_json = raw_json.clone();
+                            Callback::from(move |_| raw_json.set(Some((index, None))))
+                        }}
+                        onclose={{
+                            let raw_json = raw_json.clone();
+                            Callback::from(move |_| raw_json.set(None))
+                        }}
+                    />
+                }
+                if let Some((from, to)) = *snapshot_diff {
+                    <SnapshotDiff
+                        {from}
+                        {to}
+                        on_change={{
+                            let snapshot_diff = snapshot_diff.clone();
+                            Callback::from(move |range| snapshot_diff.set(Some(range)))
+                        }}
+                        onclose={{
+                            let snapshot_diff = snapshot_diff.clone();
+                            Callback::from(move |_| snapshot_diff.set(None))
+                        }}
+                    />
+                }
+                if let Some((from, to)) = *range_stats {
+                    <RangeStatsPanel
+                        {from}
+                        {to}
+                        onclose={{
+                            let range_stats = range_stats.clone();
+                            Callback::from(move |_| range_stats.set(None))
+                        }}
+                    />
+                }
+                if let Some(index) = *playback {
+                    <PlaybackPanel {index} />
+                }
+            </div>
+        </ContextProvider<ViewerContext>>
+    }
+}
@@ -0,0 +1,51 @@
+use crate::settings::Bookmark;
+use crate::state::{use_viewer, ViewerAction};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct BookmarkListProps {
+    pub(crate) bookmarks: Vec<Bookmark>,
+    /// Index of a bookmark to delete
+    pub(crate) on_remove: Callback<usize>,
+}
+
+/// The bookmarks of the loaded trace, each jumping the range to its time.
+#[function_component(BookmarkList)]
+pub(crate) fn bookmark_list(props: &BookmarkListProps) -> Html {
+    let viewer = use_viewer();
+    if viewer.trace_len == 0 {
+        return html! {};
+    }
+    let items = props.bookmarks.iter().enumerate().map(|(i, bookmark)| {
+        // Center the current range width on the bookmark
+        let on_jump = {
+            let dispatcher = viewer.dispatcher();
+            let index = viewer
+                .trace
+                .columns()
+                .timestamps
+                .iter()
+                .position(|timestamp| *timestamp == bookmark.timestamp);
+            Callback::from(move |_: MouseEvent| {
+                if let Some(index) = index {
+                    dispatcher.dispatch(ViewerAction::CenterOn(index));
+                }
+            })
+        };
+        let on_remove = props.on_remove.reform(move |_: MouseEvent| i);
+        html! {
+            <li>
+                { format!("{} ({}) ", bookmark.name, bookmark.timestamp) }
+                <button onclick={on_jump}>{ "Jump" }</button>
+                <button onclick={on_remove}>{ "✕" }</button>
+            </li>
+        }
+    });
+    html! {
+        <details open={!props.bookmarks.is_empty()}>
+            <summary>{ format!("Bookmarks ({})", props.bookmarks.len()) }</summary>
+            <p>{ "Double-click a chart to bookmark that time." }</p>
+            <ul>{ for items }</ul>
+        </details>
+    }
+}
@@ -0,0 +1,79 @@
+use crate::state::use_viewer;
+use timeline_core::analysis::{busiest_processes, BusySort, BUSY_PROCESS_ROWS};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct BusiestProcessesProps {
+    /// Row label of a clicked process, to show on the heatmap
+    pub(crate) on_select: Callback<String>,
+}
+
+/// Table of the processes running most over the selected range, sortable by
+/// each column.
+#[function_component(BusiestProcesses)]
+pub(crate) fn busiest_processes_table(props: &BusiestProcessesProps) -> Html {
+    let viewer = use_viewer();
+    let busy_sort = use_state(|| BusySort::Running);
+    let trace = viewer.trace.clone();
+    let busy_processes = use_memo(
+        (
+            viewer.trace_id,
+            viewer.trace_len,
+            viewer.min_time,
+            viewer.max_time,
+        ),
+        move |&(_, len, min, max)| {
+            let snapshots = trace.snapshots();
+            let end = (max + 1).min(len);
+            busiest_processes(&snapshots[min.min(end)..end])
+        },
+    );
+    if busy_processes.is_empty() {
+        return html! {};
+    }
+    let mut ranked = (*busy_processes).clone();
+    match *busy_sort {
+        BusySort::Running => {}
+        BusySort::Cpu => ranked.sort_by(|a, b| {
+            b.mean_cpu_percent
+                .unwrap_or(-1.0)
+                .total_cmp(&a.mean_cpu_percent.unwrap_or(-1.0))
+        }),
+        BusySort::Name => ranked.sort_by(|a, b| a.name.cmp(&b.name).then(a.pid.cmp(&b.pid))),
+    }
+    let header = |title: &str, sort: BusySort| {
+        let busy_sort = busy_sort.clone();
+        let marker = if *busy_sort == sort { " ▾" } else { "" };
+        html! {
+            <th style="cursor: pointer;" onclick={Callback::from(move |_: MouseEvent| busy_sort.set(sort))}>
+                { format!("{title}{marker}") }
+            </th>
+        }
+    };
+    let rows = ranked.into_iter().take(BUSY_PROCESS_ROWS).map(|busy| {
+        let onclick = props.on_select.reform({
+            let label = busy.label.clone();
+            move |_: MouseEvent| label.clone()
+        });
+        html! {
+            <tr style="cursor: pointer;" {onclick}>
+                <td>{ format!("{} (PID {})", busy.name, busy.pid) }</td>
+                <td>{ format!("{:.0}%", busy.running_fraction * 100.0) }</td>
+                <td>{ busy.mean_cpu_percent.map(|cpu| format!("{cpu:.1}%")).unwrap_or_default() }</td>
+            </tr>
+        }
+    });
+    html! {
+        <details>
+            <summary>{ "Busiest processes" }</summary>
+            <table>
+                <tr>
+                    { header("Process", BusySort::Name) }
+                    { header("Snapshots running", BusySort::Running) }
+                    { header("Mean CPU", BusySort::Cpu) }
+                </tr>
+                { for rows }
+            </table>
+        </details>
+    }
+}
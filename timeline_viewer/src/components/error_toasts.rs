@@ -0,0 +1,113 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Errors kept on screen at once; older ones make way for new ones.
+const TOASTS_SHOWN: usize = 5;
+
+thread_local! {
+    /// How to show an error in the mounted `ErrorToasts`, and the errors
+    /// reported before it mounted.
+    static TOASTS: RefCell<(Option<Callback<String>>, Vec<String>)> =
+        const { RefCell::new((None, Vec::new())) };
+}
+
+/// Log an error to the console and show it in a toast, for failures the user
+/// would otherwise only see as a blank or half-drawn page.
+pub(crate) fn report_error(message: impl Into<String>) {
+    let message = message.into();
+    gloo::console::error!(&message);
+    TOASTS.with_borrow_mut(|(show, pending)| match show {
+        Some(show) => show.emit(message),
+        None => pending.push(message),
+    });
+}
+
+/// Install a panic hook logging the panic with its stack trace, and covering
+/// the page with a banner. Nothing is rendered after a panic, so the banner
+/// is added to the DOM directly rather than by a component.
+pub(crate) fn set_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        console_error_panic_hook::hook(info);
+        let document = gloo::utils::document();
+        let Ok(banner) = document.create_element("div") else {
+            return;
+        };
+        let _ = banner.set_attribute(
+            "style",
+            "position: fixed; top: 0; left: 0; right: 0; padding: 1em; background: #a50026; color: white; z-index: 30;",
+        );
+        banner.set_text_content(Some(&format!(
+            "The viewer stopped working and has to be reloaded: {info}"
+        )));
+        let _ = gloo::utils::body().append_child(&banner);
+    }));
+}
+
+#[derive(Default, PartialEq)]
+struct Toasts {
+    next_id: usize,
+    shown: Vec<(usize, String)>,
+}
+
+enum ToastAction {
+    Show(String),
+    Dismiss(usize),
+}
+
+impl Reducible for Toasts {
+    type Action = ToastAction;
+
+    fn reduce(self: Rc<Self>, action: ToastAction) -> Rc<Self> {
+        let mut shown = self.shown.clone();
+        let mut next_id = self.next_id;
+        match action {
+            ToastAction::Show(message) => {
+                shown.push((next_id, message));
+                next_id += 1;
+                let hidden = shown.len().saturating_sub(TOASTS_SHOWN);
+                shown.drain(..hidden);
+            }
+            ToastAction::Dismiss(id) => shown.retain(|&(shown_id, _)| shown_id != id),
+        }
+        Rc::new(Toasts { next_id, shown })
+    }
+}
+
+/// The errors passed to `report_error`, each shown until dismissed.
+#[function_component(ErrorToasts)]
+pub(crate) fn error_toasts() -> Html {
+    let toasts = use_reducer(Toasts::default);
+
+    {
+        let toasts = toasts.dispatcher();
+        use_effect_with((), move |_| {
+            let show = Callback::from(move |message| toasts.dispatch(ToastAction::Show(message)));
+            TOASTS.with_borrow_mut(|(registered, pending)| {
+                for message in pending.drain(..) {
+                    show.emit(message);
+                }
+                *registered = Some(show);
+            });
+            || TOASTS.with_borrow_mut(|(registered, _)| *registered = None)
+        });
+    }
+
+    html! {
+        <div style="position: fixed; bottom: 1em; right: 1em; width: min(28em, 90%); z-index: 25; display: flex; flex-direction: column; gap: 0.5em;">
+            { for toasts.shown.iter().map(|(id, message)| {
+                let dismiss = {
+                    let toasts = toasts.dispatcher();
+                    let id = *id;
+                    Callback::from(move |_: MouseEvent| toasts.dispatch(ToastAction::Dismiss(id)))
+                };
+                html! {
+                    <div key={*id} role="alert" style="background: #a50026; color: white; padding: 0.5em 1em; border-radius: 4px; display: flex; gap: 1em; align-items: start;">
+                        <span style="flex: 1;">{ message }</span>
+                        <button onclick={dismiss} title="Dismiss">{ "×" }</button>
+                    </div>
+                }
+            }) }
+        </div>
+    }
+}
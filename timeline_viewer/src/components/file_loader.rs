@@ -0,0 +1,784 @@
+use crate::components::{report_error, ParseErrorList, ParseErrors, ParseErrorsAction};
+use crate::source::{
+    file_list_to_vec, list_recent_traces, load_recent_trace, pick_file_handles, read_all,
+    read_file_handles, save_recent_trace, src_query_param, FileSource, LiveSource, RecentTrace,
+    SnapshotSource, UrlSource, AUTO_RELOAD_INTERVAL_MS,
+};
+use crate::state::{use_viewer, Trace, ViewerAction};
+use futures::future::{abortable, AbortHandle};
+use futures::StreamExt;
+use gloo::events::{EventListener, EventListenerOptions};
+use gloo::timers::callback::Interval;
+use gloo_file::futures::read_as_text;
+use gloo_file::File;
+use std::rc::Rc;
+use timeline_core::analysis::{resample_snapshots, GapFill};
+use timeline_core::columns::TraceColumns;
+use timeline_core::model::{snapshot_order_key, Snapshot, TimelineEvent};
+use timeline_core::parse::{
+    is_trace_file_name, parse_snapshots, CsvField, CsvImport, EventCarry, ParseError,
+    TRACE_FILE_EXTENSIONS,
+};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Element, FileSystemFileHandle, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement,
+};
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+/// A second run overlaid on the loaded one in compare mode, kept only as
+/// columns. Compared by pointer, so props holding one never walk its samples.
+#[derive(Debug, Clone)]
+pub(crate) struct ComparedRun(Rc<(String, TraceColumns)>);
+
+impl ComparedRun {
+    pub(crate) fn name(&self) -> &str {
+        &self.0 .0
+    }
+
+    pub(crate) fn columns(&self) -> &TraceColumns {
+        &self.0 .1
+    }
+}
+
+impl PartialEq for ComparedRun {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct FileLoaderProps {
+    /// File name (or URL) of the loaded trace
+    pub(crate) on_trace_name: Callback<String>,
+    /// File name of the run being compared against, if any
+    pub(crate) comparing: Option<String>,
+    /// Run to overlay, or None to stop comparing
+    pub(crate) on_compare: Callback<Option<ComparedRun>>,
+    /// Element that files can be dropped onto
+    pub(crate) drop_target: NodeRef,
+}
+
+/// Opening traces from files, directories, URLs, live streams, pasted text or
+/// the recent trace cache, with the parse errors and resampling options.
+#[function_component(FileLoader)]
+pub(crate) fn file_loader(props: &FileLoaderProps) -> Html {
+    let viewer = use_viewer();
+    let append_mode = use_state(|| false);
+    let recent_traces = use_state(Vec::<RecentTrace>::new);
+    let pasted_text = use_state(String::new);
+    let parse_errors = use_reducer(ParseErrors::default);
+    // Loaded trace before resampling, kept so the grid can be changed later;
+    // the same trace the viewer shows when not resampling, and the one live
+    // mode appends to
+    let source_trace = use_mut_ref(Rc::<Trace>::default);
+    let resample_secs = use_state(|| 0.0);
+    let gap_fill = use_state(|| GapFill::Missing);
+    let file_handles = use_mut_ref(Vec::<(FileSystemFileHandle, f64)>::new);
+    let has_file_handles = use_state(|| false);
+    let auto_reload = use_mut_ref(|| None::<Interval>);
+    let load_progress = use_state(|| None::<(u64, u64)>);
+    // CSV files awaiting a column mapping, each with whether it is appended
+    let csv_imports = use_state(Vec::<(CsvImport, bool)>::new);
+    let live_url = use_state(String::new);
+    let live_connected = use_state(|| false);
+    let live_abort = use_mut_ref(|| None::<AbortHandle>);
+    let live_pinned = use_mut_ref(|| false);
+
+    let set_loaded = {
+        let source_trace = source_trace.clone();
+        let resample_secs = *resample_secs;
+        let gap_fill = *gap_fill;
+        let viewer = viewer.dispatcher();
+        Callback::from(move |(mut parsed, append): (Vec<Snapshot>, bool)| {
+            if append {
+                // Stitch rotated log files onto what is already loaded
+                let loaded = source_trace.borrow().clone();
+                parsed.splice(0..0, loaded.snapshots().iter().cloned());
+            }
+            // Capture files may be selected in any order; merge by timestamp and
+            // drop duplicates where rotated logs overlap
+            let count = parsed.len();
+            let mut keyed: Vec<_> = parsed
+                .into_iter()
+                .map(|snap| (snapshot_order_key(&snap), snap))
+                .collect();
+            keyed.sort_by(|a, b| a.0.cmp(&b.0));
+            keyed.dedup_by(|a, b| a.0 == b.0);
+            let parsed: Vec<Snapshot> = keyed.into_iter().map(|(_, snap)| snap).collect();
+            if parsed.len() < count {
+                gloo::console::log!(format!(
+                    "Dropped {} duplicate snapshot(s)",
+                    count - parsed.len()
+                ));
+            }
+            if resample_secs > 0.0 {
+                let resampled = resample_snapshots(&parsed, resample_secs, gap_fill);
+                *source_trace.borrow_mut() = Rc::new(Trace::new(parsed));
+                viewer.dispatch(ViewerAction::Load(Rc::new(Trace::new(resampled))));
+            } else {
+                let trace = Rc::new(Trace::new(parsed));
+                *source_trace.borrow_mut() = trace.clone();
+                viewer.dispatch(ViewerAction::Load(trace));
+            }
+        })
+    };
+
+    // Re-run the load stage over the unresampled data when the grid changes
+    {
+        let set_loaded = set_loaded.clone();
+        use_effect_with((*resample_secs, *gap_fill), move |_| {
+            set_loaded.emit((Vec::new(), true));
+        });
+    }
+
+    let refresh_recent = {
+        let recent_traces = recent_traces.clone();
+        Callback::from(move |_| {
+            let recent_traces = recent_traces.clone();
+            spawn_local(async move {
+                match list_recent_traces().await {
+                    Ok(recent) => recent_traces.set(recent),
+                    Err(e) => report_error(format!("Failed to list recent traces: {e:?}")),
+                }
+            });
+        })
+    };
+
+    // Cache each loaded trace in IndexedDB so it can be reopened with one click
+    let remember_trace = {
+        let refresh_recent = refresh_recent.clone();
+        let on_trace_name = props.on_trace_name.clone();
+        Callback::from(move |(name, parsed): (String, Vec<Snapshot>)| {
+            on_trace_name.emit(name.clone());
+            if parsed.is_empty() {
+                return;
+            }
+            let refresh_recent = refresh_recent.clone();
+            spawn_local(async move {
+                match save_recent_trace(&name, &parsed).await {
+                    Ok(()) => refresh_recent.emit(()),
+                    Err(e) => report_error(format!("Failed to cache {name}: {e:?}")),
+                }
+            });
+        })
+    };
+
+    {
+        let refresh_recent = refresh_recent.clone();
+        use_effect_with((), move |_| refresh_recent.emit(()));
+    }
+
+    // Files and hosted traces are read to the end, then loaded as one trace
+    let load_sources = {
+        let set_loaded = set_loaded.clone();
+        let remember_trace = remember_trace.clone();
+        let parse_errors = parse_errors.dispatcher();
+        let load_progress = load_progress.clone();
+        Callback::from(
+            move |(sources, append): (Vec<Box<dyn SnapshotSource>>, bool)| {
+                let set_loaded = set_loaded.clone();
+                let load_progress = load_progress.clone();
+                let remember_trace = remember_trace.clone();
+                let parse_errors = parse_errors.clone();
+
+                spawn_local(async move {
+                    let total: u64 = sources.iter().map(|source| source.size()).sum();
+                    let names: Vec<String> = sources.iter().map(|source| source.name()).collect();
+                    let mut completed = 0;
+                    load_progress.set(Some((0, total)));
+                    let mut parsed = Vec::new();
+                    let mut errors = Vec::new();
+                    for (source, name) in sources.into_iter().zip(&names) {
+                        let size = source.size();
+                        let on_progress = |read: u64| {
+                            load_progress.set(Some((completed + read.min(size), total)))
+                        };
+                        match read_all(source, on_progress).await {
+                            Ok((source_snapshots, source_errors)) => {
+                                parsed.extend(source_snapshots);
+                                errors.extend(source_errors);
+                            }
+                            Err(e) => errors.push(ParseError {
+                                source: name.clone(),
+                                ..ParseError::new(0, format!("Failed to read: {}", e), "")
+                            }),
+                        }
+                        completed += size;
+                    }
+                    load_progress.set(None);
+                    if parsed.is_empty() && !errors.is_empty() {
+                        report_error(format!(
+                            "No snapshots could be read from {}; see the parse errors",
+                            names.join(", ")
+                        ));
+                    }
+                    parse_errors.dispatch(if append {
+                        ParseErrorsAction::Append(errors)
+                    } else {
+                        ParseErrorsAction::Replace(errors)
+                    });
+                    remember_trace.emit((names.join(", "), parsed.clone()));
+                    set_loaded.emit((parsed, append));
+                    gloo::console::log!(format!("Snapshots loaded from {} source(s)", names.len()));
+                });
+            },
+        )
+    };
+
+    let load_files = {
+        let load_sources = load_sources.clone();
+        let csv_imports = csv_imports.clone();
+        Callback::from(move |(files, append): (Vec<File>, bool)| {
+            // CSV files need a column mapping before they can be converted; they
+            // are mapped one after another and merged with the rest of the selection
+            let (csv_files, files): (Vec<File>, Vec<File>) = files
+                .into_iter()
+                .partition(|f| f.name().to_lowercase().ends_with(".csv"));
+            if !csv_files.is_empty() {
+                let csv_imports = csv_imports.clone();
+                let mut append = append || !files.is_empty();
+                spawn_local(async move {
+                    let mut imports = Vec::new();
+                    for csv_file in csv_files {
+                        let result = read_as_text(&csv_file)
+                            .await
+                            .map_err(|e| e.to_string())
+                            .and_then(|content| {
+                                CsvImport::new(csv_file.name(), content).map_err(|e| e.to_string())
+                            });
+                        match result {
+                            Ok(import) => {
+                                imports.push((import, append));
+                                append = true;
+                            }
+                            Err(e) => {
+                                report_error(format!("Failed to read {}: {}", csv_file.name(), e))
+                            }
+                        }
+                    }
+                    csv_imports.set(imports);
+                });
+            }
+            if files.is_empty() {
+                return;
+            }
+            let sources = files
+                .into_iter()
+                .map(|file| Box::new(FileSource(file)) as Box<dyn SnapshotSource>)
+                .collect();
+            load_sources.emit((sources, append));
+        })
+    };
+
+    // Compare mode: a second run is parsed on its own and never merged
+    let on_compare_change = {
+        let on_compare = props.on_compare.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)).map(File::from) else {
+                return;
+            };
+            let on_compare = on_compare.clone();
+            spawn_local(async move {
+                let name = file.name();
+                match read_all(Box::new(FileSource(file)), |_| {}).await {
+                    Ok((mut parsed, _)) => {
+                        parsed.sort_by_cached_key(snapshot_order_key);
+                        let columns = TraceColumns::new(&parsed);
+                        on_compare.emit(Some(ComparedRun(Rc::new((name, columns)))));
+                    }
+                    Err(e) => report_error(format!("Failed to read {}: {}", name, e)),
+                }
+            });
+        })
+    };
+
+    // Load a hosted trace on startup when the page is opened with `?src=<url>`
+    {
+        let load_sources = load_sources.clone();
+        use_effect_with((), move |_| {
+            if let Some(url) = src_query_param() {
+                load_sources.emit((vec![Box::new(UrlSource(url))], false));
+            }
+        });
+    }
+
+    // Live mode: streamed snapshots are appended as they arrive, following the
+    // newest sample unless the user has pinned the current range
+    let append_snapshots = {
+        let viewer = viewer.dispatcher();
+        let live_pinned = live_pinned.clone();
+        let source_trace = source_trace.clone();
+        Callback::from(
+            move |(events, batch): (Vec<TimelineEvent>, Vec<Snapshot>)| {
+                let trace = source_trace.borrow().clone();
+                if trace.append(events, batch) {
+                    viewer.dispatch(ViewerAction::Live(trace, !*live_pinned.borrow()));
+                }
+            },
+        )
+    };
+
+    let on_live_connect = {
+        let live_url = live_url.clone();
+        let live_abort = live_abort.clone();
+        let live_connected = live_connected.clone();
+        let append_snapshots = append_snapshots.clone();
+        let parse_errors = parse_errors.dispatcher();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(handle) = live_abort.borrow_mut().take() {
+                handle.abort();
+            }
+            let url = (*live_url).trim().to_string();
+            if url.is_empty() {
+                return;
+            }
+            let mut batches = Box::new(LiveSource(url.clone())).open();
+
+            let append_snapshots = append_snapshots.clone();
+            let parse_errors = parse_errors.clone();
+            let (stream, handle) = abortable(async move {
+                let mut carry = EventCarry::default();
+                while let Some(batch) = batches.next().await {
+                    let mut part = match batch {
+                        Ok(batch) => batch.part,
+                        Err(e) => {
+                            report_error(format!("Failed to connect to {}: {}", url, e));
+                            break;
+                        }
+                    };
+                    let events = carry.carry(&mut part);
+                    parse_errors.dispatch(ParseErrorsAction::Append(part.errors));
+                    append_snapshots.emit((events, part.snapshots));
+                }
+            });
+            *live_abort.borrow_mut() = Some(handle);
+            live_connected.set(true);
+
+            let live_connected = live_connected.clone();
+            spawn_local(async move {
+                // An aborted stream was replaced or disconnected by the user
+                if stream.await.is_ok() {
+                    live_connected.set(false);
+                }
+            });
+        })
+    };
+
+    let on_live_disconnect = {
+        let live_abort = live_abort.clone();
+        let live_connected = live_connected.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(handle) = live_abort.borrow_mut().take() {
+                handle.abort();
+            }
+            live_connected.set(false);
+        })
+    };
+
+    let on_csv_import = {
+        let csv_imports = csv_imports.clone();
+        let set_loaded = set_loaded.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(((import, append), queued)) = csv_imports.split_first() else {
+                return;
+            };
+            match import.to_snapshots() {
+                Ok(parsed) => {
+                    set_loaded.emit((parsed, *append));
+                    // Files with the same columns start from this file's mapping
+                    let queued = queued
+                        .iter()
+                        .cloned()
+                        .map(|(mut next, append)| {
+                            if next.headers == import.headers {
+                                next.mapping = import.mapping.clone();
+                            }
+                            (next, append)
+                        })
+                        .collect();
+                    csv_imports.set(queued);
+                }
+                Err(e) => report_error(format!("Failed to import {}: {}", import.file_name, e)),
+            }
+        })
+    };
+
+    let csv_mapping_dialog = csv_imports.first().map(|(import, _)| {
+        let rows = CsvField::ALL.iter().enumerate().map(|(index, field)| {
+            let selected = import.mapping[index];
+            let onchange = {
+                let csv_imports = csv_imports.clone();
+                Callback::from(move |e: Event| {
+                    let select: HtmlSelectElement = e.target_unchecked_into();
+                    let mut imports = (*csv_imports).clone();
+                    if let Some((import, _)) = imports.first_mut() {
+                        import.mapping[index] = select.value().parse().ok();
+                        csv_imports.set(imports);
+                    }
+                })
+            };
+            html! {
+                <tr>
+                    <td>{ field.label() }</td>
+                    <td>
+                        <select {onchange}>
+                            <option value="" selected={selected.is_none()}>{ "(none)" }</option>
+                            { for import.headers.iter().enumerate().map(|(col, header)| html! {
+                                <option value={col.to_string()} selected={selected == Some(col)}>{ header }</option>
+                            }) }
+                        </select>
+                    </td>
+                </tr>
+            }
+        });
+        // Skips this file, moving on to the next one
+        let on_cancel = {
+            let csv_imports = csv_imports.clone();
+            Callback::from(move |_: MouseEvent| csv_imports.set(csv_imports[1..].to_vec()))
+        };
+        let remaining = match csv_imports.len() {
+            1 => String::new(),
+            count => format!(" (1 of {count})"),
+        };
+        html! {
+            <div style="border: 1px solid var(--border); padding: 1em; margin: 1em 0; display: inline-block;">
+                <strong>{ format!("Map CSV columns: {}{}", import.file_name, remaining) }</strong>
+                <table>{ for rows }</table>
+                <button onclick={on_csv_import.clone()}>{ "Import" }</button>
+                <button onclick={on_cancel}>{ "Cancel" }</button>
+            </div>
+        }
+    });
+
+    let on_file_change = {
+        let load_files = load_files.clone();
+        let append_mode = *append_mode;
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Some(files) = input.files() {
+                load_files.emit((file_list_to_vec(&files), append_mode));
+            }
+        })
+    };
+
+    // A directory of per-minute shards is loaded as one timeline; `set_loaded`
+    // merges the snapshots by timestamp
+    let on_directory_change = {
+        let load_files = load_files.clone();
+        let append_mode = *append_mode;
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            if let Some(files) = input.files() {
+                let files: Vec<File> = file_list_to_vec(&files)
+                    .into_iter()
+                    .filter(|f| is_trace_file_name(&f.name()))
+                    .collect();
+                gloo::console::log!(format!("{} trace file(s) found in directory", files.len()));
+                load_files.emit((files, append_mode));
+            }
+        })
+    };
+
+    // Files opened through the File System Access API keep a handle that can
+    // be re-read, so traces still being written by the collector can be reloaded
+    let reload_files = {
+        let load_files = load_files.clone();
+        let file_handles = file_handles.clone();
+        Callback::from(move |only_if_modified: bool| {
+            let load_files = load_files.clone();
+            let file_handles = file_handles.clone();
+            spawn_local(async move {
+                let handles: Vec<_> = file_handles
+                    .borrow()
+                    .iter()
+                    .map(|(h, _)| h.clone())
+                    .collect();
+                match read_file_handles(&handles).await {
+                    Ok(files) => {
+                        let modified: Vec<f64> = files.iter().map(|f| f.last_modified()).collect();
+                        let changed = file_handles
+                            .borrow()
+                            .iter()
+                            .zip(&modified)
+                            .any(|((_, before), after)| before != after);
+                        if only_if_modified && !changed {
+                            return;
+                        }
+                        for ((_, before), after) in
+                            file_handles.borrow_mut().iter_mut().zip(modified)
+                        {
+                            *before = after;
+                        }
+                        let files = files.into_iter().map(File::from).collect();
+                        load_files.emit((files, false));
+                    }
+                    Err(e) => report_error(format!("Failed to reload files: {e:?}")),
+                }
+            });
+        })
+    };
+
+    let on_open_reloadable = {
+        let file_handles = file_handles.clone();
+        let has_file_handles = has_file_handles.clone();
+        let reload_files = reload_files.clone();
+        Callback::from(move |_: MouseEvent| {
+            let file_handles = file_handles.clone();
+            let has_file_handles = has_file_handles.clone();
+            let reload_files = reload_files.clone();
+            spawn_local(async move {
+                match pick_file_handles().await {
+                    Ok(handles) => {
+                        // NaN never compares equal, so the first read always loads
+                        *file_handles.borrow_mut() =
+                            handles.into_iter().map(|h| (h, f64::NAN)).collect();
+                        has_file_handles.set(true);
+                        reload_files.emit(false);
+                    }
+                    Err(e) => report_error(format!("Failed to open files: {e:?}")),
+                }
+            });
+        })
+    };
+
+    let on_auto_reload = {
+        let auto_reload = auto_reload.clone();
+        let reload_files = reload_files.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            *auto_reload.borrow_mut() = input.checked().then(|| {
+                let reload_files = reload_files.clone();
+                Interval::new(AUTO_RELOAD_INTERVAL_MS, move || reload_files.emit(true))
+            });
+        })
+    };
+
+    // Snapshots pasted from the clipboard go through the same parsing as files
+    let on_load_pasted = {
+        let pasted_text = pasted_text.clone();
+        let append_mode = append_mode.clone();
+        let set_loaded = set_loaded.clone();
+        let parse_errors = parse_errors.dispatcher();
+        Callback::from(move |_: MouseEvent| {
+            let (parsed, mut errors) = parse_snapshots(pasted_text.trim());
+            gloo::console::log!(format!(
+                "{} snapshots parsed from pasted text",
+                parsed.len()
+            ));
+            for error in &mut errors {
+                error.source = "pasted text".to_string();
+            }
+            parse_errors.dispatch(if *append_mode {
+                ParseErrorsAction::Append(errors)
+            } else {
+                ParseErrorsAction::Replace(errors)
+            });
+            set_loaded.emit((parsed, *append_mode));
+        })
+    };
+
+    let recent_list = (!recent_traces.is_empty()).then(|| {
+        let items = recent_traces.iter().map(|recent| {
+            let onclick = {
+                let set_loaded = set_loaded.clone();
+                let on_trace_name = props.on_trace_name.clone();
+                let name = recent.name.clone();
+                Callback::from(move |_: MouseEvent| {
+                    on_trace_name.emit(name.clone());
+                    let set_loaded = set_loaded.clone();
+                    let name = name.clone();
+                    spawn_local(async move {
+                        match load_recent_trace(&name).await {
+                            Ok(parsed) => set_loaded.emit((parsed, false)),
+                            Err(e) => {
+                                report_error(format!("Failed to open cached trace: {e:?}"))
+                            }
+                        }
+                    });
+                })
+            };
+            let saved_at = js_sys::Date::new(&recent.saved_at.into())
+                .to_locale_string("default", &JsValue::UNDEFINED);
+            html! {
+                <li>
+                    <button {onclick}>{ &recent.name }</button>
+                    { format!(" ({} snapshots, {})", recent.snapshot_count, String::from(saved_at)) }
+                </li>
+            }
+        });
+        html! {
+            <details>
+                <summary>{ "Recent traces" }</summary>
+                <ul>{ for items }</ul>
+            </details>
+        }
+    });
+
+    // Files dropped anywhere on the viewer are loaded; the drop target is
+    // highlighted while files are dragged over it. Attached after every render
+    // so a drop uses the current loading options.
+    {
+        let load_files = load_files.clone();
+        let append_mode = *append_mode;
+        let drop_target = props.drop_target.clone();
+        use_effect(move || {
+            let listeners = drop_target.cast::<Element>().map(|target| {
+                let set_dragging = {
+                    let target = target.clone();
+                    move |dragging: bool| {
+                        let _ = target.class_list().toggle_with_force("dragging", dragging);
+                    }
+                };
+                let on_drag_over = {
+                    let set_dragging = set_dragging.clone();
+                    EventListener::new_with_options(
+                        &target,
+                        "dragover",
+                        EventListenerOptions::enable_prevent_default(),
+                        move |event| {
+                            // Required for the browser to treat the page as a drop target
+                            event.prevent_default();
+                            set_dragging(true);
+                        },
+                    )
+                };
+                let on_drag_leave = {
+                    let set_dragging = set_dragging.clone();
+                    EventListener::new(&target, "dragleave", move |_| set_dragging(false))
+                };
+                let on_drop = EventListener::new_with_options(
+                    &target,
+                    "drop",
+                    EventListenerOptions::enable_prevent_default(),
+                    move |event| {
+                        event.prevent_default();
+                        set_dragging(false);
+                        if let Some(files) = event
+                            .dyn_ref::<DragEvent>()
+                            .and_then(|event| event.data_transfer())
+                            .and_then(|dt| dt.files())
+                        {
+                            load_files.emit((file_list_to_vec(&files), append_mode));
+                        }
+                    },
+                );
+                [on_drag_over, on_drag_leave, on_drop]
+            });
+            move || drop(listeners)
+        });
+    }
+
+    html! {
+        <>
+            <input type="file" accept={TRACE_FILE_EXTENSIONS.join(",")} multiple=true onchange={on_file_change} />
+            <label>
+                { "Directory: " }
+                <input type="file" webkitdirectory=true onchange={on_directory_change} />
+            </label>
+            <label>
+                { "Compare with: " }
+                <input type="file" accept={TRACE_FILE_EXTENSIONS.join(",")} onchange={on_compare_change} />
+            </label>
+            if let Some(other) = &props.comparing {
+                <button onclick={props.on_compare.reform(|_: MouseEvent| None)}>{ format!("Stop comparing {other}") }</button>
+            }
+            <label>
+                <input type="checkbox" checked={*append_mode} onchange={{
+                    let append_mode = append_mode.clone();
+                    Callback::from(move |e: Event| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        append_mode.set(input.checked());
+                    })
+                }} />
+                { "Append to current data" }
+            </label>
+            <button onclick={on_open_reloadable}>{ "Open reloadable..." }</button>
+            if *has_file_handles {
+                <button onclick={{
+                    let reload_files = reload_files.clone();
+                    Callback::from(move |_: MouseEvent| reload_files.emit(false))
+                }}>{ "Reload" }</button>
+                <label>
+                    <input type="checkbox" checked={auto_reload.borrow().is_some()} onchange={on_auto_reload} />
+                    { "Auto-reload" }
+                </label>
+            }
+            <p>
+                <input type="text" placeholder="ws://host:port/snapshots or https://host/events" value={(*live_url).clone()} oninput={{
+                    let live_url = live_url.clone();
+                    Callback::from(move |e: InputEvent| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        live_url.set(input.value());
+                    })
+                }} />
+                <button onclick={on_live_connect}>{ "Connect" }</button>
+                if *live_connected {
+                    <button onclick={on_live_disconnect}>{ "Disconnect" }</button>
+                    <span>{ " Live " }</span>
+                    <label>
+                        <input type="checkbox" checked={*live_pinned.borrow()} onchange={{
+                            let live_pinned = live_pinned.clone();
+                            Callback::from(move |e: Event| {
+                                let input: HtmlInputElement = e.target_unchecked_into();
+                                *live_pinned.borrow_mut() = input.checked();
+                            })
+                        }} />
+                        { "Pin range" }
+                    </label>
+                }
+            </p>
+            <details>
+                <summary>{ "Paste snapshots" }</summary>
+                <textarea rows="6" cols="80" placeholder="Paste JSONL or a JSON array of snapshots" value={(*pasted_text).clone()} oninput={{
+                    let pasted_text = pasted_text.clone();
+                    Callback::from(move |e: InputEvent| {
+                        let input: HtmlTextAreaElement = e.target_unchecked_into();
+                        pasted_text.set(input.value());
+                    })
+                }} />
+                <br />
+                <button onclick={on_load_pasted}>{ "Load pasted" }</button>
+            </details>
+            { for recent_list }
+            if !parse_errors.is_empty() {
+                <ParseErrorList errors={parse_errors.clone()} />
+            }
+            { for csv_mapping_dialog }
+            if let Some((loaded, total)) = *load_progress {
+                <p>
+                    <progress max={total.max(1).to_string()} value={loaded.to_string()} />
+                    { format!(" Parsing... {}%", loaded * 100 / total.max(1)) }
+                </p>
+            }
+            <p>
+                <label>
+                    { "Resample every " }
+                    <input type="number" min="0" step="any" placeholder="off" style="width: 5em;" onchange={{
+                        let resample_secs = resample_secs.clone();
+                        Callback::from(move |e: Event| {
+                            let input: HtmlInputElement = e.target_unchecked_into();
+                            resample_secs.set(input.value().parse::<f64>().unwrap_or(0.0).max(0.0));
+                        })
+                    }} />
+                    { " s, gaps: " }
+                </label>
+                <select onchange={{
+                    let gap_fill = gap_fill.clone();
+                    Callback::from(move |e: Event| {
+                        let select: HtmlSelectElement = e.target_unchecked_into();
+                        gap_fill.set(if select.value() == "repeat" {
+                            GapFill::RepeatLast
+                        } else {
+                            GapFill::Missing
+                        });
+                    })
+                }}>
+                    <option value="missing" selected={*gap_fill == GapFill::Missing}>{ "missing" }</option>
+                    <option value="repeat" selected={*gap_fill == GapFill::RepeatLast}>{ "repeat last" }</option>
+                </select>
+            </p>
+        </>
+    }
+}
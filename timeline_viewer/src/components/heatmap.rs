@@ -0,0 +1,846 @@
+use crate::charts::Callbacks;
+use crate::components::heatmap_charts::{
+    draw_compare, draw_core_heatmap, draw_custom_metrics, draw_gantt, draw_heatmap, draw_overview,
+    CellTooltip, Gantt, HeatmapClicks, Overview, ProcessCpuChart, ScaledValue,
+};
+use crate::components::heatmap_rows::TraceRows;
+use crate::components::ComparedRun;
+use crate::links::{ChartLinks, Marker, Timeline};
+use crate::panels::fit_to_width;
+use crate::settings::{Bookmark, HeatmapColor, Palette, RowLayout, Theme, TimelineView};
+use crate::state::{use_viewer, RowOptions, Trace};
+use chrono::DateTime;
+use gloo::timers::callback::Timeout;
+use gloo::timers::future::TimeoutFuture;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::rc::Rc;
+use timeline_core::analysis::relative_utilization;
+use timeline_core::echarts::{
+    Axis, AxisLabel, ChartOption, ColorRange, DataZoom, Emphasis, Grid, ItemStyle, Legend, Length,
+    LineType, Piece, Series, SeriesType, Show, Title, Tooltip, VisualMap,
+};
+use timeline_core::matrix::{
+    escape_html, CellColoring, HeatmapCells, RowId, GPU_BUCKET_NAMES, STATE_NAMES,
+};
+use timeline_core::model::timestamp_micros;
+use timeline_core::series::{line_series, moving_average};
+use web_sys::HtmlElement;
+use yew::platform::spawn_local;
+use yew::prelude::*;
+
+/// Heatmap values of the GPU load buckets named in `GPU_BUCKET_NAMES`, as
+/// (min, max) inclusive.
+const GPU_BUCKETS: [(f64, f64); 5] = [
+    (7.0, 22.0),
+    (23.0, 42.0),
+    (43.0, 62.0),
+    (63.0, 82.0),
+    (83.0, 107.0),
+];
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct HeatmapPanelProps {
+    /// Selected range of snapshot indices
+    pub(crate) min: usize,
+    pub(crate) max: usize,
+    pub(crate) heatmap_color: HeatmapColor,
+    pub(crate) timeline_view: TimelineView,
+    /// Collapsed container headings and process rows, by heading key or row label
+    pub(crate) collapsed_rows: HashSet<String>,
+    pub(crate) rows: RowOptions,
+    pub(crate) theme: Theme,
+    pub(crate) palette: Palette,
+    pub(crate) row_layout: RowLayout,
+    /// Moving-average window of the per-process CPU and compared runs, in samples
+    pub(crate) smoothing: usize,
+    pub(crate) comparison: Option<ComparedRun>,
+    pub(crate) bookmarks: Vec<Bookmark>,
+    /// Line charts shown, some of which are drawn here
+    pub(crate) charts: Vec<String>,
+    /// Every row label before windowing, reported after each redraw
+    pub(crate) on_rows: Callback<Vec<String>>,
+    /// Container heading key or process row label to collapse or expand
+    pub(crate) on_toggle_row: Callback<String>,
+    /// Snapshot index and row label of a clicked cell
+    pub(crate) on_process_detail: Callback<(usize, String)>,
+    /// Snapshot index and row label of a shift-clicked cell
+    pub(crate) on_raw_json: Callback<(usize, String)>,
+    /// Snapshot index and name of a bookmark added by double-clicking a chart
+    pub(crate) on_bookmark: Callback<(usize, String)>,
+    /// Range zoomed to on any chart
+    pub(crate) on_time_range: Callback<(usize, usize)>,
+    /// Range brushed on the CPU or GPU charts
+    pub(crate) on_range_stats: Callback<(usize, usize)>,
+    /// Links the charts on the page once the timeline is drawn
+    pub(crate) links: Rc<ChartLinks>,
+    /// Drawn between the timeline heatmaps and the custom metric charts
+    #[prop_or_default]
+    pub(crate) children: Html,
+}
+
+/// How long a dragged zoom slider has to rest before its range is committed
+/// and the charts rebuilt for it. The linked charts follow the drag at once.
+const TIME_RANGE_DEBOUNCE_MS: u32 = 150;
+
+/// Rows filled between yields to the browser while building the matrix.
+const ROWS_PER_YIELD: usize = 50;
+
+/// Yield to the browser every `ROWS_PER_YIELD` rows of a build, so sliders
+/// stay responsive on big traces. False once the build is cancelled.
+async fn keep_building(filled: usize, cancelled: &Cell<bool>) -> bool {
+    if filled > 0 && filled.is_multiple_of(ROWS_PER_YIELD) {
+        TimeoutFuture::new(0).await;
+    }
+    !cancelled.get()
+}
+
+/// The timeline heatmap with the overview strip, process lifetimes, per-core
+/// load, custom metrics and run comparison drawn around it. Also links every
+/// chart on the page for zooming, crosshairs and markers. The trace is read
+/// from the `ViewerContext`.
+#[function_component(HeatmapPanel)]
+pub(crate) fn heatmap_panel(props: &HeatmapPanelProps) -> Html {
+    let viewer = use_viewer();
+    let chart_ref = use_node_ref();
+    // Tooltips and handlers of the charts drawn last
+    let draw_callbacks = use_mut_ref(Callbacks::default);
+    // Process rows shown in the per-process CPU chart, by label
+    let selected_rows = use_mut_ref(Vec::<String>::new);
+    // Commit of the last zoomed range, replaced (and so cancelled) by the next
+    let time_range_timer = use_mut_ref(|| None::<Timeout>);
+    // Rows of the loaded trace, rebuilt only when other snapshots are loaded
+    // or live mode appends to it
+    let trace_rows_cache = use_mut_ref(|| None::<((Rc<Trace>, usize), Rc<TraceRows>)>);
+
+    let on_rows = props.on_rows.clone();
+    let on_toggle_row = props.on_toggle_row.clone();
+    let on_process_detail = props.on_process_detail.clone();
+    let on_raw_json = props.on_raw_json.clone();
+    let on_bookmark = props.on_bookmark.clone();
+    let on_time_range = props.on_time_range.clone();
+    let on_range_stats = props.on_range_stats.clone();
+    let links = props.links.clone();
+    let trace = viewer.trace.clone();
+    // Keyed on the trace and its length rather than its columns, which
+    // would be compared one by one
+    use_effect_with(
+        (
+            viewer.trace_id,
+            viewer.trace_len,
+            chart_ref.clone(),
+            props.min,
+            props.max,
+            props.heatmap_color,
+            props.collapsed_rows.clone(),
+            props.timeline_view,
+            // Display options
+            (
+                props.theme,
+                props.palette.clone(),
+                props.row_layout,
+                props.smoothing,
+                props.comparison.clone(),
+                props.bookmarks.clone(),
+                props.charts.clone(),
+            ),
+            props.rows.clone(),
+        ),
+        move |(
+            trace_id,
+            trace_len,
+            chart_ref,
+            min,
+            max,
+            heatmap_color,
+            collapsed_rows,
+            timeline_view,
+            (theme, palette, row_layout, smoothing, comparison, bookmarks, _charts),
+            rows,
+        )| {
+            // Set when the inputs change again, so a build for the previous
+            // slider position stops at its next yield
+            let cancelled = Rc::new(Cell::new(false));
+            let cancel = {
+                let cancelled = cancelled.clone();
+                move || cancelled.set(true)
+            };
+            if *trace_len == 0 || chart_ref.get().is_none() {
+                return cancel;
+            }
+            let (min, max) = (*min, *max);
+
+            // Zooming any chart narrows the range the data is culled to, once
+            // the zoom comes to rest
+            let set_time_range = {
+                let time_range_timer = time_range_timer.clone();
+                Callback::from(move |(start, end): (usize, usize)| {
+                    let range = (start.min(end), start.max(end));
+                    let on_time_range = on_time_range.clone();
+                    // Zooming back to the committed range drops the pending commit
+                    *time_range_timer.borrow_mut() = (range != (min, max)).then(|| {
+                        Timeout::new(TIME_RANGE_DEBOUNCE_MS, move || on_time_range.emit(range))
+                    });
+                })
+            };
+
+            let inputs = (
+                (trace.clone(), *trace_len),
+                *trace_id,
+                chart_ref.clone(),
+                *heatmap_color,
+                collapsed_rows.clone(),
+                *timeline_view,
+                (
+                    *theme,
+                    palette.clone(),
+                    *row_layout,
+                    *smoothing,
+                    comparison.clone(),
+                    bookmarks.clone(),
+                ),
+                rows.clone(),
+            );
+            spawn_local(async move {
+                let (
+                    built_for,
+                    trace_id,
+                    chart_ref,
+                    heatmap_color,
+                    collapsed_rows,
+                    timeline_view,
+                    (theme, palette, row_layout, smoothing, comparison, bookmarks),
+                    rows,
+                ) = &inputs;
+                let RowOptions {
+                    processes_only,
+                    running_thread_rows,
+                    row_window,
+                    row_offset,
+                    ..
+                } = rows;
+                let trace_rows = {
+                    let mut cache = trace_rows_cache.borrow_mut();
+                    match &*cache {
+                        Some(((trace, len), trace_rows))
+                            if Rc::ptr_eq(trace, &built_for.0) && *len == built_for.1 =>
+                        {
+                            trace_rows.clone()
+                        }
+                        _ => {
+                            let trace_rows = Rc::new(TraceRows::new(built_for.0.clone()));
+                            *cache = Some((built_for.clone(), trace_rows.clone()));
+                            trace_rows
+                        }
+                    }
+                };
+                // Borrowed between the yields only, as live mode appends to it
+                let trace = &built_for.0;
+                // Line charts use a time axis; the heatmaps need categories, labelled
+                // with the wall-clock time
+                let snapshot_times = &trace_rows.snapshot_times;
+                let (trace_start, trace_end) = (trace_rows.trace_start, trace_rows.trace_end);
+
+                let row_order = trace_rows.row_order(min, max, rows, collapsed_rows);
+                // Only the rows in the window get labels and matrix entries
+                on_rows.emit(row_order.labels.clone());
+                let window_start =
+                    (*row_offset).min(row_order.labels.len().saturating_sub(*row_window));
+                let label_order: Vec<String> = row_order
+                    .labels
+                    .iter()
+                    .skip(window_start)
+                    .take(*row_window)
+                    .cloned()
+                    .collect();
+                let window_ids = row_order.ids.iter().skip(window_start).take(*row_window);
+                // Interned rows in the window, with their matrix rows
+                let shown: Vec<(RowId, usize)> = window_ids
+                    .clone()
+                    .enumerate()
+                    .filter_map(|(row, id)| Some(((*id)?, row)))
+                    .collect();
+                // GPU rows aren't interned, so they are found by label
+                let gpu_rows: Vec<(&str, usize)> = label_order
+                    .iter()
+                    .zip(window_ids)
+                    .enumerate()
+                    .filter(|(_, (_, id))| id.is_none())
+                    .map(|(row, (label, _))| (label.as_str(), row))
+                    .collect();
+
+                // Per-process CPU (shown for rows selected by clicking the heatmap) and
+                // memory (scaling the memory coloring)
+                let (process_cpu, process_gpu_mem, memory_peak_mb) = {
+                    let columns = trace.columns();
+                    let row_ids = &columns.rows;
+                    let process_cpu: BTreeMap<String, Vec<(usize, f64)>> = columns
+                        .process_series(min..=max, |proc| &proc.cpu_percent)
+                        .iter()
+                        .map(|(&row, points)| {
+                            let points = moving_average(points, *smoothing);
+                            (row_ids.label(row).to_string(), points)
+                        })
+                        .collect();
+                    // GPU memory held by each process, added to the cell tooltips
+                    let process_gpu_mem: HashMap<String, Vec<(usize, f64)>> = columns
+                        .process_series(min..=max, |proc| &proc.gpu_memory_mb)
+                        .into_iter()
+                        .map(|(row, points)| (row_ids.label(row).to_string(), points))
+                        .collect();
+
+                    let memory_peak_mb = columns
+                        .process_series(min..=max, |proc| &proc.memory_rss_mb)
+                        .values()
+                        .flatten()
+                        .map(|&(_, rss)| rss)
+                        .fold(0.0, f64::max);
+                    (process_cpu, process_gpu_mem, memory_peak_mb)
+                };
+
+                // Step 4: Build matrix
+                let coloring = match *heatmap_color {
+                    HeatmapColor::ThreadState if *processes_only => CellColoring::RunningThreads,
+                    HeatmapColor::ThreadState => CellColoring::ThreadState,
+                    HeatmapColor::Memory => CellColoring::Memory {
+                        peak_mb: memory_peak_mb,
+                    },
+                    HeatmapColor::CpuTime => CellColoring::CpuTime,
+                };
+                let mut matrix = HeatmapCells::default();
+                for (filled, &(id, row)) in shown.iter().enumerate() {
+                    if !keep_building(filled, &cancelled).await {
+                        return;
+                    }
+                    matrix.add_row(&trace.columns(), id, row, min..=max, &coloring);
+                }
+                // Let the line chart panel draw its charts first, so they are
+                // linked along with these
+                TimeoutFuture::new(0).await;
+                if cancelled.get() {
+                    return;
+                }
+                let columns = trace.columns();
+                for &(label, row) in &gpu_rows {
+                    let Some(gpu) = columns.gpus.get(label) else {
+                        continue;
+                    };
+                    for timestamp_index in (min..=max).filter(|&i| !columns.gaps[i]) {
+                        let Some(sample) = gpu.at(timestamp_index) else {
+                            continue;
+                        };
+                        // Use colormap indices 7–107 for GPU load gradient
+                        let value = sample.load_percent.clamp(0.0, 100.0) as u8 + 7;
+                        let detail = format!(
+                            "GPU #{}: {}<br/>Memory: {:.0} / {:.0} MB<br/>Temperature: {:.0} °C",
+                            gpu.id,
+                            escape_html(&gpu.name),
+                            sample.memory_used_mb,
+                            sample.memory_total_mb,
+                            sample.temperature_c
+                        );
+                        matrix.push(timestamp_index, row, value, &detail);
+                    }
+                }
+                let state_colors = &palette.states;
+                // Per-core load heatmap, rows grouped by host
+                let core_labels: Vec<String> = columns
+                    .core_loads
+                    .keys()
+                    .map(|(prefix, core)| format!("{prefix}Core {core}"))
+                    .collect();
+                let core_matrix: Vec<(usize, usize, f64)> = columns
+                    .core_loads
+                    .values()
+                    .enumerate()
+                    .flat_map(|(row, loads)| {
+                        let points = loads.points(min..=max);
+                        points
+                            .into_iter()
+                            .map(move |(index, load)| (index, row, load.clamp(0.0, 100.0)))
+                    })
+                    .collect();
+                let core_height = if core_labels.is_empty() {
+                    0
+                } else {
+                    core_labels.len() * row_layout.row_height + 80
+                };
+
+                // Custom metrics, one chart per metric name with a series per host
+                let custom_data: Vec<_> = columns
+                    .custom
+                    .iter()
+                    .filter_map(|(name, hosts)| {
+                        let hosts: Vec<_> = hosts
+                            .iter()
+                            .map(|(label, column)| (label.as_str(), column.points(min..=max)))
+                            .filter(|(_, points)| !points.is_empty())
+                            .collect();
+                        (!hosts.is_empty()).then_some((name.as_str(), hosts))
+                    })
+                    .collect();
+                // The custom charts span the page, and may be drawn while the
+                // heatmap is hidden
+                let page_width = gloo::utils::body().client_width().max(0) as u32;
+                let custom_charts: Vec<_> = custom_data
+                    .iter()
+                    .map(|(name, hosts)| {
+                        let mut series: Vec<_> = hosts
+                            .iter()
+                            .map(|(label, points)| line_series(label, "", points, snapshot_times))
+                            .collect();
+                        fit_to_width(&mut series, page_width);
+                        ChartOption {
+                            title: Some(Title {
+                                text: name.to_string(),
+                                ..Title::default()
+                            }),
+                            tooltip: Some(Tooltip::axis()),
+                            legend: Some(Legend::scrolling(20.0)),
+                            x_axis: vec![Axis::time(trace_start, trace_end)],
+                            y_axis: vec![Axis::default()],
+                            series,
+                            ..ChartOption::default()
+                        }
+                    })
+                    .collect();
+
+                // Process lifetimes for the Gantt view, ordered by first appearance
+                let mut lifetimes: Vec<(String, usize, usize)> = columns
+                    .processes
+                    .iter()
+                    .filter_map(|(&row, proc)| {
+                        let present = proc.threads.points(min..=max);
+                        let label = columns.rows.label(row);
+                        let name = label.trim().trim_start_matches("└─ ").to_string();
+                        Some((name, present.first()?.0, present.last()?.0))
+                    })
+                    .collect();
+                lifetimes.sort_by(|a, b| (a.1, a.2, &a.0).cmp(&(b.1, b.2, &b.0)));
+                let gantt_height = if *timeline_view == TimelineView::Gantt {
+                    (lifetimes.len() * row_layout.row_height).max(200) + 80
+                } else {
+                    0
+                };
+
+                // Event markers, placed at the visible snapshot nearest in time
+                let visible_micros: Vec<(usize, f64)> = (min..=max)
+                    .filter_map(|i| Some((i, (*columns.micros.get(i)?)?)))
+                    .collect();
+                let mut event_markers = BTreeSet::new();
+                let logged = columns.events.partition_point(|(index, _)| *index < min);
+                for (timestamp_index, event) in columns.events[logged..]
+                    .iter()
+                    .take_while(|(index, _)| *index <= max)
+                {
+                    let index = match timestamp_micros(&event.Timestamp) {
+                        Some(micros) => {
+                            let (Some(first), Some(last)) =
+                                (visible_micros.first(), visible_micros.last())
+                            else {
+                                continue;
+                            };
+                            if micros < first.1 || micros > last.1 {
+                                continue;
+                            }
+                            visible_micros
+                                .iter()
+                                .min_by(|a, b| {
+                                    (a.1 - micros).abs().total_cmp(&(b.1 - micros).abs())
+                                })
+                                .map_or(*timestamp_index, |&(i, _)| i)
+                        }
+                        None => *timestamp_index,
+                    };
+                    event_markers.insert((index, event.Timestamp.clone(), event.Name.clone()));
+                }
+                // Category axes place a marker on its snapshot, time axes at the event's own time
+                let marker = |index, timestamp: &str, name: &str| Marker {
+                    index,
+                    time: timestamp_micros(timestamp).map(|micros| micros / 1e3),
+                    name: name.to_string(),
+                };
+                let bookmark_markers: Vec<Marker> = bookmarks
+                    .iter()
+                    .filter_map(|bookmark| {
+                        let index = (min..=max).find(|&i| {
+                            columns
+                                .timestamps
+                                .get(i)
+                                .is_some_and(|timestamp| *timestamp == bookmark.timestamp)
+                        })?;
+                        Some(marker(index, &bookmark.timestamp, &bookmark.name))
+                    })
+                    .collect();
+                let event_markers: Vec<Marker> = event_markers
+                    .iter()
+                    .map(|(index, timestamp, name)| marker(*index, timestamp, name))
+                    .collect();
+
+                // Thread states, then GPU load buckets when there are GPU rows
+                let mut pieces: Vec<Piece> = STATE_NAMES
+                    .iter()
+                    .zip(state_colors)
+                    .enumerate()
+                    .map(|(value, (name, color))| {
+                        Piece::new(value as f64, value as f64, name, color)
+                    })
+                    .collect();
+                if trace_rows.has_gpu_rows() {
+                    pieces.extend(
+                        GPU_BUCKETS
+                            .iter()
+                            .zip(GPU_BUCKET_NAMES)
+                            .zip(&palette.gpu)
+                            .map(|(((min, max), name), color)| Piece::new(*min, *max, name, color)),
+                    );
+                }
+                // Legend and tooltip for the 108–207 range used by the non-default modes,
+                // in five 20-value steps
+                let scaled_pieces = |labels: [&str; 5], colors: [&str; 5]| -> Vec<Piece> {
+                    labels
+                        .into_iter()
+                        .zip(colors)
+                        .enumerate()
+                        .map(|(i, (label, color))| {
+                            let min = 108.0 + 20.0 * i as f64;
+                            Piece::new(min, min + 19.0, label, color)
+                        })
+                        .collect()
+                };
+                let scaled_value = match *heatmap_color {
+                    HeatmapColor::ThreadState if *processes_only => {
+                        pieces.extend(scaled_pieces(
+                            [
+                                "Running 0–20% of threads",
+                                "Running 20–40%",
+                                "Running 40–60%",
+                                "Running 60–80%",
+                                "Running 80–100%",
+                            ],
+                            ["#edf8e9", "#bae4b3", "#74c476", "#31a354", "#006d2c"],
+                        ));
+                        Some(ScaledValue::RunningThreads)
+                    }
+                    HeatmapColor::ThreadState => None,
+                    HeatmapColor::Memory => {
+                        pieces.extend(scaled_pieces(
+                            [
+                                "RSS 0–20% of peak",
+                                "RSS 20–40%",
+                                "RSS 40–60%",
+                                "RSS 60–80%",
+                                "RSS 80–100%",
+                            ],
+                            ["#f2f0f7", "#cbc9e2", "#9e9ac8", "#756bb1", "#54278f"],
+                        ));
+                        Some(ScaledValue::Memory {
+                            peak_mb: memory_peak_mb,
+                        })
+                    }
+                    HeatmapColor::CpuTime => {
+                        pieces.extend(scaled_pieces(
+                            [
+                                "CPU 0–20%",
+                                "CPU 20–40%",
+                                "CPU 40–60%",
+                                "CPU 60–80%",
+                                "CPU 80–100%",
+                            ],
+                            ["#fee5d9", "#fcae91", "#fb6a4a", "#de2d26", "#a50f15"],
+                        ));
+                        Some(ScaledValue::CpuTime)
+                    }
+                };
+                if *running_thread_rows {
+                    pieces.extend(
+                        [
+                            ("No threads running", "#ffffd4"),
+                            ("1 running", "#fed98e"),
+                            ("2–3 running", "#fe9929"),
+                            ("4–7 running", "#d95f0e"),
+                            ("8+ running", "#993404"),
+                        ]
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, (label, color))| {
+                            let value = 208.0 + i as f64;
+                            Piece::new(value, value, label, color)
+                        }),
+                    );
+                }
+
+                // Compare mode: run A (loaded) and run B overlaid on relative time
+                let compare_option = comparison.as_ref().and_then(|other| {
+                    let (cpu_a, gpu_a) = relative_utilization(&columns);
+                    let (cpu_b, gpu_b) = relative_utilization(other.columns());
+                    let series: Vec<_> = [
+                        ("A: CPU", cpu_a, LineType::Solid),
+                        ("B: CPU", cpu_b, LineType::Dashed),
+                        ("A: GPU", gpu_a, LineType::Solid),
+                        ("B: GPU", gpu_b, LineType::Dashed),
+                    ]
+                    .into_iter()
+                    .filter(|(_, points, _)| !points.is_empty())
+                    .map(|(name, points, line)| {
+                        Series::line(name, moving_average(&points, *smoothing)).with_line_type(line)
+                    })
+                    .collect();
+                    (!series.is_empty()).then(|| ChartOption {
+                        title: Some(Title {
+                            text: "Run Comparison: CPU and Mean GPU Load (%)".to_string(),
+                            subtext: Some(format!("B: {}", other.name())),
+                            subtext_style: None,
+                        }),
+                        tooltip: Some(Tooltip::axis()),
+                        legend: Some(Legend::at(45.0)),
+                        grid: Some(Grid {
+                            top: Some(Length::Px(90.0)),
+                            ..Grid::default()
+                        }),
+                        x_axis: vec![Axis {
+                            name: Some("s".to_string()),
+                            axis_label: Some(AxisLabel {
+                                formatter: Some("{value} s".to_string()),
+                                ..AxisLabel::default()
+                            }),
+                            ..Axis::default()
+                        }],
+                        y_axis: vec![Axis::from_zero("%")],
+                        data_zoom: vec![DataZoom::slider(), DataZoom::inside()],
+                        series,
+                        ..ChartOption::default()
+                    })
+                });
+
+                // Render chart
+                let height = label_order.len() * row_layout.row_height;
+                let spans_days = matches!((trace_start, trace_end), (Some(start), Some(end)) if end - start >= 86_400_000.0);
+                let label_format = if spans_days {
+                    "%m-%d %H:%M:%S"
+                } else {
+                    "%H:%M:%S"
+                };
+                let x_labels: Vec<String> = columns
+                    .timestamps
+                    .iter()
+                    .zip(snapshot_times)
+                    .map(|(timestamp, time)| {
+                        time.and_then(|ms| DateTime::from_timestamp_millis(ms as i64))
+                            .map(|datetime| datetime.format(label_format).to_string())
+                            .unwrap_or_else(|| timestamp.clone())
+                    })
+                    .collect();
+                let y_labels: Vec<String> = label_order;
+
+                // Tooltips and the expand/collapse markers on the row labels are
+                // added as functions by `draw_heatmap`
+                let heatmap_option = ChartOption {
+                    grid: Some(Grid {
+                        height: Some(Length::Percent(80.0)),
+                        top: Some(Length::Percent(10.0)),
+                        left: Some(Length::Px(row_layout.gutter as f64)),
+                        ..Grid::default()
+                    }),
+                    x_axis: vec![Axis {
+                        split_area: Some(Show { show: true }),
+                        ..Axis::category(x_labels.clone())
+                    }],
+                    y_axis: vec![Axis {
+                        split_area: Some(Show { show: true }),
+                        axis_label: Some(AxisLabel {
+                            interval: Some(0),
+                            align: Some("left"),
+                            margin: Some(row_layout.gutter),
+                            font_size: Some(row_layout.font_size),
+                            ..AxisLabel::default()
+                        }),
+                        trigger_event: true,
+                        inverse: true,
+                        ..Axis::category(y_labels.clone())
+                    }],
+                    visual_map: Some(VisualMap {
+                        kind: Some("piecewise"),
+                        dimension: Some(2),
+                        calculable: true,
+                        top: Some(Length::Keyword("center")),
+                        left: Some(Length::Keyword("right")),
+                        pieces,
+                        ..VisualMap::default()
+                    }),
+                    series: vec![Series {
+                        name: "State".to_string(),
+                        kind: SeriesType::Heatmap,
+                        data: std::mem::take(&mut matrix.cells),
+                        show_symbol: None,
+                        label: Some(Show { show: false }),
+                        emphasis: Some(Emphasis {
+                            focus: Some("self"),
+                            item_style: Some(ItemStyle {
+                                shadow_blur: Some(10.0),
+                                shadow_color: Some("rgba(0, 0, 0, 0.5)".to_string()),
+                                ..ItemStyle::default()
+                            }),
+                        }),
+                        blur: Some(Emphasis {
+                            focus: None,
+                            item_style: Some(ItemStyle {
+                                opacity: Some(0.3),
+                                ..ItemStyle::default()
+                            }),
+                        }),
+                        ..Series::line("", Vec::new())
+                    }],
+                    ..ChartOption::default()
+                };
+                let core_option = ChartOption {
+                    title: Some(Title {
+                        text: "CPU Load per Core (%)".to_string(),
+                        ..Title::default()
+                    }),
+                    grid: Some(Grid {
+                        top: Some(Length::Px(40.0)),
+                        bottom: Some(Length::Px(30.0)),
+                        left: Some(Length::Px(row_layout.gutter as f64)),
+                        ..Grid::default()
+                    }),
+                    x_axis: vec![Axis {
+                        split_area: Some(Show { show: true }),
+                        ..Axis::category(x_labels.clone())
+                    }],
+                    y_axis: vec![Axis {
+                        axis_label: Some(AxisLabel {
+                            interval: Some(0),
+                            font_size: Some(row_layout.font_size),
+                            ..AxisLabel::default()
+                        }),
+                        inverse: true,
+                        ..Axis::category(core_labels)
+                    }],
+                    visual_map: Some(VisualMap {
+                        min: Some(0.0),
+                        max: Some(100.0),
+                        calculable: true,
+                        top: Some(Length::Keyword("center")),
+                        left: Some(Length::Keyword("right")),
+                        in_range: Some(ColorRange {
+                            color: palette.gpu.to_vec(),
+                        }),
+                        ..VisualMap::default()
+                    }),
+                    series: vec![Series {
+                        name: "Core Load".to_string(),
+                        kind: SeriesType::Heatmap,
+                        show_symbol: None,
+                        ..Series::line("", core_matrix)
+                    }],
+                    ..ChartOption::default()
+                };
+
+                let Some(div) = chart_ref.cast::<HtmlElement>() else {
+                    return;
+                };
+                let _ = div.style().set_property("height", &format!("{height}px"));
+                let mut callbacks = Callbacks::default();
+                let labels = Rc::new(y_labels);
+                let process_cpu = ProcessCpuChart {
+                    theme: *theme,
+                    trace_id: *trace_id,
+                    cpu: process_cpu,
+                    snapshot_times: snapshot_times.clone(),
+                    trace_range: (trace_start, trace_end),
+                    selected: selected_rows.clone(),
+                };
+                process_cpu.draw();
+                draw_heatmap(
+                    &div,
+                    *theme,
+                    *trace_id,
+                    &heatmap_option,
+                    CellTooltip {
+                        trace: trace.clone(),
+                        labels,
+                        details: std::mem::take(&mut matrix.details),
+                        gpu_memory: process_gpu_mem,
+                        scaled: scaled_value,
+                    },
+                    HeatmapClicks {
+                        row_order: row_order.clone(),
+                        on_toggle_row: on_toggle_row.clone(),
+                        on_process_detail: on_process_detail.clone(),
+                        on_raw_json: on_raw_json.clone(),
+                        process_cpu,
+                        links: links.clone(),
+                    },
+                    &mut callbacks,
+                );
+                draw_core_heatmap(
+                    *theme,
+                    *trace_id,
+                    core_height,
+                    &core_option,
+                    trace.clone(),
+                    &mut callbacks,
+                );
+                draw_custom_metrics(*theme, *trace_id, &custom_charts);
+                draw_gantt(
+                    *theme,
+                    *trace_id,
+                    Gantt {
+                        height: gantt_height,
+                        x_labels: &x_labels,
+                        lifetimes,
+                        row_layout: *row_layout,
+                        trace: trace.clone(),
+                    },
+                    &mut callbacks,
+                );
+                draw_compare(*theme, *trace_id, compare_option.as_ref());
+                draw_overview(
+                    *theme,
+                    *trace_id,
+                    Overview {
+                        x_labels: &x_labels,
+                        running: &trace_rows.overview,
+                        color: &state_colors[1],
+                        gutter: row_layout.gutter,
+                        range: (min, max),
+                        trace: trace.clone(),
+                        on_time_range: set_time_range.clone(),
+                    },
+                    &mut callbacks,
+                );
+                *draw_callbacks.borrow_mut() = callbacks;
+
+                // Event markers, a shared zoom and a linked crosshair on every
+                // other chart
+                links.set_timeline(Timeline {
+                    theme: *theme,
+                    snapshot_times: snapshot_times.clone(),
+                    trace: trace.clone(),
+                    min,
+                    max,
+                    events: event_markers,
+                    bookmarks: bookmark_markers,
+                    on_time_range: set_time_range.clone(),
+                    on_range_stats: on_range_stats.clone(),
+                    on_bookmark: on_bookmark.clone(),
+                });
+                links.link_charts();
+            });
+            cancel
+        },
+    );
+
+    html! {
+        <>
+            <div id="overview" style="width:100%;" />
+            <div id="heatmap" ref={chart_ref} style={if props.timeline_view == TimelineView::Heatmap { "width:100%;" } else { "width:100%; display:none;" }} />
+            <div id="process-gantt" style="width:100%;" />
+            <div id="core-heatmap" style="width:100%;" />
+            { props.children.clone() }
+            <div id="custom-metrics" style="width:100%;" />
+            <div id="compare-line" style="width:100%; margin-top:2em;" />
+        </>
+    }
+}
@@ -0,0 +1,888 @@
+//! The charts of `HeatmapPanel`, drawn from their built options along with
+//! the tooltips and click handlers that look up the data behind each cell.
+
+use super::heatmap_rows::RowOrder;
+use crate::charts::{
+    dispose_chart, draw_chart, draw_chart_in, is_shown, set_path, to_js, Action, AxisFinder,
+    BrushArea, BrushEvent, BrushOption, Callbacks, ECharts,
+};
+use crate::links::{ChartLinks, TIMELINE_GROUP};
+use crate::settings::{RowLayout, Theme};
+use crate::state::Trace;
+use indexmap::IndexSet;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use timeline_core::echarts::{
+    AreaStyle, Axis, AxisLabel, AxisType, Brush, ChartOption, Encode, Grid, ItemStyle, Legend,
+    Length, OutOfBrush, Series, SeriesType, Title, Tooltip,
+};
+use timeline_core::series::line_series;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, HtmlElement, MouseEvent, ScrollBehavior, ScrollToOptions};
+use yew::Callback;
+
+#[wasm_bindgen]
+extern "C" {
+    /// Helpers ECharts passes to the `renderItem` function of a custom series.
+    type RenderApi;
+
+    /// Value of `dimension` of the item rendered
+    #[wasm_bindgen(method)]
+    fn value(this: &RenderApi, dimension: usize) -> f64;
+
+    /// Pixel position of a data point
+    #[wasm_bindgen(method)]
+    fn coord(this: &RenderApi, point: &JsValue) -> JsValue;
+
+    /// Pixel size of a data extent, e.g. one category by one category
+    #[wasm_bindgen(method)]
+    fn size(this: &RenderApi, extent: &JsValue) -> JsValue;
+
+    /// The item's visual style merged with `extra`
+    #[wasm_bindgen(method)]
+    fn style(this: &RenderApi, extra: &JsValue) -> JsValue;
+
+    /// The part of `rect` inside `clip`, if any.
+    #[wasm_bindgen(js_namespace = ["echarts", "graphic"], js_name = clipRectByRect)]
+    fn clip_rect_by_rect(rect: &JsValue, clip: &JsValue) -> JsValue;
+}
+
+/// A heatmap cell: snapshot index, row, value and the index of its tooltip
+/// detail in `HeatmapCells::details`.
+pub(super) type CellData = (usize, usize, u8, usize);
+
+/// Id of the per-process CPU chart, which follows the heatmap rows clicked.
+const PROCESS_CPU_ID: &str = "process-cpu-line";
+
+/// Tooltip parameters of an item, with its data.
+#[derive(Deserialize)]
+struct ItemParams<D> {
+    data: D,
+}
+
+/// Tooltip parameters of one series at the x value under the mouse.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AxisParams {
+    data_index: usize,
+    #[serde(default)]
+    value: Option<f64>,
+}
+
+/// Parameters of a mouse event on the heatmap, over a cell or a row label.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HeatmapEvent {
+    component_type: String,
+    #[serde(default)]
+    series_index: Option<usize>,
+    /// The label of a row label
+    #[serde(default, with = "serde_wasm_bindgen::preserve")]
+    value: JsValue,
+    /// The `CellData` of a cell
+    #[serde(default, with = "serde_wasm_bindgen::preserve")]
+    data: JsValue,
+    #[serde(default)]
+    event: Option<RendererEvent>,
+}
+
+/// The renderer's event behind a chart event.
+#[derive(Deserialize)]
+struct RendererEvent {
+    /// The browser's event
+    #[serde(default, with = "serde_wasm_bindgen::preserve")]
+    event: JsValue,
+}
+
+impl HeatmapEvent {
+    fn label(&self) -> Option<String> {
+        (self.component_type == "yAxis")
+            .then(|| self.value.as_string())
+            .flatten()
+    }
+
+    fn cell(&self) -> Option<CellData> {
+        (self.component_type == "series" && self.series_index == Some(0))
+            .then(|| serde_wasm_bindgen::from_value(self.data.clone()).ok())
+            .flatten()
+    }
+
+    fn shift_key(&self) -> bool {
+        self.event.as_ref().is_some_and(|event| {
+            event
+                .event
+                .dyn_ref::<MouseEvent>()
+                .is_some_and(MouseEvent::shift_key)
+        })
+    }
+}
+
+/// What the 108–207 values of the non-default heatmap colorings stand for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum ScaledValue {
+    /// Share of a process's threads running
+    RunningThreads,
+    /// RSS as a share of the peak RSS of any process
+    Memory { peak_mb: f64 },
+    /// CPU time of a thread as a share of one core
+    CpuTime,
+}
+
+impl ScaledValue {
+    fn describe(self, value: u8) -> String {
+        let scaled = f64::from(value.saturating_sub(108));
+        match self {
+            ScaledValue::RunningThreads => {
+                format!("Running threads: {}%", (scaled / 0.99).round())
+            }
+            ScaledValue::Memory { peak_mb } => {
+                format!("Memory: {scaled}% of {peak_mb:.0} MB peak")
+            }
+            ScaledValue::CpuTime => {
+                format!("Thread CPU: {}% of one core", (scaled / 0.99).round())
+            }
+        }
+    }
+}
+
+/// Tooltips of the heatmap cells.
+pub(super) struct CellTooltip {
+    pub(super) trace: Rc<Trace>,
+    /// Labels of the rows drawn
+    pub(super) labels: Rc<Vec<String>>,
+    /// Context of each cell's row, indexed by the cell's fourth value
+    pub(super) details: IndexSet<String>,
+    /// GPU memory (MB) held by each process row, by snapshot index
+    pub(super) gpu_memory: HashMap<String, Vec<(usize, f64)>>,
+    pub(super) scaled: Option<ScaledValue>,
+}
+
+impl CellTooltip {
+    fn format(&self, (timestamp, row, value, detail): CellData) -> String {
+        let gpu_memory = || {
+            self.labels
+                .get(row)
+                .and_then(|label| self.gpu_memory.get(label))
+                .and_then(|points| points.iter().find(|&&(index, _)| index == timestamp))
+                .map(|(_, mb)| format!("<br/>GPU Memory: {} MB", mb.round()))
+                .unwrap_or_default()
+        };
+        let text = match value {
+            208.. => {
+                let running = ["0", "1", "2–3", "4–7", "8+"];
+                let running = running.get(usize::from(value - 208)).unwrap_or(&"?");
+                format!("Running threads: {running}")
+            }
+            108.. => {
+                let scaled = self.scaled.map(|scaled| scaled.describe(value));
+                format!("{}{}", scaled.unwrap_or_default(), gpu_memory())
+            }
+            7.. => format!("GPU Load: {}%", value - 7),
+            _ => {
+                let states = ["-", "R", "S", "Z", "T", "D", "I"];
+                let state = states.get(usize::from(value)).unwrap_or(&"?");
+                format!("Thread State: {state}{}", gpu_memory())
+            }
+        };
+        let time = timestamp_at(&self.trace, timestamp);
+        let detail = self.details.get_index(detail).map_or("", String::as_str);
+        format!(
+            "Time: {time}<br/>{text}<div style=\"max-width: 40em; white-space: normal; overflow-wrap: anywhere;\">{detail}</div>"
+        )
+    }
+}
+
+fn timestamp_at(trace: &Trace, index: usize) -> String {
+    let columns = trace.columns();
+    columns.timestamps.get(index).cloned().unwrap_or_default()
+}
+
+/// Highlights the cells of the hovered heatmap row, dimming the rest.
+struct RowHover {
+    chart: ECharts,
+    /// Indices in the series data of each row's cells
+    row_cells: Vec<Vec<usize>>,
+    hovered: Cell<Option<usize>>,
+}
+
+impl RowHover {
+    fn hover(&self, row: Option<usize>) {
+        let previous = self.hovered.replace(row);
+        if previous == row {
+            return;
+        }
+        let cells = |row: Option<usize>| {
+            row.and_then(|row| self.row_cells.get(row))
+                .filter(|cells| !cells.is_empty())
+                .cloned()
+        };
+        if let Some(data_index) = cells(previous) {
+            self.chart.dispatch(&Action::Downplay {
+                series_index: 0,
+                data_index,
+                escape_connect: true,
+            });
+        }
+        if let Some(data_index) = cells(row) {
+            self.chart.dispatch(&Action::Highlight {
+                series_index: 0,
+                data_index,
+                escape_connect: true,
+            });
+        }
+    }
+}
+
+/// The per-process CPU chart, of the process rows selected by clicking them
+/// in the heatmap.
+pub(super) struct ProcessCpuChart {
+    pub(super) theme: Theme,
+    pub(super) trace_id: u32,
+    /// Smoothed CPU (%) of each process row in the selected range, by label
+    pub(super) cpu: BTreeMap<String, Vec<(usize, f64)>>,
+    pub(super) snapshot_times: Vec<Option<f64>>,
+    pub(super) trace_range: (Option<f64>, Option<f64>),
+    /// Labels of the rows shown, kept from one draw to the next
+    pub(super) selected: Rc<RefCell<Vec<String>>>,
+}
+
+impl ProcessCpuChart {
+    pub(super) fn draw(&self) {
+        let mut selected = self.selected.borrow_mut();
+        selected.retain(|label| self.cpu.contains_key(label));
+        let series = selected
+            .iter()
+            .map(|label| line_series(label, "", &self.cpu[label], &self.snapshot_times))
+            .map(|series| series.stacked("cpu"))
+            .collect();
+        let option = ChartOption {
+            title: Some(Title {
+                text: "Process CPU Usage Over Time (%)".to_string(),
+                subtext: Some("Click a process row in the heatmap to add or remove it".to_string()),
+                subtext_style: None,
+            }),
+            tooltip: Some(Tooltip::axis()),
+            legend: Some(Legend::at(45.0)),
+            grid: Some(Grid {
+                top: Some(Length::Px(90.0)),
+                ..Grid::default()
+            }),
+            x_axis: vec![Axis::time(self.trace_range.0, self.trace_range.1)],
+            y_axis: vec![Axis::from_zero("%")],
+            series,
+            ..ChartOption::default()
+        };
+        draw_chart(PROCESS_CPU_ID, self.theme, self.trace_id, &option);
+    }
+
+    /// Add the row to the chart, or remove it, if it is a process row.
+    fn toggle(&self, label: &str) -> bool {
+        if !self.cpu.contains_key(label) {
+            return false;
+        }
+        {
+            let mut selected = self.selected.borrow_mut();
+            match selected.iter().position(|selected| selected == label) {
+                Some(index) => {
+                    selected.remove(index);
+                }
+                None => selected.push(label.to_string()),
+            }
+        }
+        self.draw();
+        true
+    }
+}
+
+/// What the heatmap does with clicks on its cells and row labels.
+pub(super) struct HeatmapClicks {
+    pub(super) row_order: Rc<RowOrder>,
+    pub(super) on_toggle_row: Callback<String>,
+    pub(super) on_process_detail: Callback<(usize, String)>,
+    pub(super) on_raw_json: Callback<(usize, String)>,
+    pub(super) process_cpu: ProcessCpuChart,
+    pub(super) links: Rc<ChartLinks>,
+}
+
+/// Draw the timeline heatmap in `div` with its tooltips and expand/collapse
+/// markers, highlighting the row under the mouse. Clicking a row label
+/// toggles it, clicking a cell shows its process and adds it to the process
+/// CPU chart, and shift-clicking shows the collector output behind it.
+pub(super) fn draw_heatmap(
+    div: &Element,
+    theme: Theme,
+    trace_id: u32,
+    option: &ChartOption<Vec<CellData>>,
+    tooltip: CellTooltip,
+    clicks: HeatmapClicks,
+    callbacks: &mut Callbacks,
+) {
+    let Some(js_option) = to_js(option) else {
+        return;
+    };
+    let labels = tooltip.labels.clone();
+    let formatter = Closure::<dyn Fn(JsValue) -> String>::new(move |params| {
+        serde_wasm_bindgen::from_value::<ItemParams<CellData>>(params)
+            .map(|params| tooltip.format(params.data))
+            .unwrap_or_default()
+    });
+    set_path(
+        &js_option,
+        &["tooltip", "formatter"],
+        &callbacks.function(formatter),
+    );
+    // Expand/collapse marker after the host prefix and indent
+    let prefix = Regex::new(r"^((?:\[[^\]]*\] )?\s*(?:└─ )?)").expect("valid pattern");
+    let marked: HashMap<String, String> = clicks
+        .row_order
+        .row_markers
+        .iter()
+        .map(|(label, marker)| {
+            let replacement = format!("${{1}}{marker} ");
+            (
+                label.clone(),
+                prefix.replace(label, replacement).into_owned(),
+            )
+        })
+        .collect();
+    let formatter = Closure::<dyn Fn(String) -> String>::new(move |label: String| {
+        marked.get(&label).cloned().unwrap_or(label)
+    });
+    set_path(
+        &js_option,
+        &["yAxis", "axisLabel", "formatter"],
+        &callbacks.function(formatter),
+    );
+    let Some(chart) = draw_chart_in(div, theme, trace_id, &js_option) else {
+        return;
+    };
+    chart.set_group(TIMELINE_GROUP);
+
+    // Hovering a cell or row label highlights the whole row and dims the rest
+    let mut row_cells: Vec<Vec<usize>> = vec![Vec::new(); labels.len()];
+    for (index, &(_, row, ..)) in option.series[0].data.iter().enumerate() {
+        if let Some(cells) = row_cells.get_mut(row) {
+            cells.push(index);
+        }
+    }
+    let hover = Rc::new(RowHover {
+        chart: chart.clone(),
+        row_cells,
+        hovered: Cell::new(None),
+    });
+    chart.bind("mouseover", callbacks, {
+        let (hover, labels) = (hover.clone(), labels.clone());
+        move |event| {
+            let Ok(event) = serde_wasm_bindgen::from_value::<HeatmapEvent>(event) else {
+                return;
+            };
+            let row = match event.label() {
+                Some(label) => labels.iter().position(|row| *row == label),
+                None => event.cell().map(|(_, row, ..)| row),
+            };
+            if row.is_some() {
+                hover.hover(row);
+            }
+        }
+    });
+    chart.bind("mouseout", callbacks, {
+        let hover = hover.clone();
+        move |_| hover.hover(None)
+    });
+
+    let links = clicks.links.clone();
+    chart.bind("click", callbacks, {
+        let labels = labels.clone();
+        move |event| {
+            let Ok(event) = serde_wasm_bindgen::from_value::<HeatmapEvent>(event) else {
+                return;
+            };
+            if let Some(label) = event.label() {
+                let row_order = &clicks.row_order;
+                if let Some(key) = row_order.container_headings.get(&label) {
+                    clicks.on_toggle_row.emit(key.clone());
+                } else if row_order.row_markers.contains_key(&label) {
+                    clicks.on_toggle_row.emit(label);
+                }
+                return;
+            }
+            let Some((timestamp, row, ..)) = event.cell() else {
+                return;
+            };
+            let Some(label) = labels.get(row) else {
+                return;
+            };
+            if event.shift_key() {
+                clicks.on_raw_json.emit((timestamp, label.clone()));
+                return;
+            }
+            clicks.on_process_detail.emit((timestamp, label.clone()));
+            if clicks.process_cpu.toggle(label) {
+                clicks.links.link_charts();
+            }
+        }
+    });
+
+    // Scroll the page to a row (e.g. from the busiest processes table)
+    let finder = to_js(&AxisFinder::YAxisIndex(0)).unwrap_or_default();
+    links.set_row_scroller(move |label| {
+        let Some(row) = labels.iter().position(|row| row == label) else {
+            return false;
+        };
+        if !is_shown(&chart) {
+            return false;
+        }
+        let y = chart
+            .convert_to_pixel(&finder, &label.into())
+            .as_f64()
+            .unwrap_or_default();
+        let window = gloo::utils::window();
+        let page_top =
+            chart.dom().get_bounding_client_rect().top() + window.scroll_y().unwrap_or(0.0);
+        let view_height = window
+            .inner_height()
+            .ok()
+            .and_then(|height| height.as_f64())
+            .unwrap_or(0.0);
+        let options = ScrollToOptions::new();
+        options.set_top(page_top + y - view_height / 2.0);
+        options.set_behavior(ScrollBehavior::Smooth);
+        window.scroll_to_with_scroll_to_options(&options);
+        hover.hover(Some(row));
+        true
+    });
+}
+
+/// Size the chart div `id` to `height` pixels, disposing of its chart when
+/// that is 0. None when there is nothing to draw.
+fn sized_div(id: &str, height: usize) -> Option<HtmlElement> {
+    let div = gloo::utils::document()
+        .get_element_by_id(id)?
+        .dyn_into::<HtmlElement>()
+        .ok()?;
+    let _ = div.style().set_property("height", &format!("{height}px"));
+    if height == 0 {
+        dispose_chart(&div);
+        return None;
+    }
+    Some(div)
+}
+
+/// Draw the per-core load heatmap `height` pixels high.
+pub(super) fn draw_core_heatmap(
+    theme: Theme,
+    trace_id: u32,
+    height: usize,
+    option: &ChartOption<Vec<(usize, usize, f64)>>,
+    trace: Rc<Trace>,
+    callbacks: &mut Callbacks,
+) {
+    let Some(div) = sized_div("core-heatmap", height) else {
+        return;
+    };
+    let Some(js_option) = to_js(option) else {
+        return;
+    };
+    let labels = option
+        .y_axis
+        .first()
+        .and_then(|axis| axis.data.clone())
+        .unwrap_or_default();
+    let formatter = Closure::<dyn Fn(JsValue) -> String>::new(move |params| {
+        let Ok(ItemParams {
+            data: (timestamp, row, load),
+        }) = serde_wasm_bindgen::from_value::<ItemParams<(usize, usize, f64)>>(params)
+        else {
+            return String::new();
+        };
+        let label = labels.get(row).map_or("", String::as_str);
+        format!(
+            "Time: {}<br/>{label} Load: {}%",
+            timestamp_at(&trace, timestamp),
+            load.round()
+        )
+    });
+    set_path(
+        &js_option,
+        &["tooltip", "formatter"],
+        &callbacks.function(formatter),
+    );
+    if let Some(chart) = draw_chart_in(&div, theme, trace_id, &js_option) {
+        chart.set_group(TIMELINE_GROUP);
+    }
+}
+
+/// Draw a chart per custom metric, reusing the chart divs already there.
+pub(super) fn draw_custom_metrics(theme: Theme, trace_id: u32, options: &[ChartOption]) {
+    let document = gloo::utils::document();
+    let Some(container) = document.get_element_by_id("custom-metrics") else {
+        return;
+    };
+    // Drop the divs of metrics no longer there
+    while container.child_element_count() as usize > options.len() {
+        let Some(last) = container.last_element_child() else {
+            break;
+        };
+        dispose_chart(&last);
+        last.remove();
+    }
+    for (index, option) in options.iter().enumerate() {
+        let div = match container.children().item(index as u32) {
+            Some(div) => div,
+            None => {
+                let Ok(div) = document.create_element("div") else {
+                    return;
+                };
+                let _ = div.set_attribute("style", "width:100%; height:300px; margin-top:2em;");
+                let _ = container.append_child(&div);
+                div
+            }
+        };
+        if let Some(option) = to_js(option) {
+            draw_chart_in(&div, theme, trace_id, &option);
+        }
+    }
+}
+
+/// A rectangle in pixels.
+#[derive(Serialize)]
+struct Rect {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+/// Parameters of `renderItem` of a custom series.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RenderParams {
+    /// Area of the grid, to clip to
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    coord_sys: JsValue,
+}
+
+/// Style of a graphic element, as extended by `RenderApi::style`.
+#[derive(Serialize)]
+struct Fill {
+    fill: &'static str,
+}
+
+/// A graphic element drawn by `renderItem`.
+#[derive(Serialize)]
+struct RectElement {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    shape: JsValue,
+    #[serde(with = "serde_wasm_bindgen::preserve")]
+    style: JsValue,
+}
+
+/// The process lifetimes, a bar for each process from its first to its last
+/// snapshot.
+pub(super) struct Gantt<'a> {
+    /// 0 when the chart isn't shown
+    pub(super) height: usize,
+    pub(super) x_labels: &'a [String],
+    /// Process label, first and last snapshot index, top to bottom
+    pub(super) lifetimes: Vec<(String, usize, usize)>,
+    pub(super) row_layout: RowLayout,
+    pub(super) trace: Rc<Trace>,
+}
+
+pub(super) fn draw_gantt(theme: Theme, trace_id: u32, gantt: Gantt, callbacks: &mut Callbacks) {
+    let Gantt {
+        height,
+        x_labels,
+        lifetimes,
+        row_layout,
+        trace,
+    } = gantt;
+    let Some(div) = sized_div("process-gantt", height) else {
+        return;
+    };
+    let labels = lifetimes.iter().map(|(name, ..)| name.clone()).collect();
+    let bars: Vec<_> = lifetimes
+        .iter()
+        .enumerate()
+        .map(|(row, &(_, first, last))| (row, first, last))
+        .collect();
+    let Some(option) = to_js(&ChartOption {
+        title: Some(Title {
+            text: "Process Lifetimes".to_string(),
+            ..Title::default()
+        }),
+        grid: Some(Grid {
+            top: Some(Length::Px(40.0)),
+            left: Some(Length::Px(row_layout.gutter as f64)),
+            ..Grid::default()
+        }),
+        x_axis: vec![Axis::category(x_labels.to_vec())],
+        y_axis: vec![Axis {
+            axis_label: Some(AxisLabel {
+                interval: Some(0),
+                align: Some("left"),
+                margin: Some(row_layout.gutter),
+                font_size: Some(row_layout.font_size),
+                ..AxisLabel::default()
+            }),
+            inverse: true,
+            ..Axis::category(labels)
+        }],
+        // Bars from the first (dimension 1) to the last snapshot (2) of a row (0)
+        series: vec![Series {
+            kind: SeriesType::Custom,
+            show_symbol: None,
+            encode: Some(Encode {
+                x: vec![1, 2],
+                y: vec![0],
+            }),
+            ..Series::line("", bars)
+        }],
+        ..ChartOption::default()
+    }) else {
+        return;
+    };
+    let formatter = Closure::<dyn Fn(JsValue) -> String>::new(move |params| {
+        let Ok(ItemParams {
+            data: (row, first, last),
+        }) = serde_wasm_bindgen::from_value::<ItemParams<(usize, usize, usize)>>(params)
+        else {
+            return String::new();
+        };
+        format!(
+            "{}<br/>First seen: {}<br/>Last seen: {}<br/>Snapshots: {}",
+            lifetimes.get(row).map_or("", |(name, ..)| name.as_str()),
+            timestamp_at(&trace, first),
+            timestamp_at(&trace, last),
+            last - first + 1
+        )
+    });
+    set_path(
+        &option,
+        &["tooltip", "formatter"],
+        &callbacks.function(formatter),
+    );
+    let render_item = Closure::<dyn Fn(JsValue, RenderApi) -> JsValue>::new(render_lifetime);
+    set_path(
+        &option,
+        &["series", "renderItem"],
+        &callbacks.function(render_item),
+    );
+    if let Some(chart) = draw_chart_in(&div, theme, trace_id, &option) {
+        chart.set_group(TIMELINE_GROUP);
+    }
+}
+
+/// A process lifetime bar, spanning its first to last snapshot column.
+fn render_lifetime(params: JsValue, api: RenderApi) -> JsValue {
+    let point = |dimension| -> Option<(f64, f64)> {
+        let value = to_js(&(api.value(dimension), api.value(0)))?;
+        serde_wasm_bindgen::from_value(api.coord(&value)).ok()
+    };
+    let cell = to_js(&(1, 1))
+        .and_then(|extent| serde_wasm_bindgen::from_value::<(f64, f64)>(api.size(&extent)).ok());
+    let (Some(start), Some(end), Some(cell), Ok(params)) = (
+        point(1),
+        point(2),
+        cell,
+        serde_wasm_bindgen::from_value::<RenderParams>(params),
+    ) else {
+        return JsValue::NULL;
+    };
+    let rect = Rect {
+        x: start.0 - cell.0 / 2.0,
+        y: start.1 - cell.1 * 0.3,
+        width: end.0 - start.0 + cell.0,
+        height: cell.1 * 0.6,
+    };
+    let (Some(rect), Some(fill)) = (to_js(&rect), to_js(&Fill { fill: "#4575b4" })) else {
+        return JsValue::NULL;
+    };
+    to_js(&RectElement {
+        kind: "rect",
+        shape: clip_rect_by_rect(&rect, &params.coord_sys),
+        style: api.style(&fill),
+    })
+    .unwrap_or(JsValue::NULL)
+}
+
+/// Draw the run comparison, or remove it when there is no run to compare.
+pub(super) fn draw_compare(
+    theme: Theme,
+    trace_id: u32,
+    option: Option<&ChartOption<Vec<(f64, f64)>>>,
+) {
+    let height = if option.is_some() { 300 } else { 0 };
+    if let (Some(_), Some(option)) = (sized_div("compare-line", height), option) {
+        draw_chart("compare-line", theme, trace_id, option);
+    }
+}
+
+/// The overview strip of running threads over the whole trace, where
+/// brushing a range selects it.
+pub(super) struct Overview<'a> {
+    pub(super) x_labels: &'a [String],
+    /// Fraction of threads running in each snapshot
+    pub(super) running: &'a [Option<f64>],
+    pub(super) color: &'a str,
+    pub(super) gutter: usize,
+    /// Selected range of snapshot indices
+    pub(super) range: (usize, usize),
+    pub(super) trace: Rc<Trace>,
+    pub(super) on_time_range: Callback<(usize, usize)>,
+}
+
+pub(super) fn draw_overview(
+    theme: Theme,
+    trace_id: u32,
+    overview: Overview,
+    callbacks: &mut Callbacks,
+) {
+    let Some(div) = sized_div("overview", 60) else {
+        return;
+    };
+    let Some(option) = to_js(&ChartOption {
+        tooltip: Some(Tooltip::axis()),
+        grid: Some(Grid {
+            top: Some(Length::Px(5.0)),
+            bottom: Some(Length::Px(5.0)),
+            left: Some(Length::Px(overview.gutter as f64)),
+            ..Grid::default()
+        }),
+        x_axis: vec![Axis {
+            show: Some(false),
+            ..Axis::category(overview.x_labels.to_vec())
+        }],
+        y_axis: vec![Axis {
+            kind: AxisType::Value,
+            min: Some(0.0),
+            max: Some(1.0),
+            show: Some(false),
+            ..Axis::default()
+        }],
+        brush: Some(Brush {
+            out_of_brush: Some(OutOfBrush { color_alpha: 0.3 }),
+            ..Brush::x_range()
+        }),
+        series: vec![Series {
+            area_style: Some(AreaStyle {}),
+            item_style: Some(ItemStyle::color(overview.color)),
+            ..Series::line("", overview.running)
+        }],
+        ..ChartOption::default()
+    }) else {
+        return;
+    };
+    let trace = overview.trace.clone();
+    let formatter = Closure::<dyn Fn(JsValue) -> String>::new(move |params| {
+        let Some(params) = serde_wasm_bindgen::from_value::<Vec<AxisParams>>(params)
+            .ok()
+            .and_then(|params| params.into_iter().next())
+        else {
+            return String::new();
+        };
+        format!(
+            "Time: {}<br/>Running threads: {}%",
+            timestamp_at(&trace, params.data_index),
+            (params.value.unwrap_or(0.0) * 100.0).round()
+        )
+    });
+    set_path(
+        &option,
+        &["tooltip", "formatter"],
+        &callbacks.function(formatter),
+    );
+    let Some(chart) = draw_chart_in(&div, theme, trace_id, &option) else {
+        return;
+    };
+    chart.dispatch(&Action::TakeGlobalCursor {
+        key: "brush",
+        brush_option: BrushOption {
+            brush_type: "lineX",
+            brush_mode: "single",
+        },
+    });
+    let (min, max) = overview.range;
+    chart.dispatch(&Action::Brush {
+        areas: vec![BrushArea {
+            brush_type: "lineX".to_string(),
+            x_axis_index: Some(0),
+            coord_range: Some((min as f64, max as f64)),
+        }],
+    });
+    let last = overview.trace.len().saturating_sub(1);
+    let on_time_range = overview.on_time_range;
+    chart.bind("brushEnd", callbacks, move |event| {
+        let Ok(event) = serde_wasm_bindgen::from_value::<BrushEvent>(event) else {
+            return;
+        };
+        // Clearing the brush shows the whole trace again
+        let range = match event.areas.first().and_then(|area| area.coord_range) {
+            Some((start, end)) => (start.round() as usize, end.round() as usize),
+            None => (0, last),
+        };
+        if range != (min, max) {
+            on_time_range.emit(range);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timeline_core::model::Snapshot;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn tooltip(scaled: Option<ScaledValue>) -> CellTooltip {
+        let snapshot = Snapshot {
+            Timestamp: "2024-05-01T12:00:00".to_string(),
+            ..Snapshot::default()
+        };
+        CellTooltip {
+            trace: Rc::new(Trace::new(vec![snapshot])),
+            labels: Rc::new(vec!["python".to_string()]),
+            details: IndexSet::from(["PID 7".to_string()]),
+            gpu_memory: HashMap::from([("python".to_string(), vec![(0, 511.6)])]),
+            scaled,
+        }
+    }
+
+    /// The tooltip text between the time and the detail.
+    fn text(tooltip: &CellTooltip, value: u8) -> String {
+        let html = tooltip.format((0, 0, value, 0));
+        let text = html.strip_prefix("Time: 2024-05-01T12:00:00<br/>").unwrap();
+        assert!(text.ends_with(">PID 7</div>"));
+        text[..text.find("<div").unwrap()].to_string()
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn describes_each_kind_of_cell() {
+        let tooltip = tooltip(Some(ScaledValue::Memory { peak_mb: 2048.0 }));
+        assert_eq!(text(&tooltip, 1), "Thread State: R<br/>GPU Memory: 512 MB");
+        assert_eq!(text(&tooltip, 57), "GPU Load: 50%");
+        assert_eq!(
+            text(&tooltip, 158),
+            "Memory: 50% of 2048 MB peak<br/>GPU Memory: 512 MB"
+        );
+        assert_eq!(text(&tooltip, 210), "Running threads: 2–3");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn scales_shares_of_threads_and_cores_to_percent() {
+        assert_eq!(
+            ScaledValue::RunningThreads.describe(207),
+            "Running threads: 100%"
+        );
+        assert_eq!(
+            ScaledValue::CpuTime.describe(108),
+            "Thread CPU: 0% of one core"
+        );
+    }
+}
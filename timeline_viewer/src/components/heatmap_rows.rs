@@ -0,0 +1,566 @@
+use crate::settings::RowSort;
+use crate::state::{RowOptions, Trace};
+use indexmap::IndexMap;
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use timeline_core::columns::{TraceColumns, NO_STATE};
+use timeline_core::matrix::{row_id, state_value, RowFilter, RowId, RowIds, RowKind};
+
+#[derive(Debug, Clone)]
+struct LabelNode {
+    label: String,
+    /// Set on process and thread rows
+    row: Option<RowId>,
+    /// Set on container headings, which have no data of their own
+    container: Option<String>,
+    children: IndexMap<NodeKey, LabelNode>,
+}
+
+impl LabelNode {
+    fn row(row: RowId, row_ids: &RowIds) -> Self {
+        LabelNode {
+            label: row_ids.label(row).to_string(),
+            row: Some(row),
+            container: None,
+            children: IndexMap::new(),
+        }
+    }
+}
+
+/// Process and thread rows are keyed by their interned id, container
+/// headings by their heading key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum NodeKey {
+    Row(RowId),
+    Heading(String),
+}
+
+/// Add the process and thread rows in `children` (in interned order) below
+/// `node`, each process with the rows below it.
+fn insert_rows(
+    node: &mut LabelNode,
+    children: &[RowId],
+    parent_container: Option<&str>,
+    below: &HashMap<Option<RowId>, Vec<RowId>>,
+    columns: &TraceColumns,
+) {
+    let row_ids = &columns.rows;
+    for &row in children {
+        let Some(proc) = columns.processes.get(&row) else {
+            node.children
+                .entry(NodeKey::Row(row))
+                .or_insert_with(|| LabelNode::row(row, row_ids));
+            continue;
+        };
+        // Start a container heading wherever a process leaves its parent's container
+        let node = match proc.container.as_deref() {
+            Some(container) if Some(container) != parent_container => {
+                let prefix = row_ids.prefix(row_ids.row_host(row));
+                let indent = "    ".repeat(row_ids.depth(row));
+                let heading = format!("{prefix}{indent}{container}");
+                node.children
+                    .entry(NodeKey::Heading(heading.clone()))
+                    .or_insert(LabelNode {
+                        label: heading,
+                        row: None,
+                        container: Some(container.to_string()),
+                        children: IndexMap::new(),
+                    })
+            }
+            _ => &mut *node,
+        };
+        let child_node = node
+            .children
+            .entry(NodeKey::Row(row))
+            .or_insert_with(|| LabelNode::row(row, row_ids));
+        let container = proc.container.as_deref().or(parent_container);
+        let rows = below.get(&Some(row)).map_or(&[][..], Vec::as_slice);
+        insert_rows(child_node, rows, container, below, columns);
+    }
+}
+
+/// Container headings are shown with an expand/collapse marker and
+/// recorded in `container_headings` for the click handler. Process rows keep
+/// their label so data still maps onto them; their marker is recorded in
+/// `row_markers` and drawn by the axis label formatter. Processes in
+/// `running_rows` get their running thread count row right below, kept when
+/// the process is collapsed.
+fn flatten_tree(
+    node: &LabelNode,
+    order: &mut RowOrder,
+    collapsed: &HashSet<String>,
+    running_rows: &HashMap<RowId, RowId>,
+    row_ids: &RowIds,
+) {
+    let is_collapsed = collapsed.contains(&node.label);
+    if let Some(container) = &node.container {
+        // The heading key is `{host prefix}{indent}{container}`
+        let indent = node
+            .label
+            .strip_suffix(container.as_str())
+            .unwrap_or_default();
+        let marker = if is_collapsed { "▸" } else { "▾" };
+        let shown = format!("{indent}{marker} container: {container}");
+        order
+            .container_headings
+            .insert(shown.clone(), node.label.clone());
+        order.push(shown, None);
+        if is_collapsed {
+            return;
+        }
+    } else if let Some(row) = node.row {
+        order.push(node.label.clone(), Some(row));
+        if let Some(&running_row) = running_rows.get(&row) {
+            order.push(row_ids.label(running_row).to_string(), Some(running_row));
+        }
+        if !node.children.is_empty() {
+            let marker = if is_collapsed { "▸" } else { "▾" };
+            order.row_markers.insert(node.label.clone(), marker);
+            if is_collapsed {
+                return;
+            }
+        }
+    }
+    for child in node.children.values() {
+        flatten_tree(child, order, collapsed, running_rows, row_ids);
+    }
+}
+
+/// Drop rows that neither match nor lead to a match. A matching
+/// process keeps everything below it.
+fn filter_tree(node: &mut LabelNode, matches: &HashSet<RowId>) -> bool {
+    if node.row.is_some_and(|row| matches.contains(&row)) {
+        return true;
+    }
+    node.children.retain(|_, child| filter_tree(child, matches));
+    !node.children.is_empty()
+}
+
+/// Drop matching rows along with everything below them, and any
+/// container heading left empty.
+fn exclude_from_tree(node: &mut LabelNode, matches: &HashSet<RowId>) {
+    node.children.retain(|_, child| {
+        if child.row.is_some_and(|row| matches.contains(&row)) {
+            return false;
+        }
+        exclude_from_tree(child, matches);
+        child.container.is_none() || !child.children.is_empty()
+    });
+}
+
+/// Container headings total the rows grouped under them. Returns the total
+/// of `node`, which `key` keys in `totals`.
+fn heading_totals(key: &NodeKey, node: &LabelNode, totals: &mut HashMap<NodeKey, usize>) -> usize {
+    let below: usize = node
+        .children
+        .iter()
+        .map(|(key, child)| heading_totals(key, child, totals))
+        .sum();
+    if node.container.is_some() {
+        totals.insert(key.clone(), below);
+        below
+    } else {
+        totals.get(key).copied().unwrap_or_default()
+    }
+}
+
+fn sort_tree(node: &mut LabelNode, sort: RowSort, totals: &HashMap<NodeKey, usize>) {
+    match sort {
+        RowSort::Tree => return,
+        RowSort::RunningTime => node
+            .children
+            .sort_by_cached_key(|key, _| std::cmp::Reverse(totals.get(key).copied())),
+        RowSort::Name => node.children.sort_by(|_, a, _, b| a.label.cmp(&b.label)),
+        RowSort::Pid => node
+            .children
+            .sort_by_cached_key(|_, child| (row_id(&child.label), child.label.clone())),
+    }
+    for child in node.children.values_mut() {
+        sort_tree(child, sort, totals);
+    }
+}
+
+/// Rows of the timeline in display order, before windowing.
+#[derive(Debug, Default)]
+pub(super) struct RowOrder {
+    pub(super) labels: Vec<String>,
+    /// Interned id of each row; None for GPUs and container headings
+    pub(super) ids: Vec<Option<RowId>>,
+    /// Shown container heading -> heading key, for the click handler
+    pub(super) container_headings: HashMap<String, String>,
+    /// Expand/collapse marker of each process row with rows below it
+    pub(super) row_markers: HashMap<String, &'static str>,
+}
+
+impl RowOrder {
+    fn push(&mut self, label: String, id: Option<RowId>) {
+        self.labels.push(label);
+        self.ids.push(id);
+    }
+}
+
+/// What a `RowOrder` is built from besides the trace.
+#[derive(Debug, PartialEq)]
+struct RowOrderKey {
+    /// Without the window, which is applied afterwards
+    rows: RowOptions,
+    collapsed_rows: HashSet<String>,
+    /// Selected range, for the options that depend on it
+    range: Option<(usize, usize)>,
+}
+
+/// The timeline rows and whatever else is derived from the whole trace rather
+/// than the selected range, built once per loaded trace.
+pub(super) struct TraceRows {
+    /// Wall-clock time (ms) of each snapshot, None where it doesn't parse
+    pub(super) snapshot_times: Vec<Option<f64>>,
+    pub(super) trace_start: Option<f64>,
+    pub(super) trace_end: Option<f64>,
+    /// Fraction of threads running in each snapshot, for the overview strip
+    pub(super) overview: Vec<Option<f64>>,
+    /// Sorted, which keeps each host's GPUs together
+    gpu_labels: Vec<String>,
+    pub(super) trace: Rc<Trace>,
+    /// Every process and thread row of the trace, unfiltered
+    tree: LabelNode,
+    thread_rows: HashSet<RowId>,
+    /// Running thread count row of each process that ever had threads
+    running_rows: HashMap<RowId, RowId>,
+    /// The last row order built, reused while its options don't change
+    row_order: RefCell<Option<(RowOrderKey, Rc<RowOrder>)>>,
+}
+
+impl TraceRows {
+    pub(super) fn new(trace: Rc<Trace>) -> Self {
+        let columns = trace.columns();
+        // The axes span the whole trace so the zoom slider shows where the
+        // culled window sits
+        let snapshot_times: Vec<Option<f64>> = columns
+            .micros
+            .iter()
+            .map(|micros| micros.map(|micros| micros / 1e3))
+            .collect();
+        let timed = snapshot_times.iter().flatten();
+        let trace_start = timed.clone().copied().reduce(f64::min);
+        let trace_end = timed.copied().reduce(f64::max);
+
+        let running = state_value(Some("R")) as usize;
+        let overview = columns
+            .state_counts
+            .iter()
+            .zip(&columns.gaps)
+            .map(|(counts, &gap)| {
+                let total: u32 = counts.iter().sum();
+                (!gap && total > 0).then(|| counts[running] as f64 / total as f64)
+            })
+            .collect();
+
+        // CPU-only builds leave out the GPU rows
+        let mut gpu_labels: Vec<String> = if cfg!(feature = "gpu") {
+            columns.gpus.keys().cloned().collect()
+        } else {
+            Vec::new()
+        };
+        gpu_labels.sort();
+
+        // Build process/thread hierarchy tree
+        let mut tree = LabelNode {
+            label: String::new(),
+            row: None,
+            container: None,
+            children: IndexMap::new(),
+        };
+        // Rows are interned in tree order, so each parent before the rows below it
+        let row_ids = &columns.rows;
+        let mut below: HashMap<Option<RowId>, Vec<RowId>> = HashMap::new();
+        for row in row_ids.rows() {
+            let parent = match row_ids.kind(row) {
+                RowKind::Process => columns.processes.get(&row).map(|proc| proc.parent),
+                RowKind::Thread => columns.threads.get(&row).map(|t| Some(t.process)),
+                RowKind::RunningThreads => None,
+            };
+            if let Some(parent) = parent {
+                below.entry(parent).or_default().push(row);
+            }
+        }
+        let roots = below.get(&None).map_or(&[][..], Vec::as_slice);
+        insert_rows(&mut tree, roots, None, &below, &columns);
+        let running_rows = columns
+            .processes
+            .iter()
+            .filter_map(|(&row, proc)| Some((row, proc.running_row?)))
+            .collect();
+        let thread_rows = row_ids
+            .rows()
+            .filter(|&row| row_ids.kind(row) == RowKind::Thread)
+            .collect();
+        drop(columns);
+
+        TraceRows {
+            snapshot_times,
+            trace_start,
+            trace_end,
+            overview,
+            gpu_labels,
+            trace,
+            tree,
+            thread_rows,
+            running_rows,
+            row_order: RefCell::new(None),
+        }
+    }
+
+    /// Whether the timeline starts with GPU rows.
+    pub(super) fn has_gpu_rows(&self) -> bool {
+        !self.gpu_labels.is_empty()
+    }
+
+    /// The rows shown for `rows` and `collapsed_rows` over the selected range
+    /// `min..=max`: GPUs first, then the filtered and sorted process tree.
+    pub(super) fn row_order(
+        &self,
+        min: usize,
+        max: usize,
+        rows: &RowOptions,
+        collapsed_rows: &HashSet<String>,
+    ) -> Rc<RowOrder> {
+        let key = RowOrderKey {
+            rows: RowOptions {
+                row_window: 0,
+                row_offset: 0,
+                ..rows.clone()
+            },
+            collapsed_rows: collapsed_rows.clone(),
+            range: (rows.hide_idle_threads || rows.row_sort == RowSort::RunningTime)
+                .then_some((min, max)),
+        };
+        if let Some((built_for, order)) = &*self.row_order.borrow() {
+            if *built_for == key {
+                return order.clone();
+            }
+        }
+        let order = Rc::new(self.build_row_order(min, max, rows, collapsed_rows));
+        *self.row_order.borrow_mut() = Some((key, order.clone()));
+        order
+    }
+
+    fn build_row_order(
+        &self,
+        min: usize,
+        max: usize,
+        rows: &RowOptions,
+        collapsed_rows: &HashSet<String>,
+    ) -> RowOrder {
+        let columns = self.trace.columns();
+        let mut root = self.tree.clone();
+
+        // Processes-only view drops every thread row
+        if rows.processes_only {
+            exclude_from_tree(&mut root, &self.thread_rows);
+        }
+
+        // Threads that are sleeping (or idle, or absent) throughout the range
+        if rows.hide_idle_threads {
+            let asleep = [NO_STATE, state_value(Some("S")), state_value(Some("I"))];
+            let idle = columns
+                .threads
+                .iter()
+                .filter(|(_, thread)| {
+                    thread
+                        .states
+                        .in_range(min..=max)
+                        .iter()
+                        .all(|state| asleep.contains(state))
+                })
+                .map(|(&row, _)| row)
+                .collect();
+            exclude_from_tree(&mut root, &idle);
+        }
+
+        let query = rows.row_filter.trim();
+        let filter = if query.is_empty() {
+            None
+        } else if rows.row_filter_regex {
+            // An invalid pattern is reported next to the input; show everything meanwhile
+            Regex::new(query).ok().map(RowFilter::Pattern)
+        } else {
+            Some(RowFilter::Text(query.to_lowercase()))
+        };
+        if let Some(filter) = filter {
+            let matches = filter.matching_rows(&columns);
+            if rows.row_filter_invert {
+                exclude_from_tree(&mut root, &matches);
+            } else {
+                filter_tree(&mut root, &matches);
+            }
+        }
+
+        let mut totals = HashMap::new();
+        if rows.row_sort == RowSort::RunningTime {
+            totals = columns
+                .running_samples(min..=max)
+                .into_iter()
+                .map(|(row, total)| (NodeKey::Row(row), total))
+                .collect();
+            for (key, child) in &root.children {
+                heading_totals(key, child, &mut totals);
+            }
+        }
+        sort_tree(&mut root, rows.row_sort, &totals);
+
+        let no_running_rows = HashMap::new();
+        let mut order = RowOrder::default();
+        for label in &self.gpu_labels {
+            order.push(label.clone(), None);
+        }
+        flatten_tree(
+            &root,
+            &mut order,
+            collapsed_rows,
+            if rows.running_thread_rows {
+                &self.running_rows
+            } else {
+                &no_running_rows
+            },
+            &columns.rows,
+        );
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timeline_core::model::{GPUStatus, Process, Snapshot, Thread};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn process(pid: u32, name: &str, container: Option<&str>, tids: &[u32]) -> Process {
+        Process {
+            PID: pid,
+            Name: name.to_string(),
+            Container: container.map(str::to_string),
+            Threads: Some(
+                tids.iter()
+                    .map(|&tid| Thread {
+                        TID: tid,
+                        Name: Some(format!("t{tid}")),
+                        State: Some("S".to_string()),
+                        ..Thread::default()
+                    })
+                    .collect(),
+            ),
+            ..Process::default()
+        }
+    }
+
+    /// `init` running `web` in a container, and `db` which first appears
+    /// in the second snapshot.
+    fn snapshots() -> Vec<Snapshot> {
+        let tree = |children: Vec<Process>| Process {
+            Children: Some(children),
+            ..process(1, "init", None, &[])
+        };
+        vec![
+            Snapshot {
+                ProcessTree: tree(vec![process(3, "web", Some("app"), &[30, 31])]),
+                GPUStatus: vec![GPUStatus::default()],
+                ..Snapshot::default()
+            },
+            Snapshot {
+                ProcessTree: tree(vec![
+                    process(3, "web", Some("app"), &[30, 31]),
+                    process(2, "db", None, &[20]),
+                ]),
+                ..Snapshot::default()
+            },
+        ]
+    }
+
+    /// The rows below the GPU row, which CPU-only builds leave out.
+    fn labels(rows: &RowOptions, collapsed: &[&str]) -> Vec<String> {
+        let snapshots = snapshots();
+        let collapsed = collapsed.iter().map(|row| row.to_string()).collect();
+        let mut labels = TraceRows::new(Rc::new(Trace::new(snapshots)))
+            .row_order(0, 1, rows, &collapsed)
+            .labels
+            .clone();
+        if cfg!(feature = "gpu") {
+            assert_eq!(labels.remove(0), "GPU #0");
+        }
+        labels
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn flattens_the_tree_in_order_of_appearance() {
+        assert_eq!(
+            labels(&RowOptions::default(), &[]),
+            [
+                "init (PID 1)",
+                "    ▾ container: app",
+                "    └─ web (PID 3)",
+                "        └─ t30 (TID 30)",
+                "        └─ t31 (TID 31)",
+                "    └─ db (PID 2)",
+                "        └─ t20 (TID 20)",
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn collapsed_rows_hide_what_is_below_them() {
+        assert_eq!(
+            labels(&RowOptions::default(), &["    app", "    └─ db (PID 2)"]),
+            ["init (PID 1)", "    ▸ container: app", "    └─ db (PID 2)",]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn sorts_siblings_by_pid() {
+        let snapshots = vec![Snapshot {
+            ProcessTree: Process {
+                Children: Some(vec![
+                    process(9, "worker", None, &[]),
+                    process(4, "logger", None, &[]),
+                ]),
+                ..process(1, "init", None, &[])
+            },
+            ..Snapshot::default()
+        }];
+        let rows = RowOptions {
+            row_sort: RowSort::Pid,
+            ..RowOptions::default()
+        };
+        let order =
+            TraceRows::new(Rc::new(Trace::new(snapshots))).row_order(0, 0, &rows, &HashSet::new());
+        assert_eq!(
+            order.labels,
+            [
+                "init (PID 1)",
+                "    └─ logger (PID 4)",
+                "    └─ worker (PID 9)"
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn running_thread_rows_follow_their_process() {
+        let rows = RowOptions {
+            running_thread_rows: true,
+            processes_only: true,
+            ..RowOptions::default()
+        };
+        assert_eq!(
+            labels(&rows, &[]),
+            [
+                "init (PID 1)",
+                "    ▾ container: app",
+                "    └─ web (PID 3)",
+                "        └─ running threads (PID 3)",
+                "    └─ db (PID 2)",
+                "        └─ running threads (PID 2)",
+            ]
+        );
+    }
+}
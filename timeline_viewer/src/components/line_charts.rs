@@ -0,0 +1,155 @@
+use crate::charts::{append_to_chart, draw_chart};
+use crate::links::ChartLinks;
+use crate::panels::{fit_to_width, PanelData, PANELS};
+use crate::settings::{Palette, Theme};
+use crate::state::{use_viewer, Trace};
+use std::collections::HashMap;
+use std::rc::Rc;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct LineChartPanelProps {
+    /// Selected range of snapshot indices
+    pub(crate) min: usize,
+    pub(crate) max: usize,
+    pub(crate) theme: Theme,
+    pub(crate) palette: Palette,
+    /// Moving-average window of the GPU and CPU traces, in samples
+    pub(crate) smoothing: usize,
+    pub(crate) gpu_temp_warning: f64,
+    /// Ids from `PANELS` of the charts shown, top to bottom
+    pub(crate) charts: Vec<String>,
+    pub(crate) links: Rc<ChartLinks>,
+}
+
+/// What the line charts were last drawn for, telling snapshots streamed in
+/// live mode apart from any other change.
+struct DrawnLines {
+    trace: Rc<Trace>,
+    len: usize,
+    /// Whether the range ended at the newest snapshot
+    following: bool,
+    /// The range start and display options
+    options: (usize, Theme, Palette, usize, f64, Vec<String>),
+    /// Names of the series of each chart, by id
+    series: HashMap<&'static str, Vec<String>>,
+}
+
+/// The line charts below the timeline, over the selected range on a time axis.
+/// The trace is read from the `ViewerContext`.
+#[function_component(LineChartPanel)]
+pub(crate) fn line_chart_panel(props: &LineChartPanelProps) -> Html {
+    let viewer = use_viewer();
+    let trace = viewer.trace.clone();
+    let drawn = use_mut_ref(|| None::<DrawnLines>);
+    // Keyed on the trace and its length, like the heatmap panel
+    use_effect_with(
+        (
+            viewer.trace_id,
+            viewer.trace_len,
+            props.min,
+            props.max,
+            props.theme,
+            props.palette.clone(),
+            props.smoothing,
+            props.gpu_temp_warning,
+            props.charts.clone(),
+            props.links.clone(),
+        ),
+        move |(
+            trace_id,
+            len,
+            min,
+            max,
+            theme,
+            palette,
+            smoothing,
+            gpu_temp_warning,
+            charts,
+            links,
+        )| {
+            let columns = trace.columns();
+            if columns.is_empty() {
+                return;
+            }
+            let data = PanelData::new(&columns, *min, *max, palette, *smoothing, *gpu_temp_warning);
+            let options = (
+                *min,
+                *theme,
+                palette.clone(),
+                *smoothing,
+                *gpu_temp_warning,
+                charts.clone(),
+            );
+            let following = *max + 1 == *len;
+            // Snapshots streamed in while the range follows the newest one are
+            // appended to the charts rather than drawn with all the others
+            let mut drawn = drawn.borrow_mut();
+            let appended = drawn.take().filter(|drawn| {
+                Rc::ptr_eq(&drawn.trace, &trace)
+                    && drawn.following
+                    && following
+                    && drawn.len < *len
+                    && drawn.options == options
+            });
+            let after = appended.as_ref().map(|drawn| {
+                let times = data.snapshot_times[..drawn.len].iter().flatten();
+                times.copied().fold(f64::NEG_INFINITY, f64::max)
+            });
+            let mut series_names = HashMap::new();
+            for panel in PANELS
+                .iter()
+                .filter(|panel| charts.iter().any(|id| id == panel.id()))
+            {
+                let Some(div) = gloo::utils::document().get_element_by_id(panel.id()) else {
+                    continue;
+                };
+                let drawn_series = appended
+                    .as_ref()
+                    .and_then(|drawn| drawn.series.get(panel.id()));
+                if let (Some(drawn_series), Some(after)) = (drawn_series, after) {
+                    let series = panel.build_series(&data);
+                    if let Some(option) = panel.render(&data, series) {
+                        if append_to_chart(
+                            panel.id(),
+                            *theme,
+                            *trace_id,
+                            &option,
+                            drawn_series,
+                            after,
+                        ) {
+                            series_names.insert(panel.id(), drawn_series.clone());
+                            continue;
+                        }
+                    }
+                }
+                let mut series = panel.build_series(&data);
+                fit_to_width(&mut series, div.client_width().max(0) as u32);
+                if let Some(option) = panel.render(&data, series) {
+                    draw_chart(panel.id(), *theme, *trace_id, &option);
+                    let names = option.series.iter().map(|series| series.name.clone());
+                    series_names.insert(panel.id(), names.collect());
+                }
+            }
+            *drawn = Some(DrawnLines {
+                trace: trace.clone(),
+                len: *len,
+                following,
+                options,
+                series: series_names,
+            });
+
+            // New charts aren't linked to the others yet, and updated ones have
+            // lost their marker series
+            links.link_charts();
+        },
+    );
+
+    html! {
+        <>
+            { for props.charts.iter().map(|id| html! {
+            <div key={id.clone()} id={id.clone()} style="width:100%; height:300px; margin-top:2em;" />
+            }) }
+        </>
+    }
+}
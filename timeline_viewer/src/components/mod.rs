@@ -0,0 +1,37 @@
+mod app;
+mod bookmarks;
+mod busiest;
+mod error_toasts;
+mod file_loader;
+mod heatmap;
+mod heatmap_charts;
+mod heatmap_rows;
+mod line_charts;
+mod parse_errors;
+mod playback;
+mod process_detail;
+mod range_stats;
+mod raw_json;
+mod row_pager;
+mod snapshot_diff;
+mod summary;
+mod time_range;
+mod view_settings;
+
+pub(crate) use app::App;
+pub(crate) use bookmarks::BookmarkList;
+pub(crate) use busiest::BusiestProcesses;
+pub(crate) use error_toasts::{report_error, set_panic_hook, ErrorToasts};
+pub(crate) use file_loader::{ComparedRun, FileLoader};
+pub(crate) use heatmap::HeatmapPanel;
+pub(crate) use line_charts::LineChartPanel;
+pub(crate) use parse_errors::{ParseErrorList, ParseErrors, ParseErrorsAction};
+pub(crate) use playback::PlaybackPanel;
+pub(crate) use process_detail::ProcessDetail;
+pub(crate) use range_stats::RangeStatsPanel;
+pub(crate) use raw_json::RawJsonModal;
+pub(crate) use row_pager::RowPager;
+pub(crate) use snapshot_diff::SnapshotDiff;
+pub(crate) use summary::TraceSummaryCard;
+pub(crate) use time_range::TimeRangeControls;
+pub(crate) use view_settings::ViewSettings;
@@ -0,0 +1,71 @@
+use std::rc::Rc;
+use timeline_core::parse::ParseError;
+use yew::prelude::*;
+
+/// Parse errors listed in the error panel; the rest are only counted.
+const PARSE_ERRORS_SHOWN: usize = 200;
+
+/// Records of the loaded trace that failed to parse.
+#[derive(Default, PartialEq)]
+pub(crate) struct ParseErrors(Vec<ParseError>);
+
+pub(crate) enum ParseErrorsAction {
+    /// The errors of a newly loaded trace
+    Replace(Vec<ParseError>),
+    /// Errors of data added to the trace, e.g. streamed in live mode
+    Append(Vec<ParseError>),
+}
+
+impl Reducible for ParseErrors {
+    type Action = ParseErrorsAction;
+
+    fn reduce(self: Rc<Self>, action: ParseErrorsAction) -> Rc<Self> {
+        match action {
+            ParseErrorsAction::Replace(errors) => Rc::new(ParseErrors(errors)),
+            ParseErrorsAction::Append(errors) if errors.is_empty() => self,
+            ParseErrorsAction::Append(errors) => {
+                Rc::new(ParseErrors(self.0.iter().cloned().chain(errors).collect()))
+            }
+        }
+    }
+}
+
+impl ParseErrors {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct ParseErrorListProps {
+    pub(crate) errors: UseReducerHandle<ParseErrors>,
+}
+
+/// Records that failed to parse, so a partially loaded trace isn't a mystery.
+#[function_component(ParseErrorList)]
+pub(crate) fn parse_error_list(props: &ParseErrorListProps) -> Html {
+    let errors = &props.errors.0;
+    let rows = errors.iter().take(PARSE_ERRORS_SHOWN).map(|error| {
+        html! {
+            <tr>
+                <td>{ &error.source }</td>
+                <td>{ if error.line > 0 { error.line.to_string() } else { String::new() } }</td>
+                <td>{ &error.message }</td>
+                <td><code>{ &error.excerpt }</code></td>
+            </tr>
+        }
+    });
+    let hidden = errors.len().saturating_sub(PARSE_ERRORS_SHOWN);
+    html! {
+        <details style="color: #a50026;">
+            <summary>{ format!("{} record(s) failed to parse", errors.len()) }</summary>
+            <table>
+                <tr><th>{ "Source" }</th><th>{ "Line" }</th><th>{ "Error" }</th><th>{ "Content" }</th></tr>
+                { for rows }
+            </table>
+            if hidden > 0 {
+                <p>{ format!("... and {} more", hidden) }</p>
+            }
+        </details>
+    }
+}
@@ -0,0 +1,95 @@
+use crate::state::use_viewer;
+use timeline_core::analysis::is_gap;
+use timeline_core::matrix::count_running_threads;
+use timeline_core::model::Process;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct PlaybackPanelProps {
+    /// Snapshot index of the playback cursor
+    pub(crate) index: usize,
+}
+
+fn top_rows(proc: &Process, depth: usize, rows: &mut Vec<Html>) {
+    let threads = proc.Threads.as_deref().unwrap_or_default();
+    let running = threads
+        .iter()
+        .filter(|t| t.State.as_deref().is_some_and(|s| s.starts_with('R')))
+        .count();
+    rows.push(html! {
+        <tr>
+            <td>{ proc.PID }</td>
+            <td style="white-space: pre;">{ format!("{}{}", "  ".repeat(depth), proc.Name) }</td>
+            <td>{ format!("{running}/{}", threads.len()) }</td>
+            <td>{ proc.CPU_Percent.map(|cpu| format!("{cpu:.1}")).unwrap_or_default() }</td>
+            <td>{ proc.Memory_RSS_MB.map(|mb| format!("{mb:.0}")).unwrap_or_default() }</td>
+        </tr>
+    });
+    for child in proc.Children.iter().flatten() {
+        top_rows(child, depth + 1, rows);
+    }
+}
+
+/// Process tree at the playback cursor, like a frame of `top`.
+#[function_component(PlaybackPanel)]
+pub(crate) fn playback_panel(props: &PlaybackPanelProps) -> Html {
+    let viewer = use_viewer();
+    let index = props.index;
+    let snapshots = viewer.trace.snapshots();
+    let Some(snap) = snapshots.get(index) else {
+        return html! {};
+    };
+    let mut rows = Vec::new();
+    if !is_gap(snap) {
+        top_rows(&snap.ProcessTree, 0, &mut rows);
+    }
+    let gpus = snap.GPUStatus.iter().map(|gpu| {
+        html! {
+            <tr>
+                <th>{ format!("GPU #{}", gpu.GPU_ID) }</th>
+                <td>{ format!(
+                    "{:.0}% load, {:.0} / {:.0} MB",
+                    gpu.Load_Percent, gpu.Memory_Used_MB, gpu.Memory_Total_MB
+                ) }</td>
+            </tr>
+        }
+    });
+    let load = match (snap.LoadAvg_1, snap.LoadAvg_5, snap.LoadAvg_15) {
+        (Some(one), Some(five), Some(fifteen)) => {
+            Some(format!("{one:.2}, {five:.2}, {fifteen:.2}"))
+        }
+        _ => None,
+    };
+    html! {
+        <aside style="position: fixed; bottom: 0; left: 0; width: min(32em, 100%); max-height: 50%; overflow-y: auto; background: var(--panel-bg); border: 1px solid var(--border); padding: 0.5em 1em; box-sizing: border-box; z-index: 10; font-size: smaller;">
+            <h4 style="margin: 0;">{ format!("{} (snapshot {index})", snap.Timestamp) }</h4>
+            <table>
+                <tr>
+                    <th>{ "Running threads" }</th>
+                    <td>{ format!(
+                        "{} on {} cores",
+                        count_running_threads(&snap.ProcessTree),
+                        snap.CPU_Cores_Total
+                    ) }</td>
+                </tr>
+                if let Some(load) = load {
+                    <tr><th>{ "Load average" }</th><td>{ load }</td></tr>
+                }
+                if let (Some(used), Some(total)) = (snap.Memory_Used_MB, snap.Memory_Total_MB) {
+                    <tr><th>{ "Memory" }</th><td>{ format!("{used:.0} / {total:.0} MB") }</td></tr>
+                }
+                { for gpus }
+            </table>
+            <table>
+                <tr>
+                    <th>{ "PID" }</th>
+                    <th>{ "Name" }</th>
+                    <th title="Running / total threads">{ "Threads" }</th>
+                    <th>{ "%CPU" }</th>
+                    <th>{ "RSS MB" }</th>
+                </tr>
+                { for rows }
+            </table>
+        </aside>
+    }
+}
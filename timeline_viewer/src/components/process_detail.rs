@@ -0,0 +1,72 @@
+use crate::state::use_viewer;
+use timeline_core::matrix::RowKind;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct ProcessDetailProps {
+    /// Snapshot index and row label of the process shown
+    pub(crate) index: usize,
+    pub(crate) label: String,
+    /// Show the process as raw JSON
+    pub(crate) on_raw_json: Callback<MouseEvent>,
+    pub(crate) onclose: Callback<MouseEvent>,
+}
+
+/// Side panel with the command, user and threads of a clicked process.
+#[function_component(ProcessDetail)]
+pub(crate) fn process_detail(props: &ProcessDetailProps) -> Html {
+    let viewer = use_viewer();
+    let snapshots = viewer.trace.snapshots();
+    let Some(snap) = snapshots.get(props.index) else {
+        return html! {};
+    };
+    let columns = viewer.trace.columns();
+    let rows = &columns.rows;
+    let Some(proc) = rows
+        .find_label(&props.label)
+        .filter(|&row| rows.kind(row) == RowKind::Process)
+        .and_then(|row| rows.row_owner(&snap.ProcessTree, columns.hosts[props.index], row))
+    else {
+        return html! {};
+    };
+    let user = match (&proc.User, proc.UID) {
+        (Some(user), Some(uid)) => format!("{user} (UID {uid})"),
+        (Some(user), None) => user.clone(),
+        (None, Some(uid)) => format!("UID {uid}"),
+        (None, None) => "unknown".to_string(),
+    };
+    let threads = proc.Threads.iter().flatten().map(|t| {
+        html! {
+            <tr>
+                <td>{ t.TID }</td>
+                <td>{ t.Name.clone().unwrap_or_default() }</td>
+                <td>{ t.State.clone().unwrap_or_default() }</td>
+                <td>{ t.CPU_Time_Ms.map(|ms| format!("{:.0} ms", ms)).unwrap_or_default() }</td>
+            </tr>
+        }
+    });
+    html! {
+        <aside style="position: fixed; top: 0; right: 0; width: min(28em, 100%); height: 100%; overflow-y: auto; background: var(--panel-bg); border-left: 1px solid var(--border); padding: 1em; box-sizing: border-box; z-index: 10;">
+            <button style="float: right;" onclick={props.onclose.clone()}>{ "✕" }</button>
+            <button style="float: right; margin-right: 0.5em;" title="Also shift-click a heatmap cell" onclick={props.on_raw_json.clone()}>{ "Raw JSON" }</button>
+            <h3>{ format!("{} (PID {})", proc.Name, proc.PID) }</h3>
+            <p>{ format!("At {}", snap.Timestamp) }</p>
+            <table>
+                <tr><th>{ "Command" }</th><td><code style="word-break: break-all;">{ proc.CMD.clone().unwrap_or_default() }</code></td></tr>
+                <tr><th>{ "User" }</th><td>{ user }</td></tr>
+                <tr><th>{ "Started" }</th><td>{ proc.Start_Time.clone().unwrap_or_else(|| "unknown".to_string()) }</td></tr>
+                if let Some(host) = &snap.Hostname {
+                    <tr><th>{ "Host" }</th><td>{ host }</td></tr>
+                }
+                if let Some(container) = &proc.Container {
+                    <tr><th>{ "Container" }</th><td>{ container }</td></tr>
+                }
+            </table>
+            <h4>{ format!("Threads ({})", proc.Threads.as_ref().map_or(0, Vec::len)) }</h4>
+            <table>
+                <tr><th>{ "TID" }</th><th>{ "Name" }</th><th>{ "State" }</th><th>{ "CPU time" }</th></tr>
+                { for threads }
+            </table>
+        </aside>
+    }
+}
@@ -0,0 +1,71 @@
+use crate::state::use_viewer;
+use timeline_core::analysis::RangeStats;
+use timeline_core::matrix::STATE_NAMES;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct RangeStatsPanelProps {
+    /// Indices of the first and last brushed snapshots
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) onclose: Callback<MouseEvent>,
+}
+
+/// Modal summarizing the snapshots selected with a chart's range brush.
+#[function_component(RangeStatsPanel)]
+pub(crate) fn range_stats_panel(props: &RangeStatsPanelProps) -> Html {
+    let viewer = use_viewer();
+    let snapshots = viewer.trace.snapshots();
+    let end = (props.to + 1).min(snapshots.len());
+    let start = props.from.min(end);
+    let stats = RangeStats::new(&snapshots[start..end]);
+    let timestamp = |index: usize| {
+        snapshots
+            .get(index)
+            .map(|snap| snap.Timestamp.clone())
+            .unwrap_or_default()
+    };
+    let series = stats.series.iter().map(|s| {
+        html! {
+            <tr>
+                <th style="text-align: left;">{ s.label.clone() }</th>
+                <td>{ format!("{:.1}{}", s.min, s.unit) }</td>
+                <td>{ format!("{:.1}{}", s.mean, s.unit) }</td>
+                <td>{ format!("{:.1}{}", s.max, s.unit) }</td>
+            </tr>
+        }
+    });
+    let states = stats.states.iter().map(|&(state, count)| {
+        html! {
+            <tr>
+                <th style="text-align: left;">{ STATE_NAMES[state] }</th>
+                <td>{ format!(
+                    "{:.1}% ({count} samples)",
+                    count as f64 / stats.thread_samples as f64 * 100.0
+                ) }</td>
+            </tr>
+        }
+    });
+    html! {
+        <div style="position: fixed; inset: 0; background: rgba(0, 0, 0, 0.4); z-index: 20; display: flex; align-items: center; justify-content: center;">
+            <div style="max-width: 80%; max-height: 80vh; overflow: auto; background: var(--panel-bg); border: 1px solid var(--border); padding: 1em; box-sizing: border-box;">
+                <button style="float: right;" onclick={props.onclose.clone()}>{ "✕" }</button>
+                <h3 style="margin-top: 0;">{ "Brushed range" }</h3>
+                <p>{ format!(
+                    "{} to {} ({} snapshots)",
+                    timestamp(props.from),
+                    timestamp(props.to),
+                    end - start
+                ) }</p>
+                <table>
+                    <tr><th></th><th>{ "Min" }</th><th>{ "Mean" }</th><th>{ "Max" }</th></tr>
+                    { for series }
+                </table>
+                if stats.thread_samples > 0 {
+                    <h4>{ "Thread states" }</h4>
+                    <table>{ for states }</table>
+                }
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,56 @@
+use crate::state::use_viewer;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct RawJsonModalProps {
+    pub(crate) index: usize,
+    /// Row whose process subtree is shown, or None for the whole snapshot
+    pub(crate) label: Option<String>,
+    /// Show the whole snapshot instead
+    pub(crate) on_whole_snapshot: Callback<MouseEvent>,
+    pub(crate) onclose: Callback<MouseEvent>,
+}
+
+/// Modal with a snapshot, or the process subtree of a row in it, as JSON.
+#[function_component(RawJsonModal)]
+pub(crate) fn raw_json_modal(props: &RawJsonModalProps) -> Html {
+    let viewer = use_viewer();
+    let snapshots = viewer.trace.snapshots();
+    let Some(snap) = snapshots.get(props.index) else {
+        return html! {};
+    };
+    // The process subtree of the clicked row, or the whole snapshot for
+    // rows without one (e.g. GPUs)
+    let columns = viewer.trace.columns();
+    let rows = &columns.rows;
+    let host = columns.hosts[props.index];
+    let owner = props
+        .label
+        .as_deref()
+        .and_then(|label| rows.find_label(label))
+        .and_then(|row| rows.row_owner(&snap.ProcessTree, host, row));
+    let (title, json) = match owner {
+        Some(proc) => (
+            format!("{} (PID {}) at {}", proc.Name, proc.PID, snap.Timestamp),
+            serde_json::to_string_pretty(proc),
+        ),
+        None => (
+            format!("Snapshot at {}", snap.Timestamp),
+            serde_json::to_string_pretty(snap),
+        ),
+    };
+    html! {
+        <div style="position: fixed; inset: 0; background: rgba(0, 0, 0, 0.4); z-index: 20; display: flex; align-items: center; justify-content: center;">
+            <div style="width: 80%; max-height: 80vh; display: flex; flex-direction: column; background: var(--panel-bg); border: 1px solid var(--border); padding: 1em; box-sizing: border-box;">
+                <div>
+                    <button style="float: right;" onclick={props.onclose.clone()}>{ "✕" }</button>
+                    if owner.is_some() {
+                        <button style="float: right; margin-right: 0.5em;" onclick={props.on_whole_snapshot.clone()}>{ "Whole snapshot" }</button>
+                    }
+                    <h3 style="margin-top: 0;">{ title }</h3>
+                </div>
+                <pre style="overflow: auto; margin: 0;">{ json.unwrap_or_else(|e| e.to_string()) }</pre>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,68 @@
+use crate::settings::DEFAULT_ROW_WINDOW;
+use crate::state::{dispatch_with, use_viewer, RowOptions, ViewerAction};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct RowPagerProps {
+    /// Heatmap rows before windowing
+    pub(crate) row_count: usize,
+}
+
+/// Paging through the heatmap rows when there are more than the row window
+/// shows at once.
+#[function_component(RowPager)]
+pub(crate) fn row_pager(props: &RowPagerProps) -> Html {
+    let viewer = use_viewer();
+    let row_count = props.row_count;
+    let RowOptions {
+        row_offset,
+        row_window,
+        ..
+    } = viewer.rows;
+    // Kept visible after enlarging the window so it can be shrunk again
+    if row_count <= row_window.min(DEFAULT_ROW_WINDOW) {
+        return html! {};
+    }
+    let last_start = row_count.saturating_sub(row_window);
+    let start = row_offset.min(last_start);
+    let end = (start + row_window).min(row_count);
+    let step = |delta: isize| {
+        let window = row_window as isize;
+        dispatch_with(&viewer, move |_: MouseEvent| {
+            let offset = start as isize + delta * window;
+            ViewerAction::SetRowOffset(offset.clamp(0, last_start as isize) as usize)
+        })
+    };
+    html! {
+        <p>
+            <button onclick={step(-1)} disabled={start == 0}>{ "Previous rows" }</button>
+            { format!(" Rows {}–{end} of {row_count} ", start + 1) }
+            <button onclick={step(1)} disabled={end == row_count}>{ "Next rows" }</button>
+            <label>
+                { " First row: " }
+                <input type="number" min="1" max={row_count.to_string()} style="width: 6em;" value={(start + 1).to_string()} onchange={{
+                    let dispatcher = viewer.dispatcher();
+                    Callback::from(move |e: Event| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        if let Ok(first) = input.value().parse::<usize>() {
+                            dispatcher.dispatch(ViewerAction::SetRowOffset(first.saturating_sub(1)));
+                        }
+                    })
+                }} />
+            </label>
+            <label>
+                { " Rows per page: " }
+                <input type="number" min="50" style="width: 6em;" value={row_window.to_string()} onchange={{
+                    let dispatcher = viewer.dispatcher();
+                    Callback::from(move |e: Event| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        if let Ok(window) = input.value().parse::<usize>() {
+                            dispatcher.dispatch(ViewerAction::SetRowWindow(window.max(50)));
+                        }
+                    })
+                }} />
+            </label>
+        </p>
+    }
+}
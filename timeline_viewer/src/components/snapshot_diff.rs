@@ -0,0 +1,85 @@
+use crate::state::use_viewer;
+use timeline_core::analysis::{diff_process_trees, is_gap, RowChange};
+use timeline_core::matrix::host_prefix;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct SnapshotDiffProps {
+    /// Snapshot indices compared
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    /// Snapshot indices typed in to compare instead
+    pub(crate) on_change: Callback<(usize, usize)>,
+    pub(crate) onclose: Callback<MouseEvent>,
+}
+
+/// Modal listing the processes and threads that appeared, disappeared or
+/// changed state between two snapshots.
+#[function_component(SnapshotDiff)]
+pub(crate) fn snapshot_diff(props: &SnapshotDiffProps) -> Html {
+    let viewer = use_viewer();
+    let snapshots = viewer.trace.snapshots();
+    let (from, to) = (props.from, props.to);
+    let mut rows = Vec::new();
+    if let (Some(before), Some(after)) = (snapshots.get(from), snapshots.get(to)) {
+        let before_tree = (!is_gap(before)).then_some(&before.ProcessTree);
+        let after_tree = (!is_gap(after)).then_some(&after.ProcessTree);
+        diff_process_trees(before_tree, after_tree, 0, &host_prefix(after), &mut rows);
+    }
+    let unchanged = rows.is_empty();
+    let rows = rows.into_iter().map(|(label, change)| {
+        let (mark, color, note) = match change {
+            RowChange::Appeared => ("+", "green", String::new()),
+            RowChange::Disappeared => ("−", "red", String::new()),
+            RowChange::StateChanged(old, new) => ("~", "orange", format!("  {old} → {new}")),
+            RowChange::Context => (" ", "inherit", String::new()),
+        };
+        html! {
+            <div style={format!("color: {color};")}>{ format!("{mark} {label}{note}") }</div>
+        }
+    });
+    let index_input = |value: usize, set: fn((usize, usize), usize) -> (usize, usize)| {
+        let on_change = props.on_change.clone();
+        let last = snapshots.len().saturating_sub(1);
+        let onchange = Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(index) = input.value().parse::<usize>() {
+                on_change.emit(set((from, to), index.min(last)));
+            }
+        });
+        let timestamp = snapshots
+            .get(value)
+            .map(|snap| snap.Timestamp.clone())
+            .unwrap_or_default();
+        html! {
+            <>
+                <input type="number" min="0" max={last.to_string()} style="width: 6em;" value={value.to_string()} {onchange} />
+                { format!(" ({timestamp}) ") }
+            </>
+        }
+    };
+    html! {
+        <div style="position: fixed; inset: 0; background: rgba(0, 0, 0, 0.4); z-index: 20; display: flex; align-items: center; justify-content: center;">
+            <div style="width: 80%; max-height: 80vh; display: flex; flex-direction: column; background: var(--panel-bg); border: 1px solid var(--border); padding: 1em; box-sizing: border-box;">
+                <div>
+                    <button style="float: right;" onclick={props.onclose.clone()}>{ "✕" }</button>
+                    <h3 style="margin-top: 0;">{ "Snapshot diff" }</h3>
+                    <p>
+                        { "From snapshot " }
+                        { index_input(from, |(_, to), from| (from, to)) }
+                        { "to " }
+                        { index_input(to, |(from, _), to| (from, to)) }
+                    </p>
+                </div>
+                <div style="overflow: auto; font-family: monospace; white-space: pre;">
+                    if unchanged {
+                        { "No processes or threads appeared, disappeared or changed state." }
+                    } else {
+                        { for rows }
+                    }
+                </div>
+            </div>
+        </div>
+    }
+}
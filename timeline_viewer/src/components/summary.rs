@@ -0,0 +1,52 @@
+use crate::state::use_viewer;
+use timeline_core::analysis::{format_duration, TraceSummary};
+use yew::prelude::*;
+
+/// Card with the duration, mean loads and process counts of the whole trace.
+#[function_component(TraceSummaryCard)]
+pub(crate) fn trace_summary_card() -> Html {
+    let viewer = use_viewer();
+    let trace = viewer.trace.clone();
+    let summary = use_memo((viewer.trace_id, viewer.trace_len), move |_| {
+        TraceSummary::new(&trace.snapshots())
+    });
+    if summary.snapshots == 0 {
+        return html! {};
+    }
+    let gpus = summary.gpus.iter().map(|gpu| {
+        html! {
+            <tr>
+                <th>{ gpu.label.clone() }</th>
+                <td>{ format!(
+                    "load {:.0}% mean, {:.0}% max; memory {:.0} / {:.0} MB mean",
+                    gpu.mean_load, gpu.max_load, gpu.mean_memory_mb, gpu.memory_total_mb
+                ) }</td>
+            </tr>
+        }
+    });
+    html! {
+        <div style="display: inline-block; border: 1px solid var(--border); background: var(--panel-bg); padding: 0.5em 1em; margin-bottom: 1em;">
+            <table>
+                <tr>
+                    <th>{ "Duration" }</th>
+                    <td>{ format!(
+                        "{} ({} snapshots)",
+                        summary.duration_secs.map(format_duration).unwrap_or_else(|| "unknown".to_string()),
+                        summary.snapshots
+                    ) }</td>
+                </tr>
+                if let Some(load) = summary.mean_core_load {
+                    <tr><th>{ "CPU" }</th><td>{ format!("{load:.0}% mean core load") }</td></tr>
+                }
+                if let Some(running) = summary.mean_running_per_core {
+                    <tr><th>{ "Running threads" }</th><td>{ format!("{running:.0}% of cores on average") }</td></tr>
+                }
+                <tr>
+                    <th>{ "Distinct" }</th>
+                    <td>{ format!("{} processes, {} threads", summary.processes, summary.threads) }</td>
+                </tr>
+                { for gpus }
+            </table>
+        </div>
+    }
+}
@@ -0,0 +1,188 @@
+use crate::links::ChartLinks;
+use crate::state::{use_viewer, ViewerAction};
+use gloo::events::EventListener;
+use gloo::timers::callback::Interval;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// Playback speeds offered, in snapshots per second.
+const PLAYBACK_SPEEDS: [u32; 4] = [1, 2, 5, 10];
+
+#[derive(Properties, PartialEq)]
+pub(crate) struct TimeRangeControlsProps {
+    /// Snapshot index of the playback cursor, None when playback is off
+    pub(crate) playback: Option<usize>,
+    pub(crate) on_playback: Callback<Option<usize>>,
+    /// Compare the snapshots at both ends of the range
+    pub(crate) on_diff: Callback<(usize, usize)>,
+    /// Download the selected range as "csv" or "json"
+    pub(crate) on_download: Callback<&'static str>,
+    pub(crate) links: Rc<ChartLinks>,
+}
+
+/// The selected time range with its keyboard shortcuts, downloads and the
+/// playback cursor stepping through it.
+#[function_component(TimeRangeControls)]
+pub(crate) fn time_range_controls(props: &TimeRangeControlsProps) -> Html {
+    let viewer = use_viewer();
+    let playing = use_state(|| false);
+    let playback_speed = use_state(|| PLAYBACK_SPEEDS[0]);
+    let (first, last) = (viewer.min_time, viewer.max_time);
+    let len = viewer.trace_len;
+
+    // Advance the playback cursor through the selected range while playing
+    {
+        let playing = playing.clone();
+        let playback = props.playback;
+        let on_playback = props.on_playback.clone();
+        use_effect_with((*playing, *playback_speed), move |&(is_playing, speed)| {
+            let timer = is_playing.then(|| {
+                let mut index = playback.map_or(first, |index| index.clamp(first, last));
+                Interval::new(1000 / speed, move || {
+                    if index >= last {
+                        playing.set(false);
+                        return;
+                    }
+                    index += 1;
+                    on_playback.emit(Some(index));
+                })
+            });
+            move || drop(timer)
+        });
+    }
+
+    // Keyboard navigation: ←/→ step the time window, +/- zoom it, Home/End
+    // jump to either end of the trace and / focuses the row filter
+    {
+        let viewer = viewer.dispatcher();
+        use_effect_with((len, first, last), move |&(len, min, max)| {
+            let listener = EventListener::new(&gloo::utils::document(), "keydown", move |e| {
+                let Some(e) = e.dyn_ref::<KeyboardEvent>() else {
+                    return;
+                };
+                let typing = e
+                    .target()
+                    .and_then(|target| target.dyn_into::<Element>().ok())
+                    .is_some_and(|el| {
+                        matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+                    });
+                if len == 0 || typing || e.ctrl_key() || e.meta_key() || e.alt_key() {
+                    return;
+                }
+                let last = len - 1;
+                let width = max - min;
+                let range = match e.key().as_str() {
+                    "ArrowLeft" => {
+                        let start = min.saturating_sub(width + 1);
+                        Some((start, start + width))
+                    }
+                    "ArrowRight" => {
+                        let end = (max + width + 1).min(last);
+                        Some((end - width, end))
+                    }
+                    "+" | "=" => {
+                        let quarter = width / 4;
+                        Some((min + quarter, max - quarter))
+                    }
+                    "-" => {
+                        let half = width / 2 + 1;
+                        Some((min.saturating_sub(half), (max + half).min(last)))
+                    }
+                    "Home" => Some((0, width.min(last))),
+                    "End" => Some((last - width.min(last), last)),
+                    "/" => {
+                        if let Some(filter) = gloo::utils::document()
+                            .get_element_by_id("row-filter")
+                            .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+                        {
+                            let _ = filter.focus();
+                        }
+                        None
+                    }
+                    _ => return,
+                };
+                e.prevent_default();
+                if let Some((min, max)) = range {
+                    viewer.dispatch(ViewerAction::SetTimeRange(min, max));
+                }
+            });
+            move || drop(listener)
+        });
+    }
+
+    // Draw the playback cursor on the charts
+    use_effect_with((props.playback, props.links.clone()), |(index, links)| {
+        links.show_playback_cursor(*index);
+    });
+
+    let on_download = &props.on_download;
+    html! {
+        <>
+            <p>
+                { format!("Time range: {first} - {last} ") }
+                <button onclick={props.on_diff.reform(move |_: MouseEvent| (first, last))}>{ "Diff range ends" }</button>
+                if len > 0 {
+                    { " Download data: " }
+                    <button title="Selected range as CSV, one column per series" onclick={on_download.reform(|_: MouseEvent| "csv")}>{ "CSV" }</button>
+                    <button title="Selected range as JSON" onclick={on_download.reform(|_: MouseEvent| "json")}>{ "JSON" }</button>
+                }
+            </p>
+            if len > 0 {
+                <p>
+                    { "Playback: " }
+                    <button title="Step back" onclick={{
+                        let playback = props.playback;
+                        props.on_playback.reform(move |_: MouseEvent| {
+                            Some(playback.map_or(first, |index| index.saturating_sub(1).max(first)))
+                        })
+                    }}>{ "⏮" }</button>
+                    <button title={if *playing { "Pause" } else { "Play" }} onclick={{
+                        let playing = playing.clone();
+                        let playback = props.playback;
+                        let on_playback = props.on_playback.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            if !*playing && playback.is_none_or(|index| index >= last) {
+                                // Start over from the beginning of the range
+                                on_playback.emit(Some(first));
+                            }
+                            playing.set(!*playing);
+                        })
+                    }}>{ if *playing { "⏸" } else { "▶" } }</button>
+                    <button title="Step forward" onclick={{
+                        let playback = props.playback;
+                        props.on_playback.reform(move |_: MouseEvent| {
+                            Some(playback.map_or(first, |index| (index + 1).min(last)))
+                        })
+                    }}>{ "⏭" }</button>
+                    <select onchange={{
+                        let playback_speed = playback_speed.clone();
+                        Callback::from(move |e: Event| {
+                            let select: HtmlSelectElement = e.target_unchecked_into();
+                            if let Ok(speed) = select.value().parse() {
+                                playback_speed.set(speed);
+                            }
+                        })
+                    }}>
+                        { for PLAYBACK_SPEEDS.iter().map(|&speed| html! {
+                            <option value={speed.to_string()} selected={*playback_speed == speed}>
+                                { format!("{speed} snapshots/s") }
+                            </option>
+                        }) }
+                    </select>
+                    if props.playback.is_some() {
+                        <button onclick={{
+                            let playing = playing.clone();
+                            let on_playback = props.on_playback.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                playing.set(false);
+                                on_playback.emit(None);
+                            })
+                        }}>{ "Stop" }</button>
+                    }
+                </p>
+            }
+        </>
+    }
+}
@@ -0,0 +1,354 @@
+use crate::charts::SMOOTHING_WINDOWS;
+use crate::panels::{panel, PANELS};
+use crate::settings::{
+    load_view_presets, HeatmapColor, Palette, RowLayout, RowSort, Theme, TimelineView,
+    VIEW_PRESETS_STORAGE_KEY,
+};
+use crate::state::{dispatch_with, use_viewer, ViewerAction, ViewerContext};
+use gloo::storage::{LocalStorage, Storage};
+use regex::Regex;
+use timeline_core::matrix::{GPU_BUCKET_NAMES, STATE_NAMES};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+/// Colors of the thread states and GPU load buckets, from a preset or picked
+/// one by one.
+fn palette_settings(viewer: &ViewerContext) -> Html {
+    let palette = viewer.palette();
+    let preset = match &viewer.custom_palette {
+        None => "default",
+        Some(custom) if *custom == Palette::color_blind_safe(viewer.theme) => "color-blind",
+        Some(_) => "custom",
+    };
+    // Editing any color turns the palette in use into a custom one
+    let color_input = |label: &str, value: &str, set: fn(&mut Palette, usize, String), index| {
+        let palette = palette.clone();
+        let onchange = dispatch_with(viewer, move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut palette = palette.clone();
+            set(&mut palette, index, input.value());
+            ViewerAction::SetCustomPalette(Some(palette))
+        });
+        html! {
+            <label style="margin-right: 1em;">
+                <input type="color" value={value.to_string()} {onchange} />
+                { format!(" {label}") }
+            </label>
+        }
+    };
+    html! {
+        <details>
+            <summary>{ "Colors" }</summary>
+            <label>
+                { "Preset: " }
+                <select onchange={{
+                    let dispatcher = viewer.dispatcher();
+                    let theme = viewer.theme;
+                    Callback::from(move |e: Event| {
+                        let select: HtmlSelectElement = e.target_unchecked_into();
+                        match select.value().as_str() {
+                            "default" => dispatcher.dispatch(ViewerAction::SetCustomPalette(None)),
+                            "color-blind" => dispatcher.dispatch(ViewerAction::SetCustomPalette(Some(
+                                Palette::color_blind_safe(theme),
+                            ))),
+                            _ => {}
+                        }
+                    })
+                }}>
+                    <option value="default" selected={preset == "default"}>{ "theme default" }</option>
+                    <option value="color-blind" selected={preset == "color-blind"}>{ "color-blind safe" }</option>
+                    if preset == "custom" {
+                        <option value="custom" selected=true>{ "custom" }</option>
+                    }
+                </select>
+            </label>
+            <p>
+                { for STATE_NAMES.iter().enumerate().map(|(i, name)| {
+                    color_input(name, &palette.states[i], |p, i, color| p.states[i] = color, i)
+                }) }
+            </p>
+            <p>
+                { for GPU_BUCKET_NAMES.iter().enumerate().map(|(i, name)| {
+                    color_input(name, &palette.gpu[i], |p, i, color| p.gpu[i] = color, i)
+                }) }
+            </p>
+        </details>
+    }
+}
+
+/// The line charts, each shown or hidden and moved up or down.
+fn chart_settings(viewer: &ViewerContext) -> Html {
+    let chart_layout = &viewer.chart_layout;
+    // Charts needing data the trace never recorded are left out
+    let missing_fields = |id: &str| {
+        panel(id).map_or_else(Vec::new, |panel| {
+            panel.missing_fields(&viewer.trace.columns())
+        })
+    };
+    html! {
+        <details>
+            <summary>{ format!(
+                "Charts ({} of {} shown)",
+                chart_layout.shown(&viewer.trace.columns()).len(),
+                PANELS.len()
+            ) }</summary>
+            { for chart_layout.order.iter().enumerate().map(|(index, id)| {
+                let name = panel(id).map_or(id.as_str(), |panel| panel.name());
+                let missing = missing_fields(id);
+                let onchange = {
+                    let id = id.clone();
+                    dispatch_with(viewer, move |e: Event| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        ViewerAction::ShowChart(id.clone(), input.checked())
+                    })
+                };
+                let move_by = |offset: isize| {
+                    dispatch_with(viewer, move |_: MouseEvent| ViewerAction::MoveChart(index, offset))
+                };
+                html! {
+                    <div>
+                        <button title="Move up" disabled={index == 0} onclick={move_by(-1)}>{ "↑" }</button>
+                        <button title="Move down" disabled={index + 1 == chart_layout.order.len()} onclick={move_by(1)}>{ "↓" }</button>
+                        <label>
+                            <input type="checkbox" checked={!chart_layout.hidden.contains(id)} {onchange} />
+                            { format!(" {name}") }
+                            if !missing.is_empty() {
+                                <span style="opacity: 0.6;">{ format!(
+                                    " (no {} data)",
+                                    missing.iter().map(|field| field.name()).collect::<Vec<_>>().join(" or ")
+                                ) }</span>
+                            }
+                        </label>
+                    </div>
+                }
+            }) }
+        </details>
+    }
+}
+
+/// Heatmap coloring, row filters and sorting, theme, smoothing, row layout,
+/// colors, visible charts and saved view presets.
+#[function_component(ViewSettings)]
+pub(crate) fn view_settings() -> Html {
+    let viewer = use_viewer();
+    let view_presets = use_state(load_view_presets);
+
+    let row_filter_error = match (viewer.rows.row_filter_regex, viewer.rows.row_filter.trim()) {
+        (true, query) if !query.is_empty() => Regex::new(query).err(),
+        _ => None,
+    };
+
+    let current_preset = viewer.preset();
+    let selected_preset = view_presets
+        .iter()
+        .find(|(_, preset)| **preset == current_preset)
+        .map(|(name, _)| name.clone());
+    let preset_settings = {
+        let on_select = {
+            let view_presets = view_presets.clone();
+            let dispatcher = viewer.dispatcher();
+            Callback::from(move |e: Event| {
+                let select: HtmlSelectElement = e.target_unchecked_into();
+                if let Some(preset) = view_presets.get(&select.value()).cloned() {
+                    dispatcher.dispatch(ViewerAction::ApplyPreset(preset));
+                }
+            })
+        };
+        let on_save = {
+            let view_presets = view_presets.clone();
+            let current_preset = current_preset.clone();
+            let default_name = selected_preset.clone().unwrap_or_default();
+            Callback::from(move |_: MouseEvent| {
+                let Some(name) =
+                    gloo::dialogs::prompt("Save the current view as", Some(&default_name))
+                        .filter(|name| !name.trim().is_empty())
+                else {
+                    return;
+                };
+                let mut presets = (*view_presets).clone();
+                presets.insert(name.trim().to_string(), current_preset.clone());
+                let _ = LocalStorage::set(VIEW_PRESETS_STORAGE_KEY, &presets);
+                view_presets.set(presets);
+            })
+        };
+        let on_delete = {
+            let view_presets = view_presets.clone();
+            let selected_preset = selected_preset.clone();
+            Callback::from(move |_: MouseEvent| {
+                let Some(name) = &selected_preset else {
+                    return;
+                };
+                let mut presets = (*view_presets).clone();
+                presets.remove(name);
+                let _ = LocalStorage::set(VIEW_PRESETS_STORAGE_KEY, &presets);
+                view_presets.set(presets);
+            })
+        };
+        html! {
+            <p>
+                <label>
+                    { "View preset: " }
+                    <select onchange={on_select}>
+                        <option value="" selected={selected_preset.is_none()}>{ "(unsaved)" }</option>
+                        { for view_presets.keys().map(|name| html! {
+                            <option value={name.clone()} selected={selected_preset.as_ref() == Some(name)}>{ name.clone() }</option>
+                        }) }
+                    </select>
+                </label>
+                <button title="Filters, sort order, visible charts and colors" onclick={on_save}>{ "Save as…" }</button>
+                if selected_preset.is_some() {
+                    <button onclick={on_delete}>{ "Delete" }</button>
+                }
+            </p>
+        }
+    };
+
+    // Setting inputs dispatching the value they were changed to
+    let on_checked = |action: fn(bool) -> ViewerAction| {
+        dispatch_with(&viewer, move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            action(input.checked())
+        })
+    };
+    let on_selected = |action: fn(&str) -> ViewerAction| {
+        dispatch_with(&viewer, move |e: Event| {
+            let select: HtmlSelectElement = e.target_unchecked_into();
+            action(&select.value())
+        })
+    };
+
+    html! {
+        <>
+            <p>
+                <label>
+                    { "Color processes by: " }
+                    <select onchange={on_selected(|value| ViewerAction::SetHeatmapColor(match value {
+                        "memory" => HeatmapColor::Memory,
+                        "cpu-time" => HeatmapColor::CpuTime,
+                        _ => HeatmapColor::ThreadState,
+                    }))}>
+                        <option value="state" selected={viewer.heatmap_color == HeatmapColor::ThreadState}>{ "thread state" }</option>
+                        <option value="memory" selected={viewer.heatmap_color == HeatmapColor::Memory}>{ "memory (RSS)" }</option>
+                        <option value="cpu-time" selected={viewer.heatmap_color == HeatmapColor::CpuTime}>{ "thread CPU time" }</option>
+                    </select>
+                </label>
+                <label>
+                    { " Filter rows: " }
+                    <input id="row-filter" type="search" title="Shortcut: /" placeholder={if viewer.rows.row_filter_regex { "regex on row label" } else { "name or command" }} value={viewer.rows.row_filter.clone()} oninput={dispatch_with(&viewer, |e: InputEvent| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        ViewerAction::SetRowFilter(input.value())
+                    })} />
+                </label>
+                <label>
+                    <input type="checkbox" checked={viewer.rows.row_filter_regex} onchange={on_checked(ViewerAction::SetRowFilterRegex)} />
+                    { "regex" }
+                </label>
+                <label>
+                    <input type="checkbox" checked={viewer.rows.row_filter_invert} onchange={on_checked(ViewerAction::SetRowFilterInvert)} />
+                    { "exclude matches" }
+                </label>
+                if let Some(error) = row_filter_error {
+                    <span style="color: #a50026;">{ format!(" Invalid regex: {error}") }</span>
+                }
+                <label>
+                    <input type="checkbox" checked={viewer.rows.hide_idle_threads} onchange={on_checked(ViewerAction::SetHideIdleThreads)} />
+                    { "hide idle threads" }
+                </label>
+                <label>
+                    <input type="checkbox" checked={viewer.rows.processes_only} onchange={on_checked(ViewerAction::SetProcessesOnly)} />
+                    { "processes only" }
+                </label>
+                <label>
+                    <input type="checkbox" checked={viewer.rows.running_thread_rows} onchange={on_checked(ViewerAction::SetRunningThreadRows)} />
+                    { "running thread counts" }
+                </label>
+                <label>
+                    { " Sort rows by: " }
+                    <select onchange={on_selected(|value| ViewerAction::SetRowSort(match value {
+                        "running" => RowSort::RunningTime,
+                        "name" => RowSort::Name,
+                        "pid" => RowSort::Pid,
+                        _ => RowSort::Tree,
+                    }))}>
+                        <option value="tree" selected={viewer.rows.row_sort == RowSort::Tree}>{ "tree order" }</option>
+                        <option value="running" selected={viewer.rows.row_sort == RowSort::RunningTime}>{ "running time" }</option>
+                        <option value="name" selected={viewer.rows.row_sort == RowSort::Name}>{ "name" }</option>
+                        <option value="pid" selected={viewer.rows.row_sort == RowSort::Pid}>{ "PID" }</option>
+                    </select>
+                </label>
+                <label>
+                    <input type="checkbox" checked={viewer.theme == Theme::Dark} onchange={on_checked(|dark| {
+                        ViewerAction::SetTheme(if dark { Theme::Dark } else { Theme::Light })
+                    })} />
+                    { "dark mode" }
+                </label>
+                <label>
+                    { " Smoothing: " }
+                    <select onchange={on_selected(|value| {
+                        ViewerAction::SetSmoothing(value.parse().unwrap_or(SMOOTHING_WINDOWS[0]))
+                    })}>
+                        { for SMOOTHING_WINDOWS.iter().map(|&window| html! {
+                            <option value={window.to_string()} selected={viewer.smoothing == window}>
+                                { if window == 1 { "off".to_string() } else { format!("{window} samples") } }
+                            </option>
+                        }) }
+                    </select>
+                </label>
+                <label>
+                    { " View: " }
+                    <select onchange={on_selected(|value| ViewerAction::SetTimelineView(if value == "gantt" {
+                        TimelineView::Gantt
+                    } else {
+                        TimelineView::Heatmap
+                    }))}>
+                        <option value="heatmap" selected={viewer.timeline_view == TimelineView::Heatmap}>{ "heatmap" }</option>
+                        <option value="gantt" selected={viewer.timeline_view == TimelineView::Gantt}>{ "process lifetimes" }</option>
+                    </select>
+                </label>
+                if cfg!(feature = "gpu") {
+                    <label>
+                        { " GPU temperature warning: " }
+                        <input type="number" step="any" style="width: 4em;" value={viewer.gpu_temp_warning.to_string()} onchange={{
+                            let dispatcher = viewer.dispatcher();
+                            Callback::from(move |e: Event| {
+                                let input: HtmlInputElement = e.target_unchecked_into();
+                                if let Ok(value) = input.value().parse::<f64>() {
+                                    dispatcher.dispatch(ViewerAction::SetGpuTempWarning(value));
+                                }
+                            })
+                        }} />
+                        { " °C" }
+                    </label>
+                }
+            </p>
+            <p>
+                { for [
+                    ("Row height", viewer.row_layout.row_height, (4, 40), (|layout, value| layout.row_height = value) as fn(&mut RowLayout, usize)),
+                    ("Label font size", viewer.row_layout.font_size, (6, 24), |layout, value| layout.font_size = value),
+                    ("Label gutter", viewer.row_layout.gutter, (50, 800), |layout, value| layout.gutter = value),
+                ].into_iter().map(|(label, value, (min, max), set)| {
+                    let dispatcher = viewer.dispatcher();
+                    let layout = viewer.row_layout;
+                    let onchange = Callback::from(move |e: Event| {
+                        let input: HtmlInputElement = e.target_unchecked_into();
+                        if let Ok(value) = input.value().parse::<usize>() {
+                            let mut layout = layout;
+                            set(&mut layout, value.clamp(min, max));
+                            dispatcher.dispatch(ViewerAction::SetRowLayout(layout));
+                        }
+                    });
+                    html! {
+                        <label>
+                            { format!("{label}: ") }
+                            <input type="number" min={min.to_string()} max={max.to_string()} style="width: 4em;" value={value.to_string()} {onchange} />
+                            { " px " }
+                        </label>
+                    }
+                }) }
+            </p>
+            { palette_settings(&viewer) }
+            { chart_settings(&viewer) }
+            { preset_settings }
+        </>
+    }
+}
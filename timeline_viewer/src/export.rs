@@ -0,0 +1,143 @@
+use gloo::timers::callback::Timeout;
+use indexmap::IndexMap;
+use serde::Serialize;
+use timeline_core::analysis::is_gap;
+use timeline_core::matrix::{count_running_threads, host_prefix, visit_threads};
+use timeline_core::model::Snapshot;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+/// One GPU's series in a data export, aligned with `DataExport::timestamps`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct GpuExport {
+    label: String,
+    load_percent: Vec<Option<f64>>,
+    memory_used_mb: Vec<Option<f64>>,
+}
+
+/// One thread's state letters in a data export, aligned with `DataExport::timestamps`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ThreadExport {
+    label: String,
+    states: Vec<Option<String>>,
+}
+
+/// Series derived from the selected time range, downloaded for analysis
+/// elsewhere (e.g. pandas).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct DataExport {
+    timestamps: Vec<String>,
+    /// Running threads per core in percent, as in the CPU trace
+    cpu_utilization_percent: Vec<Option<f64>>,
+    gpus: Vec<GpuExport>,
+    threads: Vec<ThreadExport>,
+}
+
+impl DataExport {
+    pub(crate) fn new(snapshots: &[Snapshot]) -> Self {
+        let len = snapshots.len();
+        let mut gpus: IndexMap<String, GpuExport> = IndexMap::new();
+        let mut threads: IndexMap<String, ThreadExport> = IndexMap::new();
+        let mut cpu_utilization_percent = vec![None; len];
+        for (i, snap) in snapshots.iter().enumerate() {
+            if is_gap(snap) {
+                continue;
+            }
+            let prefix = host_prefix(snap);
+            let running_threads = count_running_threads(&snap.ProcessTree);
+            cpu_utilization_percent[i] =
+                Some(running_threads as f64 / snap.CPU_Cores_Total.max(1) as f64 * 100.0);
+            for gpu in &snap.GPUStatus {
+                let label = format!("{prefix}GPU #{} ({})", gpu.GPU_ID, gpu.Name);
+                let series = gpus.entry(label.clone()).or_insert_with(|| GpuExport {
+                    label,
+                    load_percent: vec![None; len],
+                    memory_used_mb: vec![None; len],
+                });
+                series.load_percent[i] = Some(gpu.Load_Percent);
+                series.memory_used_mb[i] = Some(gpu.Memory_Used_MB);
+            }
+            visit_threads(&snap.ProcessTree, &mut |t| {
+                let label = format!(
+                    "{prefix}{} (TID {})",
+                    t.Name.as_deref().unwrap_or_default(),
+                    t.TID
+                );
+                let series = threads
+                    .entry(label.clone())
+                    .or_insert_with(|| ThreadExport {
+                        label,
+                        states: vec![None; len],
+                    });
+                series.states[i] = t
+                    .State
+                    .as_deref()
+                    .and_then(|s| s.get(..1))
+                    .map(String::from);
+            });
+        }
+        DataExport {
+            timestamps: snapshots
+                .iter()
+                .map(|snap| snap.Timestamp.clone())
+                .collect(),
+            cpu_utilization_percent,
+            gpus: gpus.into_values().collect(),
+            threads: threads.into_values().collect(),
+        }
+    }
+
+    /// One row per snapshot and one column per series, blank where there is no sample.
+    pub(crate) fn to_csv(&self) -> Result<String, csv::Error> {
+        let number = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let mut header = vec![
+            "timestamp".to_string(),
+            "cpu_utilization_percent".to_string(),
+        ];
+        for gpu in &self.gpus {
+            header.push(format!("{} load_percent", gpu.label));
+            header.push(format!("{} memory_used_mb", gpu.label));
+        }
+        header.extend(self.threads.iter().map(|t| t.label.clone()));
+        writer.write_record(&header)?;
+        for (i, timestamp) in self.timestamps.iter().enumerate() {
+            let mut record = vec![timestamp.clone(), number(self.cpu_utilization_percent[i])];
+            for gpu in &self.gpus {
+                record.push(number(gpu.load_percent[i]));
+                record.push(number(gpu.memory_used_mb[i]));
+            }
+            record.extend(
+                self.threads
+                    .iter()
+                    .map(|t| t.states[i].clone().unwrap_or_default()),
+            );
+            writer.write_record(&record)?;
+        }
+        let bytes = writer.into_inner().map_err(|e| e.into_error())?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// Has the browser download `content` as a file.
+pub(crate) fn download_file(file_name: &str, mime_type: &str, content: &str) {
+    let blob = gloo_file::Blob::new_with_options(content, Some(mime_type));
+    let url = gloo_file::ObjectUrl::from(blob);
+    download_url(file_name, &url);
+    // Revoking the URL straight away can cancel the download
+    Timeout::new(1_000, move || drop(url)).forget();
+}
+
+/// Has the browser download `url`, e.g. a data URL, as a file.
+pub(crate) fn download_url(file_name: &str, url: &str) {
+    let document = gloo::utils::document();
+    if let Some(link) = document
+        .create_element("a")
+        .ok()
+        .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+    {
+        let _ = link.set_attribute("href", url);
+        let _ = link.set_attribute("download", file_name);
+        link.click();
+    }
+}
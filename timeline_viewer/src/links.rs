@@ -0,0 +1,717 @@
+//! Links the charts on the page to the timeline and to each other: zooming
+//! one zooms them all, the crosshair follows the mouse across them, and each
+//! shows the event and bookmark markers, the playback cursor and buttons to
+//! export it or show it fullscreen.
+
+use crate::charts::{
+    echarts_connect, echarts_init_detached, is_shown, page_charts, resize_charts, set_path, to_js,
+    Action, AxisFinder, BrushEvent, Callbacks, DataUrlOptions, ECharts, InitOptions,
+    PointerTrigger,
+};
+use crate::export::download_url;
+use crate::settings::Theme;
+use crate::state::Trace;
+use js_sys::{Array, Object, Reflect};
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use timeline_core::echarts::{
+    AxisPointer, AxisUpdate, Brush, BrushFeature, DataZoom, Length, LineStyle, LineType, MarkLabel,
+    MarkLine, MarkLineSeries, OptionUpdate, Show, Toolbox, ToolboxButton, ToolboxFeatures, XMark,
+};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use yew::Callback;
+
+/// Charts not spanning the snapshots of the selected range, left unlinked.
+const UNLINKED_CHARTS: [&str; 2] = ["overview", "compare-line"];
+
+/// Charts whose brushed range shows its statistics.
+const BRUSHED_CHARTS: [&str; 3] = ["gpu-load-line", "cpu-load-line", "gpu-mem-line"];
+
+/// Group of the charts on the snapshot category axis (the heatmaps and the
+/// process lifetimes), which ECharts keeps in step itself once connected.
+pub(crate) const TIMELINE_GROUP: &str = "timeline";
+
+/// A marker line at a snapshot of the selected range.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Marker {
+    pub(crate) index: usize,
+    /// Where the marker goes on time axes (ms), when not at the snapshot's time
+    pub(crate) time: Option<f64>,
+    pub(crate) name: String,
+}
+
+/// The timeline the charts are linked to, set by `HeatmapPanel` each time it
+/// is drawn.
+pub(crate) struct Timeline {
+    pub(crate) theme: Theme,
+    /// Wall-clock time (ms) of each snapshot, None where it doesn't parse
+    pub(crate) snapshot_times: Vec<Option<f64>>,
+    pub(crate) trace: Rc<Trace>,
+    /// Selected range of snapshot indices
+    pub(crate) min: usize,
+    pub(crate) max: usize,
+    pub(crate) events: Vec<Marker>,
+    pub(crate) bookmarks: Vec<Marker>,
+    /// Range zoomed to on any chart
+    pub(crate) on_time_range: Callback<(usize, usize)>,
+    /// Range brushed on one of the `BRUSHED_CHARTS`
+    pub(crate) on_range_stats: Callback<(usize, usize)>,
+    /// Snapshot index and name of a bookmark added by double-clicking a chart
+    pub(crate) on_bookmark: Callback<(usize, String)>,
+}
+
+impl Timeline {
+    /// Where snapshot `index` is on an x axis of time, or of the snapshots.
+    fn axis_value(&self, time_axis: bool, index: usize) -> Option<f64> {
+        if time_axis {
+            *self.snapshot_times.get(index)?
+        } else {
+            Some(index as f64)
+        }
+    }
+
+    /// The snapshot nearest to `value` on an x axis of time, or of the snapshots.
+    fn snapshot_index(&self, time_axis: bool, value: f64) -> Option<usize> {
+        if !time_axis {
+            let index = value.round();
+            return (index >= 0.0 && index < self.trace.len() as f64).then_some(index as usize);
+        }
+        self.snapshot_times
+            .iter()
+            .enumerate()
+            .filter_map(|(index, time)| Some((index, ((*time)? - value).abs())))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+    }
+
+    /// The first and last snapshots from `from` to `to` (ms), if any.
+    fn snapshots_between(&self, from: f64, to: f64) -> Option<(usize, usize)> {
+        let timed = || {
+            self.snapshot_times
+                .iter()
+                .enumerate()
+                .filter_map(|(index, time)| Some((index, (*time)?)))
+        };
+        let start = timed().find(|&(_, time)| time >= from)?.0;
+        let end = timed().rev().find(|&(_, time)| time <= to)?.0;
+        (end >= start).then_some((start, end))
+    }
+}
+
+/// A chart on the page linked to the timeline.
+struct LinkedChart {
+    chart: ECharts,
+    /// On a time axis rather than the snapshot categories
+    time_axis: bool,
+    /// Shows its tooltip for the x value under the mouse, with a crosshair
+    axis_tooltip: bool,
+    /// In `TIMELINE_GROUP`
+    grouped: bool,
+}
+
+/// What the handlers of the linked charts share.
+#[derive(Default)]
+struct Links {
+    charts: Vec<LinkedChart>,
+    /// Set while an action is applied to the other charts, so it isn't echoed back
+    linking: Cell<bool>,
+    /// Snapshot the crosshairs were last moved to. Each chart of
+    /// `TIMELINE_GROUP` reports the moves relayed to it, which are passed on
+    /// only once.
+    pointer: Cell<Option<usize>>,
+    /// Range the charts were last zoomed to, likewise
+    zoom: Cell<Option<(usize, usize)>>,
+}
+
+impl Links {
+    /// Apply `apply` to the linked charts but `source`, without the actions it
+    /// dispatches being echoed back. ECharts relays them within
+    /// `TIMELINE_GROUP`, so only one shown chart of it is passed.
+    fn for_others(&self, source: &LinkedChart, apply: impl Fn(&LinkedChart)) {
+        if self.linking.replace(true) {
+            return;
+        }
+        let mut group_reached = source.grouped;
+        for target in &self.charts {
+            if target.grouped {
+                if group_reached || !is_shown(&target.chart) {
+                    continue;
+                }
+                group_reached = true;
+            } else if target.chart == source.chart {
+                continue;
+            }
+            apply(target);
+        }
+        self.linking.set(false);
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AxisPointerEvent {
+    #[serde(default)]
+    axes_info: Vec<AxisInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AxisInfo {
+    axis_dim: String,
+    value: f64,
+}
+
+/// An event anywhere on a chart's canvas.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CanvasEvent {
+    offset_x: f64,
+    offset_y: f64,
+}
+
+/// Scrolls the page to a heatmap row, returning false when it isn't drawn.
+type RowScroller = Box<dyn Fn(&str) -> bool>;
+
+/// Links the charts on the page to the timeline. There is one for the page,
+/// passed to the panels drawing charts, which link them again after each draw.
+#[derive(Default)]
+pub(crate) struct ChartLinks {
+    timeline: RefCell<Option<Rc<Timeline>>>,
+    links: RefCell<Rc<Links>>,
+    /// Handlers of the linked charts, replaced each time they are linked
+    callbacks: RefCell<Callbacks>,
+    /// Snapshot index of the playback cursor
+    playback: Cell<Option<usize>>,
+    /// Heatmap row label to scroll to once it is drawn
+    pending_row: RefCell<Option<String>>,
+    row_scroller: RefCell<Option<RowScroller>>,
+}
+
+impl PartialEq for ChartLinks {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl ChartLinks {
+    pub(crate) fn set_timeline(&self, timeline: Timeline) {
+        *self.timeline.borrow_mut() = Some(Rc::new(timeline));
+    }
+
+    /// Link every chart on the page but the `UNLINKED_CHARTS`, replacing the
+    /// links of earlier draws. Does nothing until the timeline is drawn.
+    pub(crate) fn link_charts(&self) {
+        let Some(timeline) = self.timeline.borrow().clone() else {
+            return;
+        };
+        echarts_connect(TIMELINE_GROUP);
+        let charts = page_charts()
+            .into_iter()
+            .filter(|chart| !UNLINKED_CHARTS.contains(&chart.dom().id().as_str()))
+            .map(|chart| {
+                let option = chart.drawn_option();
+                LinkedChart {
+                    time_axis: option.has_time_axis(),
+                    axis_tooltip: option
+                        .tooltip
+                        .first()
+                        .is_some_and(|tooltip| tooltip.trigger.as_deref() == Some("axis")),
+                    grouped: chart.group().as_deref() == Some(TIMELINE_GROUP),
+                    chart,
+                }
+            })
+            .collect();
+        let links = Rc::new(Links {
+            charts,
+            ..Links::default()
+        });
+        let mut callbacks = Callbacks::default();
+        for source in 0..links.charts.len() {
+            add_markers(&links, source, &timeline, &mut callbacks);
+            add_time_zoom(&links, source, &timeline, &mut callbacks);
+            link_crosshair(&links, source, &timeline, &mut callbacks);
+            add_toolbox(&links.charts[source].chart, timeline.theme, &mut callbacks);
+            let id = links.charts[source].chart.dom().id();
+            if BRUSHED_CHARTS.contains(&id.as_str()) {
+                add_range_brush(&links.charts[source].chart, &timeline, &mut callbacks);
+            }
+        }
+        // Charts entering or leaving fullscreen are laid out for their new size
+        let on_fullscreen = callbacks.function(Closure::<dyn Fn()>::new(resize_charts));
+        gloo::utils::document().set_onfullscreenchange(Some(&on_fullscreen));
+        *self.callbacks.borrow_mut() = callbacks;
+        *self.links.borrow_mut() = links;
+        if self.playback.get().is_some() {
+            self.show_playback_cursor(self.playback.get());
+        }
+    }
+
+    /// Draw the playback cursor at snapshot `index` on the linked charts, or
+    /// remove it for None. Moved without redrawing the charts.
+    pub(crate) fn show_playback_cursor(&self, index: Option<usize>) {
+        self.playback.set(index);
+        let Some(timeline) = self.timeline.borrow().clone() else {
+            return;
+        };
+        for linked in &self.links.borrow().charts {
+            let data: Vec<_> = index
+                .and_then(|index| timeline.axis_value(linked.time_axis, index))
+                .map(|x| XMark {
+                    name: None,
+                    x_axis: x,
+                })
+                .into_iter()
+                .collect();
+            let cursor = MarkLine {
+                symbol: Some("none"),
+                silent: true,
+                animation: Some(false),
+                label: Some(MarkLabel {
+                    show: Some(false),
+                    ..MarkLabel::default()
+                }),
+                line_style: Some(LineStyle {
+                    kind: Some(LineType::Solid),
+                    width: Some(2.0),
+                    color: Some("#d62728".to_string()),
+                }),
+                data,
+            };
+            linked.chart.merge_option(&OptionUpdate {
+                series: vec![MarkLineSeries::new("playback-cursor", cursor)],
+                ..OptionUpdate::default()
+            });
+        }
+    }
+
+    /// Have `scroller` scroll the page to heatmap rows, and scroll to the
+    /// row asked for while it wasn't drawn.
+    pub(crate) fn set_row_scroller(&self, scroller: impl Fn(&str) -> bool + 'static) {
+        *self.row_scroller.borrow_mut() = Some(Box::new(scroller));
+        self.scroll_to_pending_row();
+    }
+
+    /// Scroll the page to a heatmap row, now if it is drawn or else once it
+    /// is (e.g. after paging to it).
+    pub(crate) fn scroll_to_row(&self, label: String) {
+        *self.pending_row.borrow_mut() = Some(label);
+        self.scroll_to_pending_row();
+    }
+
+    fn scroll_to_pending_row(&self) {
+        let Some(label) = self.pending_row.borrow().clone() else {
+            return;
+        };
+        let scrolled = self
+            .row_scroller
+            .borrow()
+            .as_ref()
+            .is_some_and(|scroll| scroll(&label));
+        if scrolled {
+            self.pending_row.take();
+        }
+    }
+}
+
+/// The event and bookmark markers, and double-clicking to add a bookmark at
+/// the snapshot under the cursor.
+fn add_markers(
+    links: &Rc<Links>,
+    source: usize,
+    timeline: &Rc<Timeline>,
+    callbacks: &mut Callbacks,
+) {
+    let linked = &links.charts[source];
+    // Set even when empty, clearing markers removed since the charts were linked
+    let marker_lines = |id, markers: &[Marker], color: &str, kind| {
+        let data = markers
+            .iter()
+            .filter_map(|marker| {
+                let time = marker.time.filter(|_| linked.time_axis);
+                let x = time.or_else(|| timeline.axis_value(linked.time_axis, marker.index))?;
+                Some(XMark {
+                    name: Some(marker.name.clone()),
+                    x_axis: x,
+                })
+            })
+            .collect();
+        let mark_line = MarkLine {
+            symbol: Some("none"),
+            silent: true,
+            animation: None,
+            label: Some(MarkLabel {
+                formatter: Some("{b}".to_string()),
+                position: Some("insideEndTop"),
+                ..MarkLabel::default()
+            }),
+            line_style: Some(LineStyle {
+                kind: Some(kind),
+                width: None,
+                color: Some(color.to_string()),
+            }),
+            data,
+        };
+        MarkLineSeries::new(id, mark_line)
+    };
+    linked.chart.merge_option(&OptionUpdate {
+        series: vec![
+            marker_lines(
+                "timeline-events",
+                &timeline.events,
+                "#555",
+                LineType::Dashed,
+            ),
+            marker_lines(
+                "timeline-bookmarks",
+                &timeline.bookmarks,
+                "#1f77b4",
+                LineType::Solid,
+            ),
+        ],
+        ..OptionUpdate::default()
+    });
+
+    let chart = &linked.chart;
+    let (links, timeline) = (links.clone(), timeline.clone());
+    let finder = to_js(&AxisFinder::XAxisIndex(0)).unwrap_or_default();
+    chart.bind_canvas("dblclick", callbacks, move |event| {
+        let Ok(event) = serde_wasm_bindgen::from_value::<CanvasEvent>(event) else {
+            return;
+        };
+        let linked = &links.charts[source];
+        let pixel = to_js(&[event.offset_x, event.offset_y]).unwrap_or_default();
+        let point = linked.chart.convert_from_pixel(&finder, &pixel);
+        let Some(x) = serde_wasm_bindgen::from_value::<Vec<f64>>(point)
+            .ok()
+            .and_then(|point| point.first().copied())
+            .filter(|x| !x.is_nan())
+        else {
+            return;
+        };
+        let Some(index) = timeline.snapshot_index(linked.time_axis, x) else {
+            return;
+        };
+        let message = format!("Bookmark at {}", timeline.trace.columns().timestamps[index]);
+        let name = gloo::utils::window().prompt_with_message_and_default(&message, "Bookmark");
+        if let Ok(Some(name)) = name.map(|name| name.filter(|name| !name.is_empty())) {
+            timeline.on_bookmark.emit((index, name));
+        }
+    });
+}
+
+/// Zoom sliders over the selected range; zooming one chart zooms the others
+/// and narrows the selected range.
+fn add_time_zoom(
+    links: &Rc<Links>,
+    source: usize,
+    timeline: &Rc<Timeline>,
+    callbacks: &mut Callbacks,
+) {
+    let linked = &links.charts[source];
+    let start_value = timeline.axis_value(linked.time_axis, timeline.min);
+    let end_value = timeline.axis_value(linked.time_axis, timeline.max);
+    linked.chart.merge_option(&OptionUpdate {
+        // Naive timestamps are parsed as UTC; show them as written
+        use_utc: Some(true),
+        data_zoom: vec![
+            DataZoom::slider().between(start_value, end_value),
+            DataZoom::inside().between(start_value, end_value),
+        ],
+        ..OptionUpdate::default()
+    });
+
+    let (links, timeline) = (links.clone(), timeline.clone());
+    linked.chart.bind("datazoom", callbacks, move |_| {
+        let linked = &links.charts[source];
+        let option = linked.chart.drawn_option();
+        let Some((Some(from), Some(to))) = option
+            .data_zoom
+            .first()
+            .map(|zoom| (zoom.start_value, zoom.end_value))
+        else {
+            return;
+        };
+        let range = if linked.time_axis {
+            timeline.snapshots_between(from, to)
+        } else {
+            Some((from.round() as usize, to.round() as usize))
+        };
+        let Some((start, end)) = range else {
+            return;
+        };
+        if links.zoom.replace(Some((start, end))) == Some((start, end)) {
+            return;
+        }
+        // Follow along in the other charts before the data is re-culled
+        links.for_others(linked, |target| {
+            let start_value = timeline.axis_value(target.time_axis, start);
+            let end_value = timeline.axis_value(target.time_axis, end);
+            if let (Some(start_value), Some(end_value)) = (start_value, end_value) {
+                target.chart.dispatch(&Action::DataZoom {
+                    start_value,
+                    end_value,
+                });
+            }
+        });
+        timeline.on_time_range.emit((start, end));
+    });
+}
+
+/// A crosshair at the same snapshot on every chart, following the mouse.
+fn link_crosshair(
+    links: &Rc<Links>,
+    source: usize,
+    timeline: &Rc<Timeline>,
+    callbacks: &mut Callbacks,
+) {
+    let linked = &links.charts[source];
+    // Item-triggered charts need an x axis pointer to show the crosshair
+    if !linked.axis_tooltip {
+        linked.chart.merge_option(&OptionUpdate {
+            x_axis: Some(AxisUpdate {
+                axis_pointer: Some(AxisPointer {
+                    show: true,
+                    kind: "line",
+                    trigger_tooltip: false,
+                    label: Show { show: false },
+                }),
+            }),
+            ..OptionUpdate::default()
+        });
+    }
+
+    let (moved_links, timeline) = (links.clone(), timeline.clone());
+    let finder = to_js(&AxisFinder::XAxisIndex(0)).unwrap_or_default();
+    linked
+        .chart
+        .bind("updateAxisPointer", callbacks, move |event| {
+            let links = &moved_links;
+            let Ok(event) = serde_wasm_bindgen::from_value::<AxisPointerEvent>(event) else {
+                return;
+            };
+            let Some(axis) = event.axes_info.iter().find(|axis| axis.axis_dim == "x") else {
+                return;
+            };
+            let linked = &links.charts[source];
+            let Some(index) = timeline.snapshot_index(linked.time_axis, axis.value) else {
+                return;
+            };
+            if links.pointer.replace(Some(index)) == Some(index) {
+                return;
+            }
+            links.for_others(linked, |target| {
+                let Some(value) = timeline.axis_value(target.time_axis, index) else {
+                    return;
+                };
+                let x = target
+                    .chart
+                    .convert_to_pixel(&finder, &value.into())
+                    .as_f64();
+                target.chart.dispatch(&Action::UpdateAxisPointer {
+                    curr_trigger: PointerTrigger::Mousemove,
+                    x,
+                    y: Some(target.chart.height() / 2.0),
+                });
+            });
+        });
+
+    let links = links.clone();
+    linked.chart.bind("globalout", callbacks, move |_| {
+        links.pointer.set(None);
+        links.for_others(&links.charts[source], |target| {
+            target.chart.dispatch(&Action::UpdateAxisPointer {
+                curr_trigger: PointerTrigger::Leave,
+                x: None,
+                y: None,
+            });
+            target.chart.dispatch(&Action::HideTip);
+        });
+    });
+}
+
+/// Toolbox buttons saving the chart as PNG or SVG, and showing it fullscreen.
+fn add_toolbox(chart: &ECharts, theme: Theme, callbacks: &mut Callbacks) {
+    let feature = ToolboxFeatures {
+        my_export_png: Some(ToolboxButton {
+            title: "Save as PNG",
+            icon: "path://M4,4H28V28H4ZM8,22L13,15L17,20L20,17L24,22Z",
+        }),
+        my_export_svg: Some(ToolboxButton {
+            title: "Save as SVG",
+            icon: "path://M4,4H28V28H4ZM8,24L24,8M8,8H14M18,24H24",
+        }),
+        my_fullscreen: Some(ToolboxButton {
+            title: "Fullscreen",
+            icon: "path://M4,12V4H12M20,4H28V12M28,20V28H20M12,28H4V20",
+        }),
+        ..ToolboxFeatures::default()
+    };
+    let Some(option) = to_js(&OptionUpdate {
+        toolbox: Some(Toolbox {
+            right: None,
+            feature,
+        }),
+        ..OptionUpdate::default()
+    }) else {
+        return;
+    };
+    for (feature, kind) in [("myExportPng", "png"), ("myExportSvg", "svg")] {
+        let chart = chart.clone();
+        let onclick = Closure::<dyn Fn()>::new(move || download_chart(&chart, kind, theme));
+        let onclick = callbacks.function(onclick);
+        set_path(
+            &option,
+            &["toolbox", "feature", feature, "onclick"],
+            &onclick,
+        );
+    }
+    let dom = chart.dom();
+    let onclick = callbacks.function(Closure::<dyn Fn()>::new(move || {
+        let document = gloo::utils::document();
+        if document.fullscreen_element().as_ref() == Some(&dom) {
+            document.exit_fullscreen();
+        } else {
+            let _ = dom.request_fullscreen();
+        }
+    }));
+    set_path(
+        &option,
+        &["toolbox", "feature", "myFullscreen", "onclick"],
+        &onclick,
+    );
+    chart.set_option(&option);
+}
+
+/// Download a chart as "png", or as "svg" rendered again offscreen since the
+/// charts on the page use the canvas renderer.
+fn download_chart(chart: &ECharts, kind: &str, theme: Theme) {
+    let background = match theme {
+        Theme::Light => "#fff",
+        Theme::Dark => "#100c2a",
+    };
+    let url = if kind == "svg" {
+        let Some(svg) = render_svg(chart, theme, background) else {
+            return;
+        };
+        let svg = String::from(js_sys::encode_uri_component(&svg));
+        format!("data:image/svg+xml;charset=utf-8,{svg}")
+    } else {
+        let Some(options) = to_js(&DataUrlOptions {
+            kind,
+            pixel_ratio: 2.0,
+            background_color: background,
+            exclude_components: &["toolbox", "dataZoom", "brush"],
+        }) else {
+            return;
+        };
+        chart.data_url(&options)
+    };
+    let id = chart.dom().id();
+    let name = if id.is_empty() { "chart" } else { &id };
+    download_url(&format!("{name}.{kind}"), &url);
+}
+
+/// The chart as an SVG document, without its toolbox and zoom sliders.
+fn render_svg(chart: &ECharts, theme: Theme, background: &str) -> Option<String> {
+    let opts = to_js(&InitOptions {
+        renderer: "svg",
+        ssr: true,
+        width: chart.width(),
+        height: chart.height(),
+    })?;
+    let offscreen = echarts_init_detached(JsValue::NULL, theme.chart_theme(), &opts).ok()?;
+    let option = Object::assign(&Object::new(), chart.get_option().unchecked_ref());
+    // Keep the zoomed range without drawing the slider
+    let zooms: Array = Reflect::get(&option, &"dataZoom".into())
+        .ok()
+        .and_then(|zooms| zooms.dyn_into::<Array>().ok())
+        .unwrap_or_default()
+        .iter()
+        .map(|zoom| {
+            let zoom = Object::assign(&Object::new(), zoom.unchecked_ref());
+            set_path(&zoom, &["show"], &false.into());
+            zoom
+        })
+        .collect();
+    set_path(&option, &["dataZoom"], &zooms);
+    set_path(&option, &["animation"], &false.into());
+    set_path(&option, &["backgroundColor"], &background.into());
+    set_path(&option, &["toolbox"], &Array::new());
+    set_path(&option, &["brush"], &Array::new());
+    offscreen.set_option(&option);
+    let svg = offscreen.render_to_svg_string();
+    offscreen.dispose();
+    Some(svg)
+}
+
+/// Brushing a range (with the toolbox button) shows its statistics.
+fn add_range_brush(chart: &ECharts, timeline: &Rc<Timeline>, callbacks: &mut Callbacks) {
+    chart.merge_option(&OptionUpdate {
+        toolbox: Some(Toolbox {
+            right: Some(Length::Px(20.0)),
+            feature: ToolboxFeatures {
+                brush: Some(BrushFeature {
+                    kinds: vec!["lineX", "clear"],
+                }),
+                ..ToolboxFeatures::default()
+            },
+        }),
+        brush: Some(Brush::x_range()),
+        ..OptionUpdate::default()
+    });
+    let timeline = timeline.clone();
+    chart.bind("brushEnd", callbacks, move |event| {
+        let Ok(event) = serde_wasm_bindgen::from_value::<BrushEvent>(event) else {
+            return;
+        };
+        let Some((from, to)) = event.areas.first().and_then(|area| area.coord_range) else {
+            return;
+        };
+        if let Some(range) = timeline.snapshots_between(from, to) {
+            timeline.on_range_stats.emit(range);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use timeline_core::model::Snapshot;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    /// Snapshots at 0, 1000 and 3000 ms, with one in between that has no time.
+    fn timeline() -> Timeline {
+        Timeline {
+            theme: Theme::Light,
+            snapshot_times: vec![Some(0.0), Some(1000.0), None, Some(3000.0)],
+            trace: Rc::new(Trace::new(vec![Snapshot::default(); 4])),
+            min: 0,
+            max: 3,
+            events: Vec::new(),
+            bookmarks: Vec::new(),
+            on_time_range: Callback::noop(),
+            on_range_stats: Callback::noop(),
+            on_bookmark: Callback::noop(),
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn maps_snapshots_onto_time_and_category_axes() {
+        let timeline = timeline();
+        assert_eq!(timeline.axis_value(true, 1), Some(1000.0));
+        assert_eq!(timeline.axis_value(true, 2), None);
+        assert_eq!(timeline.axis_value(false, 2), Some(2.0));
+        assert_eq!(timeline.snapshot_index(true, 2200.0), Some(3));
+        assert_eq!(timeline.snapshot_index(false, 1.6), Some(2));
+        assert_eq!(timeline.snapshot_index(false, 3.6), None);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn finds_the_snapshots_within_a_time_range() {
+        let timeline = timeline();
+        assert_eq!(timeline.snapshots_between(500.0, 3000.0), Some((1, 3)));
+        assert_eq!(timeline.snapshots_between(1500.0, 2500.0), None);
+    }
+}
@@ -0,0 +1,157 @@
+use super::{Panel, PanelData, TraceField};
+use timeline_core::echarts::{Axis, ChartOption, ItemStyle, Legend, LineStyle, Series};
+use timeline_core::matrix::state_value;
+use timeline_core::series::{line_series, moving_average};
+
+pub(super) struct CpuLoad;
+
+impl Panel for CpuLoad {
+    fn id(&self) -> &'static str {
+        "cpu-load-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "CPU utilization"
+    }
+
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let mut cpu_trace: Vec<(usize, f64)> = Vec::new();
+        let running = state_value(Some("R")) as usize;
+        for timestamp_index in data.recorded() {
+            let running_threads = data.columns.state_counts[timestamp_index][running];
+            let total_cores = data.columns.cores[timestamp_index].max(1); // prevent division by 0
+            let cpu_percent = (running_threads as f64 / total_cores as f64) * 100.0;
+            cpu_trace.push((timestamp_index, cpu_percent));
+        }
+        let cpu_trace = moving_average(&cpu_trace, data.smoothing);
+        vec![line_series(
+            "CPU Utilization",
+            "",
+            &cpu_trace,
+            &data.snapshot_times,
+        )]
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "CPU Utilization Over Time (%)",
+            None,
+            Axis::percent(),
+            series,
+        ))
+    }
+}
+
+/// Thread states plotted as (name, heatmap value).
+const STATE_SERIES: [(&str, usize); 6] = [
+    ("Running (R)", 1),
+    ("Sleeping (S)", 2),
+    ("Uninterruptible (D)", 5),
+    ("Zombie (Z)", 3),
+    ("Stopped (T)", 4),
+    ("Idle (I)", 6),
+];
+
+pub(super) struct ThreadStates;
+
+impl Panel for ThreadStates {
+    fn id(&self) -> &'static str {
+        "thread-state-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "Threads by state"
+    }
+
+    /// Threads per state, stacked with the heatmap's state colors.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let mut state_counts: Vec<Vec<(usize, f64)>> = vec![Vec::new(); STATE_SERIES.len()];
+        for timestamp_index in data.recorded() {
+            let counts = &data.columns.state_counts[timestamp_index];
+            for (points, &(_, value)) in state_counts.iter_mut().zip(&STATE_SERIES) {
+                points.push((timestamp_index, counts[value] as f64));
+            }
+        }
+        let state_colors = &data.palette.states;
+        STATE_SERIES
+            .iter()
+            .zip(&state_counts)
+            .map(|(&(name, value), points)| Series {
+                item_style: Some(ItemStyle::color(&state_colors[value])),
+                line_style: Some(LineStyle {
+                    width: Some(0.0),
+                    ..LineStyle::default()
+                }),
+                ..line_series(name, "", points, &data.snapshot_times).stacked("states")
+            })
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "Threads by State",
+            Some(Legend::at(20.0)),
+            Axis::from_zero(""),
+            series,
+        ))
+    }
+}
+
+pub(super) struct LoadAverage;
+
+impl Panel for LoadAverage {
+    fn id(&self) -> &'static str {
+        "load-avg-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "Load average"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        &[TraceField::LoadAverage]
+    }
+
+    /// One set of series per host.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let system = &data.columns.system;
+        data.host_series(&[
+            ("1 min", &system.load_avg_1),
+            ("5 min", &system.load_avg_5),
+            ("15 min", &system.load_avg_15),
+        ])
+        .iter()
+        .map(|(name, points)| line_series(name, "", points, &data.snapshot_times))
+        .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "Load Average",
+            Some(Legend::at(20.0)),
+            Axis::from_zero(""),
+            series,
+        ))
+    }
+}
+
+pub(super) struct ProcessCpu;
+
+impl Panel for ProcessCpu {
+    fn id(&self) -> &'static str {
+        "process-cpu-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "CPU per process"
+    }
+
+    /// Drawn by `HeatmapPanel` along with the timeline, whose rows it follows.
+    fn build_series(&self, _data: &PanelData) -> Vec<Series> {
+        Vec::new()
+    }
+
+    fn render(&self, _data: &PanelData, _series: Vec<Series>) -> Option<ChartOption> {
+        None
+    }
+}
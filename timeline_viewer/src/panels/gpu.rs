@@ -0,0 +1,438 @@
+use super::{Panel, PanelData, TraceField};
+use indexmap::IndexMap;
+use timeline_core::columns::GpuSample;
+use timeline_core::echarts::{
+    Axis, ChartOption, ItemStyle, Legend, Level, LineStyle, LineType, MarkArea, MarkLabel,
+    MarkLine, Series, Show, YMark,
+};
+use timeline_core::series::{dashed_line_series, line_series, moving_average};
+
+const GPU_FIELDS: &[TraceField] = &[TraceField::GpuStatus];
+
+/// Points by GPU label.
+type GpuPoints = IndexMap<String, Vec<(usize, f64)>>;
+
+/// Points of each GPU over the selected range, labelled by host and GPU id,
+/// skipping the samples where `value` is None.
+fn per_gpu(data: &PanelData, value: impl Fn(&GpuSample) -> Option<f64>) -> GpuPoints {
+    let mut series = GpuPoints::new();
+    for (label, gpu) in &data.columns.gpus {
+        let points: Vec<(usize, f64)> = (data.min..=data.max)
+            .filter_map(|timestamp_index| {
+                let value = value(&gpu.at(timestamp_index)?)?;
+                Some((timestamp_index, value))
+            })
+            .collect();
+        if !points.is_empty() {
+            series.insert(label.clone(), points);
+        }
+    }
+    series
+}
+
+pub(super) struct GpuLoad;
+
+impl Panel for GpuLoad {
+    fn id(&self) -> &'static str {
+        "gpu-load-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "GPU load"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        GPU_FIELDS
+    }
+
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        per_gpu(data, |gpu| Some(gpu.load_percent))
+            .iter()
+            .map(|(gpu_label, points)| {
+                let points = moving_average(points, data.smoothing);
+                line_series(gpu_label, "", &points, &data.snapshot_times)
+            })
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "GPU Load Over Time (%)",
+            Some(Legend::at(20.0)),
+            Axis::percent(),
+            series,
+        ))
+    }
+}
+
+pub(super) struct GpuMemory;
+
+impl GpuMemory {
+    /// Memory used by each GPU in MB, and its capacity.
+    fn used_and_total(data: &PanelData) -> (GpuPoints, IndexMap<String, f64>) {
+        let used = per_gpu(data, |gpu| Some(gpu.memory_used_mb));
+        let mut total: IndexMap<String, f64> = IndexMap::new();
+        for (label, capacity) in per_gpu(data, |gpu| Some(gpu.memory_total_mb)) {
+            total.insert(
+                label,
+                capacity.iter().map(|&(_, mb)| mb).fold(0.0, f64::max),
+            );
+        }
+        (used, total)
+    }
+}
+
+impl Panel for GpuMemory {
+    fn id(&self) -> &'static str {
+        "gpu-mem-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "GPU memory"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        GPU_FIELDS
+    }
+
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let percent_used = per_gpu(data, |gpu| {
+            Some(if gpu.memory_total_mb > 0.0 {
+                (gpu.memory_used_mb / gpu.memory_total_mb) * 100.0
+            } else {
+                0.0
+            })
+        });
+        // Absolute GPU memory on the second axis, with each GPU's capacity marked
+        let (used_mb, total_mb) = Self::used_and_total(data);
+        percent_used
+            .iter()
+            .map(|(gpu_label, points)| {
+                line_series(gpu_label, " Mem %", points, &data.snapshot_times)
+            })
+            .chain(used_mb.iter().map(|(label, points)| {
+                let total = total_mb.get(label).copied().unwrap_or_default();
+                Series {
+                    y_axis_index: Some(1),
+                    mark_line: Some(MarkLine {
+                        symbol: Some("none"),
+                        silent: true,
+                        animation: None,
+                        label: Some(MarkLabel {
+                            formatter: Some(format!("{label} total")),
+                            ..MarkLabel::default()
+                        }),
+                        line_style: None,
+                        data: vec![YMark {
+                            y_axis: Level::Value(total),
+                        }],
+                    }),
+                    ..line_series(label, " MB", points, &data.snapshot_times)
+                        .with_line_type(LineType::Dotted)
+                }
+            }))
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        // Keep the capacity lines in view
+        let (used_mb, total_mb) = Self::used_and_total(data);
+        let axis_max_mb = total_mb
+            .values()
+            .copied()
+            .chain(used_mb.values().flatten().map(|&(_, mb)| mb))
+            .fold(0.0, f64::max);
+        let mb_axis = Axis {
+            max: Some(axis_max_mb),
+            split_line: Some(Show { show: false }),
+            ..Axis::from_zero("MB")
+        };
+        Some(ChartOption {
+            y_axis: vec![Axis::percent(), mb_axis],
+            ..data.line_chart(
+                "GPU Memory Usage Over Time (% and MB)",
+                Some(Legend::scrolling(20.0)),
+                Axis::percent(),
+                series,
+            )
+        })
+    }
+}
+
+pub(super) struct GpuPower;
+
+impl Panel for GpuPower {
+    fn id(&self) -> &'static str {
+        "gpu-power-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "GPU power"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        GPU_FIELDS
+    }
+
+    /// Power draw, with the limit as a dashed line.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let draw = per_gpu(data, |gpu| gpu.power_draw_w);
+        let limit = per_gpu(data, |gpu| gpu.power_limit_w);
+        draw.iter()
+            .map(|(label, points)| {
+                line_series(
+                    label,
+                    "",
+                    &moving_average(points, data.smoothing),
+                    &data.snapshot_times,
+                )
+            })
+            .chain(limit.iter().map(|(label, points)| {
+                dashed_line_series(label, " limit", points, &data.snapshot_times)
+            }))
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "GPU Power Over Time (W)",
+            Some(Legend::at(20.0)),
+            Axis::from_zero("W"),
+            series,
+        ))
+    }
+}
+
+pub(super) struct GpuTemperature;
+
+impl Panel for GpuTemperature {
+    fn id(&self) -> &'static str {
+        "gpu-temp-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "GPU temperature"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        GPU_FIELDS
+    }
+
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let mut series: Vec<_> = per_gpu(data, |gpu| Some(gpu.temperature_c))
+            .iter()
+            .map(|(label, points)| line_series(label, "", points, &data.snapshot_times))
+            .collect();
+        // Shade everything above the warning threshold
+        let warning = YMark {
+            y_axis: Level::Value(data.gpu_temp_warning),
+        };
+        series.push(Series {
+            mark_line: Some(MarkLine {
+                symbol: Some("none"),
+                silent: true,
+                animation: None,
+                label: None,
+                line_style: Some(LineStyle {
+                    color: Some("#d73027".to_string()),
+                    ..LineStyle::default()
+                }),
+                data: vec![warning],
+            }),
+            mark_area: Some(MarkArea {
+                silent: true,
+                item_style: Some(ItemStyle::color("rgba(215, 48, 39, 0.1)")),
+                data: vec![[warning, YMark { y_axis: Level::Max }]],
+            }),
+            ..Series::line("Warning", Vec::new())
+        });
+        series
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        let axis_max = series
+            .iter()
+            .flat_map(|series| series.data.iter().filter_map(|&(_, celsius)| celsius))
+            .fold(data.gpu_temp_warning, f64::max)
+            + 5.0;
+        Some(data.line_chart(
+            "GPU Temperature Over Time (°C)",
+            Some(Legend::at(20.0)),
+            Axis {
+                max: Some(axis_max),
+                ..Axis::from_zero("°C")
+            },
+            series,
+        ))
+    }
+}
+
+pub(super) struct GpuClocks;
+
+impl Panel for GpuClocks {
+    fn id(&self) -> &'static str {
+        "gpu-clock-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "GPU clocks"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        GPU_FIELDS
+    }
+
+    /// SM clocks solid, memory clocks dashed.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let sm_clock = per_gpu(data, |gpu| gpu.sm_clock_mhz);
+        let mem_clock = per_gpu(data, |gpu| gpu.memory_clock_mhz);
+        sm_clock
+            .iter()
+            .map(|(label, points)| line_series(label, " SM", points, &data.snapshot_times))
+            .chain(mem_clock.iter().map(|(label, points)| {
+                dashed_line_series(label, " memory", points, &data.snapshot_times)
+            }))
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "GPU Clocks Over Time (MHz)",
+            Some(Legend::at(20.0)),
+            Axis::from_zero("MHz"),
+            series,
+        ))
+    }
+}
+
+pub(super) struct ProcessGpuMemory;
+
+impl Panel for ProcessGpuMemory {
+    fn id(&self) -> &'static str {
+        "process-gpu-mem-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "GPU memory per process"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        GPU_FIELDS
+    }
+
+    /// GPU memory held by each process in the tree, summed over GPUs.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        data.process_series(|proc| &proc.gpu_memory_mb)
+            .iter()
+            .map(|(&row, points)| {
+                line_series(data.label(row), "", points, &data.snapshot_times).stacked("gpu-mem")
+            })
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "GPU Memory by Process (MB)",
+            Some(Legend::scrolling(20.0)),
+            Axis::from_zero("MB"),
+            series,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{Palette, Theme};
+    use timeline_core::columns::TraceColumns;
+    use timeline_core::model::{GPUStatus, Snapshot};
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn gpu(id: u32, load: f64, power_draw: Option<f64>) -> GPUStatus {
+        GPUStatus {
+            GPU_ID: id,
+            Load_Percent: load,
+            Memory_Used_MB: 1000.0,
+            Memory_Total_MB: 4000.0,
+            Power_Draw_W: power_draw,
+            Power_Limit_W: Some(300.0),
+            ..GPUStatus::default()
+        }
+    }
+
+    /// Two GPUs on `node1`; only the first reports its power draw.
+    fn snapshots() -> Vec<Snapshot> {
+        (0..2)
+            .map(|second| Snapshot {
+                Timestamp: format!("2024-05-01T12:00:0{second}"),
+                Hostname: Some("node1".to_string()),
+                GPUStatus: vec![gpu(0, 50.0, Some(200.0)), gpu(1, 10.0, None)],
+                ..Snapshot::default()
+            })
+            .collect()
+    }
+
+    fn series(panel: &dyn Panel) -> Vec<Series> {
+        let snapshots = snapshots();
+        let columns = TraceColumns::new(&snapshots);
+        let palette = Palette::defaults(Theme::Light);
+        let data = PanelData::new(&columns, 0, 1, &palette, 1, 80.0);
+        panel.build_series(&data)
+    }
+
+    fn names(series: &[Series]) -> Vec<&str> {
+        series.iter().map(|series| series.name.as_str()).collect()
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn labels_gpus_by_host() {
+        let load = series(&GpuLoad);
+        assert_eq!(names(&load), ["[node1] GPU #0", "[node1] GPU #1"]);
+        let start = 1714564800000.0;
+        assert_eq!(
+            load[0].data,
+            [(start, Some(50.0)), (start + 1000.0, Some(50.0))]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn skips_missing_readings() {
+        assert_eq!(
+            names(&series(&GpuPower)),
+            [
+                "[node1] GPU #0",
+                "[node1] GPU #0 limit",
+                "[node1] GPU #1 limit"
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn puts_memory_in_mb_on_the_second_axis() {
+        let memory = series(&GpuMemory);
+        assert_eq!(
+            names(&memory),
+            [
+                "[node1] GPU #0 Mem %",
+                "[node1] GPU #1 Mem %",
+                "[node1] GPU #0 MB",
+                "[node1] GPU #1 MB"
+            ]
+        );
+        assert_eq!(memory[0].data[0].1, Some(25.0));
+        assert_eq!(memory[2].y_axis_index, Some(1));
+        let capacity = memory[2]
+            .mark_line
+            .as_ref()
+            .map(|mark| &mark.data[0].y_axis);
+        assert!(matches!(capacity, Some(Level::Value(mb)) if *mb == 4000.0));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn needs_gpu_status() {
+        let columns = TraceColumns::new(&[Snapshot::default()]);
+        assert_eq!(GpuLoad.missing_fields(&columns), [TraceField::GpuStatus]);
+        let columns = TraceColumns::new(&self::snapshots());
+        assert!(GpuLoad.missing_fields(&columns).is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use super::{Panel, PanelData, TraceField};
+use timeline_core::echarts::{Axis, ChartOption, Legend, Series};
+use timeline_core::series::{dashed_line_series, line_series};
+
+pub(super) struct SystemMemory;
+
+impl Panel for SystemMemory {
+    fn id(&self) -> &'static str {
+        "system-mem-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "System memory"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        &[TraceField::SystemMemory]
+    }
+
+    /// One set of series per host.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let system = &data.columns.system;
+        data.host_series(&[
+            ("Used", &system.memory_used_mb),
+            ("Cached", &system.memory_cached_mb),
+            ("Total", &system.memory_total_mb),
+        ])
+        .iter()
+        .map(|(name, points)| line_series(name, "", points, &data.snapshot_times))
+        .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "System Memory (MB)",
+            Some(Legend::at(20.0)),
+            Axis::from_zero("MB"),
+            series,
+        ))
+    }
+}
+
+pub(super) struct Swap;
+
+impl Panel for Swap {
+    fn id(&self) -> &'static str {
+        "swap-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "Swap"
+    }
+
+    fn required_fields(&self) -> &'static [TraceField] {
+        &[TraceField::Swap]
+    }
+
+    /// Swap used, with the total drawn dashed as the ceiling.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let system = &data.columns.system;
+        let swap_used = data.host_series(&[("Swap used", &system.swap_used_mb)]);
+        let swap_total = data.host_series(&[("Swap total", &system.swap_total_mb)]);
+        swap_used
+            .iter()
+            .map(|(name, points)| line_series(name, "", points, &data.snapshot_times))
+            .chain(
+                swap_total.iter().map(|(name, points)| {
+                    dashed_line_series(name, "", points, &data.snapshot_times)
+                }),
+            )
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "Swap Usage (MB)",
+            Some(Legend::at(20.0)),
+            Axis::from_zero("MB"),
+            series,
+        ))
+    }
+}
+
+pub(super) struct ProcessMemory;
+
+impl Panel for ProcessMemory {
+    fn id(&self) -> &'static str {
+        "process-mem-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "Memory per process"
+    }
+
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let process_rss = data.process_series(|proc| &proc.memory_rss_mb);
+        process_rss
+            .iter()
+            .map(|(&row, points)| line_series(data.label(row), "", points, &data.snapshot_times))
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "Process Memory Over Time (RSS MB)",
+            Some(Legend::scrolling(20.0)),
+            Axis::from_zero("MB"),
+            series,
+        ))
+    }
+}
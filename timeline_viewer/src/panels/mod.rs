@@ -0,0 +1,251 @@
+//! The line charts below the timeline. Each is a `Panel` listed in `PANELS`,
+//! which is all a new chart needs to be drawn, hidden and reordered.
+
+mod cpu;
+#[cfg(feature = "gpu")]
+mod gpu;
+mod memory;
+mod process;
+
+use crate::settings::Palette;
+use indexmap::IndexMap;
+use std::collections::BTreeMap;
+use timeline_core::columns::{Column, ProcessColumns, Sample, ThreadColumns, TraceColumns};
+use timeline_core::echarts::{Axis, ChartOption, Legend, Series, Title, Tooltip};
+use timeline_core::matrix::RowId;
+use timeline_core::series::downsample;
+
+/// Points drawn per pixel of chart width. Longer series are downsampled, and
+/// regain their detail as zooming in culls them to fewer samples.
+const POINTS_PER_PIXEL: usize = 2;
+
+/// Downsample each series to the points a chart `width_px` wide can show.
+/// Stacked series are left whole, as their points have to line up, and so is
+/// everything on a chart not laid out yet.
+pub(crate) fn fit_to_width(series: &mut [Series], width_px: u32) {
+    if width_px == 0 {
+        return;
+    }
+    let max_points = width_px as usize * POINTS_PER_PIXEL;
+    for series in series.iter_mut().filter(|series| series.stack.is_none()) {
+        series.data = downsample(&series.data, max_points);
+    }
+}
+
+/// What the panels build their series from.
+pub(crate) struct PanelData<'a> {
+    /// The whole trace; panels plot the selected range of it
+    pub(crate) columns: &'a TraceColumns,
+    /// Selected range of snapshot indices
+    pub(crate) min: usize,
+    pub(crate) max: usize,
+    /// Timestamp of each snapshot in microseconds, for counter rates
+    pub(crate) micros: &'a [Option<f64>],
+    /// Timestamp of each snapshot in milliseconds, for the time axis
+    pub(crate) snapshot_times: Vec<Option<f64>>,
+    pub(crate) time_axis: Axis,
+    pub(crate) palette: &'a Palette,
+    /// Moving-average window of the GPU and CPU traces, in samples
+    pub(crate) smoothing: usize,
+    /// Only read by the GPU temperature chart
+    #[cfg_attr(not(feature = "gpu"), allow(dead_code))]
+    pub(crate) gpu_temp_warning: f64,
+}
+
+impl<'a> PanelData<'a> {
+    pub(crate) fn new(
+        columns: &'a TraceColumns,
+        min: usize,
+        max: usize,
+        palette: &'a Palette,
+        smoothing: usize,
+        gpu_temp_warning: f64,
+    ) -> Self {
+        let micros = &columns.micros;
+        let snapshot_times: Vec<Option<f64>> = micros
+            .iter()
+            .map(|micros| micros.map(|micros| micros / 1e3))
+            .collect();
+        // The time axis spans the whole trace so the zoom slider shows where
+        // the culled window sits
+        let timed = snapshot_times.iter().flatten();
+        let trace_start = timed.clone().copied().reduce(f64::min);
+        let trace_end = timed.copied().reduce(f64::max);
+        PanelData {
+            columns,
+            min,
+            max,
+            micros,
+            time_axis: Axis::time(trace_start, trace_end),
+            snapshot_times,
+            palette,
+            smoothing,
+            gpu_temp_warning,
+        }
+    }
+
+    /// Indices of the snapshots in the selected range that aren't gaps in
+    /// the recording.
+    pub(crate) fn recorded(&self) -> impl Iterator<Item = usize> + 'a {
+        self.columns
+            .gaps
+            .iter()
+            .enumerate()
+            .skip(self.min)
+            .take(self.max + 1 - self.min)
+            .filter(|&(_, &gap)| !gap)
+            .map(|(index, _)| index)
+    }
+
+    /// Samples of `metric` in the selected range, by process row.
+    pub(crate) fn process_series<T: Sample>(
+        &self,
+        metric: impl Fn(&ProcessColumns) -> &Column<T>,
+    ) -> BTreeMap<RowId, Vec<(usize, f64)>> {
+        self.columns.process_series(self.min..=self.max, metric)
+    }
+
+    /// Samples of `metric` in the selected range, by thread row.
+    pub(crate) fn thread_series<T: Sample>(
+        &self,
+        metric: impl Fn(&ThreadColumns) -> &Column<T>,
+    ) -> BTreeMap<RowId, Vec<(usize, f64)>> {
+        self.columns.thread_series(self.min..=self.max, metric)
+    }
+
+    /// Samples of host-wide metrics in the selected range, each named
+    /// series prefixed by the host it was sampled on.
+    pub(crate) fn host_series(
+        &self,
+        metrics: &[(&str, &Column)],
+    ) -> IndexMap<String, Vec<(usize, f64)>> {
+        let mut series: IndexMap<String, Vec<(usize, f64)>> = IndexMap::new();
+        for index in self.min..=self.max {
+            let prefix = self.columns.rows.prefix(self.columns.hosts[index]);
+            for (name, column) in metrics {
+                if let Some(value) = column.at(index) {
+                    series
+                        .entry(format!("{prefix}{name}"))
+                        .or_default()
+                        .push((index, value));
+                }
+            }
+        }
+        series
+    }
+
+    /// Label of a process or thread row, which names its series.
+    pub(crate) fn label(&self, row: RowId) -> &'a str {
+        self.columns.rows.label(row)
+    }
+
+    /// A line chart on the time axis, the layout most panels share.
+    pub(crate) fn line_chart(
+        &self,
+        title: &str,
+        legend: Option<Legend>,
+        y_axis: Axis,
+        series: Vec<Series>,
+    ) -> ChartOption {
+        ChartOption {
+            title: Some(Title {
+                text: title.to_string(),
+                ..Title::default()
+            }),
+            tooltip: Some(Tooltip::axis()),
+            legend,
+            x_axis: vec![self.time_axis.clone()],
+            y_axis: vec![y_axis],
+            series,
+            ..ChartOption::default()
+        }
+    }
+}
+
+/// Optional parts of a snapshot. A panel needing one the trace never
+/// recorded is left out rather than drawn empty.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TraceField {
+    #[cfg(feature = "gpu")]
+    GpuStatus,
+    LoadAverage,
+    SystemMemory,
+    Swap,
+}
+
+impl TraceField {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "gpu")]
+            TraceField::GpuStatus => "GPU",
+            TraceField::LoadAverage => "load average",
+            TraceField::SystemMemory => "system memory",
+            TraceField::Swap => "swap",
+        }
+    }
+
+    fn recorded_in(self, columns: &TraceColumns) -> bool {
+        let system = &columns.system;
+        match self {
+            #[cfg(feature = "gpu")]
+            TraceField::GpuStatus => !columns.gpus.is_empty(),
+            TraceField::LoadAverage => !system.load_avg_1.is_empty(),
+            TraceField::SystemMemory => !system.memory_used_mb.is_empty(),
+            TraceField::Swap => !system.swap_used_mb.is_empty() || !system.swap_total_mb.is_empty(),
+        }
+    }
+}
+
+/// A line chart below the timeline.
+pub(crate) trait Panel {
+    /// Id of the chart div, also the key of the chart layout
+    fn id(&self) -> &'static str;
+    /// Name in the chart settings
+    fn name(&self) -> &'static str;
+    fn required_fields(&self) -> &'static [TraceField] {
+        &[]
+    }
+    fn build_series(&self, data: &PanelData) -> Vec<Series>;
+    /// The chart of the built series, or None for a panel drawn elsewhere.
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption>;
+
+    /// The required fields missing from every snapshot of the trace.
+    fn missing_fields(&self, columns: &TraceColumns) -> Vec<TraceField> {
+        self.required_fields()
+            .iter()
+            .copied()
+            .filter(|field| !field.recorded_in(columns))
+            .collect()
+    }
+}
+
+/// Every line chart, in their default order. The GPU charts are only built
+/// with the `gpu` feature.
+pub(crate) const PANELS: &[&dyn Panel] = &[
+    #[cfg(feature = "gpu")]
+    &gpu::GpuLoad,
+    #[cfg(feature = "gpu")]
+    &gpu::GpuMemory,
+    #[cfg(feature = "gpu")]
+    &gpu::GpuPower,
+    #[cfg(feature = "gpu")]
+    &gpu::GpuTemperature,
+    #[cfg(feature = "gpu")]
+    &gpu::GpuClocks,
+    #[cfg(feature = "gpu")]
+    &gpu::ProcessGpuMemory,
+    &cpu::CpuLoad,
+    &cpu::ThreadStates,
+    &cpu::LoadAverage,
+    &memory::SystemMemory,
+    &memory::Swap,
+    &cpu::ProcessCpu,
+    &memory::ProcessMemory,
+    &process::ProcessIo,
+    &process::FileDescriptors,
+    &process::ContextSwitches,
+];
+
+pub(crate) fn panel(id: &str) -> Option<&'static dyn Panel> {
+    PANELS.iter().copied().find(|panel| panel.id() == id)
+}
@@ -0,0 +1,163 @@
+use super::{Panel, PanelData};
+use timeline_core::echarts::{
+    Axis, ChartOption, Grid, ItemStyle, Legend, Length, LineStyle, Series, TextStyle, Title,
+};
+use timeline_core::series::{counter_rates, dashed_line_series, grows_monotonically, line_series};
+
+/// Name suffix of the file descriptor series of likely leaks.
+const GROWING_SUFFIX: &str = " (growing)";
+
+pub(super) struct ProcessIo;
+
+impl Panel for ProcessIo {
+    fn id(&self) -> &'static str {
+        "process-io-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "I/O per process"
+    }
+
+    /// Throughput from the cumulative byte counters.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let process_io_read = data.process_series(|proc| &proc.io_read_bytes);
+        let process_io_write = data.process_series(|proc| &proc.io_write_bytes);
+        let to_mb_per_sec = |points: &Vec<(usize, f64)>| -> Vec<(usize, f64)> {
+            counter_rates(points, data.micros)
+                .into_iter()
+                .map(|(t, rate)| (t, rate / (1024.0 * 1024.0)))
+                .collect()
+        };
+        process_io_read
+            .iter()
+            .map(|(&row, points)| {
+                let label = data.label(row);
+                line_series(label, " read", &to_mb_per_sec(points), &data.snapshot_times)
+            })
+            .chain(process_io_write.iter().map(|(&row, points)| {
+                line_series(
+                    data.label(row),
+                    " write",
+                    &to_mb_per_sec(points),
+                    &data.snapshot_times,
+                )
+            }))
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "Process I/O Throughput (MB/s)",
+            Some(Legend::scrolling(20.0)),
+            Axis::from_zero("MB/s"),
+            series,
+        ))
+    }
+}
+
+pub(super) struct FileDescriptors;
+
+impl Panel for FileDescriptors {
+    fn id(&self) -> &'static str {
+        "process-fd-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "File descriptors"
+    }
+
+    /// Open descriptors per process; those whose count only ever grows are
+    /// highlighted as likely leaks.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let process_fds = data.process_series(|proc| &proc.fd_count);
+        process_fds
+            .iter()
+            .map(|(&row, points)| {
+                let label = data.label(row);
+                if grows_monotonically(points) {
+                    Series {
+                        line_style: Some(LineStyle {
+                            color: Some("#d73027".to_string()),
+                            width: Some(3.0),
+                            kind: None,
+                        }),
+                        item_style: Some(ItemStyle::color("#d73027")),
+                        ..line_series(label, GROWING_SUFFIX, points, &data.snapshot_times)
+                    }
+                } else {
+                    line_series(label, "", points, &data.snapshot_times)
+                }
+            })
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        let fd_leaks = series
+            .iter()
+            .filter(|series| series.name.ends_with(GROWING_SUFFIX))
+            .count();
+        let fd_subtext = if fd_leaks > 0 {
+            format!("{fd_leaks} process(es) with a steadily growing count: possible fd leak")
+        } else {
+            String::new()
+        };
+        Some(ChartOption {
+            title: Some(Title {
+                text: "Open File Descriptors".to_string(),
+                subtext: Some(fd_subtext),
+                subtext_style: Some(TextStyle {
+                    color: "#d73027".to_string(),
+                }),
+            }),
+            grid: Some(Grid {
+                top: Some(Length::Px(80.0)),
+                ..Grid::default()
+            }),
+            ..data.line_chart(
+                "",
+                Some(Legend::scrolling(40.0)),
+                Axis::from_zero(""),
+                series,
+            )
+        })
+    }
+}
+
+pub(super) struct ContextSwitches;
+
+impl Panel for ContextSwitches {
+    fn id(&self) -> &'static str {
+        "ctxt-switch-line"
+    }
+
+    fn name(&self) -> &'static str {
+        "Context switches"
+    }
+
+    /// Thread context switch rates; involuntary switches are dashed.
+    fn build_series(&self, data: &PanelData) -> Vec<Series> {
+        let voluntary_switches = data.thread_series(|t| &t.voluntary_ctxt_switches);
+        let involuntary_switches = data.thread_series(|t| &t.nonvoluntary_ctxt_switches);
+        voluntary_switches
+            .iter()
+            .map(|(&row, points)| {
+                let rates = counter_rates(points, data.micros);
+                line_series(data.label(row), " voluntary", &rates, &data.snapshot_times)
+            })
+            .chain(involuntary_switches.iter().map(|(&row, points)| {
+                let rates = counter_rates(points, data.micros);
+                let label = data.label(row);
+                dashed_line_series(label, " involuntary", &rates, &data.snapshot_times)
+            }))
+            .collect()
+    }
+
+    fn render(&self, data: &PanelData, series: Vec<Series>) -> Option<ChartOption> {
+        Some(data.line_chart(
+            "Thread Context Switches (/s)",
+            Some(Legend::scrolling(20.0)),
+            Axis::from_zero("/s"),
+            series,
+        ))
+    }
+}
@@ -0,0 +1,230 @@
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use wasm_bindgen::JsValue;
+
+/// Initial number of heatmap rows rendered at once; larger traces are paged.
+pub(crate) const DEFAULT_ROW_WINDOW: usize = 500;
+
+/// Sizing of the labelled rows shared by the heatmap, core heatmap and
+/// process lifetime charts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RowLayout {
+    /// Pixels per row
+    pub(crate) row_height: usize,
+    /// Row label font size in pixels
+    pub(crate) font_size: usize,
+    /// Width in pixels of the label gutter left of the plot
+    pub(crate) gutter: usize,
+}
+
+impl Default for RowLayout {
+    fn default() -> Self {
+        RowLayout {
+            row_height: 14,
+            font_size: 12,
+            gutter: 300,
+        }
+    }
+}
+
+/// What the process rows of the heatmap encode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum HeatmapColor {
+    ThreadState,
+    /// Process RSS relative to the peak in view; thread rows are left empty
+    Memory,
+    /// Thread CPU consumed since the previous snapshot, which unlike the
+    /// instantaneous state also catches short bursts
+    CpuTime,
+}
+
+/// Page and chart color scheme, remembered in localStorage.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Theme {
+    Light,
+    Dark,
+}
+
+pub(crate) const THEME_STORAGE_KEY: &str = "timeline-viewer-theme";
+
+impl Theme {
+    pub(crate) fn load() -> Self {
+        match LocalStorage::get::<String>(THEME_STORAGE_KEY).as_deref() {
+            Ok("dark") => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    /// ECharts theme argument; light uses the library default.
+    pub(crate) fn chart_theme(self) -> JsValue {
+        match self {
+            Theme::Light => JsValue::NULL,
+            Theme::Dark => JsValue::from_str("dark"),
+        }
+    }
+
+    /// Heatmap colors of the thread state values 0 (unknown) to 6, i.e.
+    /// unknown, R, S, Z, T, D and I.
+    fn state_colors(self) -> [&'static str; 7] {
+        match self {
+            Theme::Light => [
+                "#ffffff", "#008000", "#ffa500", "#ff0000", "#808080", "#800080", "#d9d9d9",
+            ],
+            Theme::Dark => [
+                "#100c2a", "#4caf50", "#ffb74d", "#ef5350", "#9e9e9e", "#ba68c8", "#424242",
+            ],
+        }
+    }
+}
+
+pub(crate) const PALETTE_STORAGE_KEY: &str = "timeline-viewer-palette";
+
+pub(crate) const VIEW_PRESETS_STORAGE_KEY: &str = "timeline-viewer-presets";
+
+/// A named combination of row filters, sort order, visible charts and colors,
+/// saved in localStorage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ViewPreset {
+    pub(crate) row_filter: String,
+    pub(crate) row_filter_regex: bool,
+    pub(crate) row_filter_invert: bool,
+    pub(crate) hide_idle_threads: bool,
+    pub(crate) processes_only: bool,
+    pub(crate) running_thread_rows: bool,
+    pub(crate) row_sort: RowSort,
+    pub(crate) timeline_view: TimelineView,
+    pub(crate) hidden_charts: BTreeSet<String>,
+    pub(crate) heatmap_color: HeatmapColor,
+    pub(crate) theme: Theme,
+    pub(crate) custom_palette: Option<Palette>,
+    pub(crate) smoothing: usize,
+}
+
+pub(crate) fn load_view_presets() -> BTreeMap<String, ViewPreset> {
+    LocalStorage::get(VIEW_PRESETS_STORAGE_KEY).unwrap_or_default()
+}
+
+/// Prefix of the URL fragment holding the encoded `ViewState`.
+const VIEW_FRAGMENT_PREFIX: &str = "#view=";
+
+/// What is being looked at, kept in the URL fragment so a shared link opens
+/// the same trace at the same place.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ViewState {
+    pub(crate) min_time: usize,
+    pub(crate) max_time: usize,
+    pub(crate) row_filter: String,
+    pub(crate) row_filter_regex: bool,
+    pub(crate) row_filter_invert: bool,
+    pub(crate) hide_idle_threads: bool,
+    pub(crate) processes_only: bool,
+    pub(crate) collapsed_rows: Vec<String>,
+    pub(crate) theme: Option<Theme>,
+}
+
+impl ViewState {
+    pub(crate) fn from_fragment() -> Option<Self> {
+        let hash = gloo::utils::window().location().hash().ok()?;
+        let encoded = hash.strip_prefix(VIEW_FRAGMENT_PREFIX)?;
+        let json = js_sys::decode_uri_component(encoded).ok()?.as_string()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    /// Replace the URL fragment without adding a history entry.
+    pub(crate) fn save_to_fragment(&self) {
+        let Ok(json) = serde_json::to_string(self) else {
+            return;
+        };
+        let location = gloo::utils::window().location();
+        let url = format!(
+            "{}{}{VIEW_FRAGMENT_PREFIX}{}",
+            location.pathname().unwrap_or_default(),
+            location.search().unwrap_or_default(),
+            String::from(js_sys::encode_uri_component(&json))
+        );
+        let _ = gloo::utils::history().replace_state_with_url(&JsValue::NULL, "", Some(&url));
+    }
+}
+
+/// Prefix of the localStorage keys holding each trace's bookmarks, by file name.
+const BOOKMARKS_STORAGE_PREFIX: &str = "timeline-viewer-bookmarks:";
+
+/// A named point in a trace added by the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Bookmark {
+    pub(crate) name: String,
+    /// Timestamp of the bookmarked snapshot, as written in the trace
+    pub(crate) timestamp: String,
+}
+
+pub(crate) fn load_bookmarks(trace_name: &str) -> Vec<Bookmark> {
+    LocalStorage::get(format!("{BOOKMARKS_STORAGE_PREFIX}{trace_name}")).unwrap_or_default()
+}
+
+pub(crate) fn save_bookmarks(trace_name: &str, bookmarks: &[Bookmark]) {
+    let key = format!("{BOOKMARKS_STORAGE_PREFIX}{trace_name}");
+    if bookmarks.is_empty() {
+        LocalStorage::delete(key);
+    } else {
+        let _ = LocalStorage::set(key, bookmarks);
+    }
+}
+
+/// User-chosen heatmap colors, as `#rrggbb` so they can back color inputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Palette {
+    pub(crate) states: [String; 7],
+    /// GPU load buckets, also the core heatmap gradient
+    pub(crate) gpu: [String; 5],
+}
+
+impl Palette {
+    pub(crate) fn defaults(theme: Theme) -> Self {
+        Palette {
+            states: theme.state_colors().map(String::from),
+            gpu: ["#e0f3f8", "#abd9e9", "#74add1", "#4575b4", "#313695"].map(String::from),
+        }
+    }
+
+    /// Okabe-Ito state colors and the viridis GPU ramp, which stay
+    /// distinguishable under the common forms of color blindness.
+    pub(crate) fn color_blind_safe(theme: Theme) -> Self {
+        let unknown = theme.state_colors()[0];
+        Palette {
+            states: [
+                unknown, "#009e73", "#e69f00", "#d55e00", "#999999", "#cc79a7", "#56b4e9",
+            ]
+            .map(String::from),
+            gpu: ["#fde725", "#5ec962", "#21918c", "#3b528b", "#440154"].map(String::from),
+        }
+    }
+}
+
+/// Which chart occupies the main timeline slot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum TimelineView {
+    Heatmap,
+    /// One bar per process from its first to last appearance
+    Gantt,
+}
+
+/// Order of sibling rows in the heatmap; the hierarchy is always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum RowSort {
+    /// Order of first appearance in the trace
+    Tree,
+    /// Most running thread samples in the selected range first
+    RunningTime,
+    Name,
+    Pid,
+}
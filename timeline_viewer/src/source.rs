@@ -0,0 +1,429 @@
+use crate::components::report_error;
+use crate::worker::{spawn_parser, ParseInput, ParseWorker};
+use futures::channel::mpsc;
+use futures::future::ready;
+use futures::stream::{self, LocalBoxStream};
+use futures::StreamExt;
+use gloo::net::eventsource::futures::EventSource;
+use gloo::net::http::Request;
+use gloo::net::websocket::futures::WebSocket;
+use gloo::net::websocket::Message;
+use gloo::worker::oneshot::OneshotBridge;
+use gloo_file::futures::read_as_bytes;
+use gloo_file::File;
+use serde::{Deserialize, Serialize};
+use timeline_core::model::Snapshot;
+use timeline_core::parse::{
+    is_json_document, is_pidstat_report, parse_msgpack_snapshots, parse_snapshot_part, EventCarry,
+    ParseError, ParsedPart, TraceFormat, GZIP_MAGIC, ZSTD_MAGIC,
+};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    FileList, FileSystemFileHandle, IdbDatabase, IdbRequest, IdbTransactionMode, UrlSearchParams,
+};
+
+pub(crate) fn file_list_to_vec(files: &FileList) -> Vec<File> {
+    (0..files.length())
+        .filter_map(|i| files.get(i))
+        .map(File::from)
+        .collect()
+}
+
+pub(crate) fn src_query_param() -> Option<String> {
+    let search = gloo::utils::window().location().search().ok()?;
+    let params = UrlSearchParams::new_with_str(&search).ok()?;
+    params.get("src").filter(|src| !src.is_empty())
+}
+
+/// Snapshots parsed from part of a source, with the parse errors met there.
+/// Events leading the part belong to the last snapshot of the batches before.
+pub(crate) struct SnapshotBatch {
+    pub(crate) part: ParsedPart,
+    /// Bytes of the source consumed so far
+    pub(crate) bytes_read: u64,
+}
+
+/// Batches as they are parsed; an error ends the stream.
+pub(crate) type SnapshotStream = LocalBoxStream<'static, Result<SnapshotBatch, String>>;
+
+/// Somewhere snapshots are read from. Loading only deals with the stream of
+/// batches, so a new kind of source needs no changes to the viewer.
+pub(crate) trait SnapshotSource {
+    /// Name of the trace, also given as the source of its parse errors
+    fn name(&self) -> String;
+    /// Bytes to read in all, for the progress bar; 0 when not known up front
+    fn size(&self) -> u64 {
+        0
+    }
+    fn open(self: Box<Self>) -> SnapshotStream;
+}
+
+/// Read a source to the end, calling `on_progress` with the bytes read so far.
+pub(crate) async fn read_all(
+    source: Box<dyn SnapshotSource>,
+    on_progress: impl Fn(u64),
+) -> Result<(Vec<Snapshot>, Vec<ParseError>), String> {
+    let mut batches = source.open();
+    let mut snapshots: Vec<Snapshot> = Vec::new();
+    let mut errors = Vec::new();
+    let mut carry = EventCarry::default();
+    while let Some(batch) = batches.next().await {
+        let SnapshotBatch {
+            mut part,
+            bytes_read,
+        } = batch?;
+        let events = carry.carry(&mut part);
+        if let Some(last) = snapshots.last_mut() {
+            last.Events.extend(events);
+        }
+        snapshots.extend(part.snapshots);
+        errors.extend(part.errors);
+        on_progress(bytes_read);
+    }
+    errors.extend(carry.finish());
+    Ok((snapshots, errors))
+}
+
+/// A trace file picked, dropped or reloaded from disk.
+pub(crate) struct FileSource(pub(crate) File);
+
+impl SnapshotSource for FileSource {
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    fn size(&self) -> u64 {
+        self.0.size()
+    }
+
+    fn open(self: Box<Self>) -> SnapshotStream {
+        let (batches, stream) = mpsc::unbounded();
+        spawn_local(async move {
+            let mut parser = spawn_parser();
+            let on_batch = |batch| {
+                let _ = batches.unbounded_send(Ok(batch));
+            };
+            if let Err(e) = read_snapshots_chunked(&self.0, &mut parser, on_batch).await {
+                let _ = batches.unbounded_send(Err(e));
+            }
+        });
+        stream.boxed_local()
+    }
+}
+
+/// A trace hosted at a URL, fetched whole.
+pub(crate) struct UrlSource(pub(crate) String);
+
+impl SnapshotSource for UrlSource {
+    fn name(&self) -> String {
+        self.0.clone()
+    }
+
+    fn open(self: Box<Self>) -> SnapshotStream {
+        let url = self.0;
+        stream::once(async move {
+            let bytes = fetch_bytes(&url).await.map_err(|e| e.to_string())?;
+            let bytes_read = bytes.len() as u64;
+            let path = url.split(['?', '#']).next().unwrap_or(&url);
+            let format = TraceFormat::from_file_name(path);
+            let mut part = spawn_parser().run(ParseInput::Trace(format, bytes)).await;
+            for error in &mut part.errors {
+                error.source = url.clone();
+            }
+            Ok(SnapshotBatch { part, bytes_read })
+        })
+        .boxed_local()
+    }
+}
+
+/// Snapshots streamed by a running collector over a WebSocket or Server-Sent
+/// Events, as JSONL. The stream never ends on its own.
+pub(crate) struct LiveSource(pub(crate) String);
+
+impl SnapshotSource for LiveSource {
+    fn name(&self) -> String {
+        self.0.clone()
+    }
+
+    fn open(self: Box<Self>) -> SnapshotStream {
+        let lines = match open_live_stream(&self.0) {
+            Ok(lines) => lines,
+            Err(e) => return stream::once(ready(Err(e))).boxed_local(),
+        };
+        let mut bytes_read = 0;
+        lines
+            .map(move |lines| {
+                bytes_read += lines.len() as u64;
+                // A message may hold only events, for the snapshot of an earlier one
+                let mut part = parse_snapshot_part(&lines);
+                let leading_errors = part.leading_events.iter_mut().map(|(_, error)| error);
+                for error in part.errors.iter_mut().chain(leading_errors) {
+                    error.source = self.0.clone();
+                }
+                Ok(SnapshotBatch { part, bytes_read })
+            })
+            .boxed_local()
+    }
+}
+
+/// Slice size used when streaming uncompressed traces from disk.
+const READ_CHUNK_BYTES: u64 = 8 * 1024 * 1024;
+/// Bytes inspected at the start of a file to detect its format.
+const HEAD_PEEK_BYTES: u64 = 256;
+
+/// Read a trace file in `READ_CHUNK_BYTES` slices, parsing complete lines as
+/// each slice arrives so large files never have to be held as one `String`.
+/// Each slice's snapshots are passed to `on_batch`, with parse errors given
+/// line numbers relative to the whole file.
+async fn read_snapshots_chunked(
+    file: &File,
+    parser: &mut OneshotBridge<ParseWorker>,
+    mut on_batch: impl FnMut(SnapshotBatch),
+) -> Result<(), String> {
+    let size = file.size();
+    let format = TraceFormat::from_file_name(&file.name());
+    let head = read_as_bytes(&file.slice(0, size.min(HEAD_PEEK_BYTES)))
+        .await
+        .map_err(|e| e.to_string())?;
+    if format != TraceFormat::Json
+        || head.starts_with(&GZIP_MAGIC)
+        || head.starts_with(&ZSTD_MAGIC)
+        || is_json_document(&head)
+        || is_pidstat_report(&head)
+    {
+        // Binary formats, compressed streams and whole documents can't be split on
+        // line boundaries, so decode in one go
+        let bytes = read_as_bytes(file).await.map_err(|e| e.to_string())?;
+        let mut part = parser.run(ParseInput::Trace(format, bytes)).await;
+        for error in &mut part.errors {
+            error.source = file.name();
+        }
+        on_batch(SnapshotBatch {
+            part,
+            bytes_read: size,
+        });
+        return Ok(());
+    }
+
+    let mut lines_before = 0;
+    let mut parse_lines = async |lines: Vec<u8>, bytes_read: u64| {
+        let line_count = lines.iter().filter(|&&b| b == b'\n').count();
+        let mut part = parser.run(ParseInput::Lines(lines)).await;
+        let leading_errors = part.leading_events.iter_mut().map(|(_, error)| error);
+        for error in part.errors.iter_mut().chain(leading_errors) {
+            error.source = file.name();
+            error.line += lines_before;
+        }
+        on_batch(SnapshotBatch { part, bytes_read });
+        lines_before += line_count;
+    };
+    let mut pending: Vec<u8> = Vec::new();
+    let mut offset = 0;
+    while offset < size {
+        let end = (offset + READ_CHUNK_BYTES).min(size);
+        let chunk = read_as_bytes(&file.slice(offset, end))
+            .await
+            .map_err(|e| e.to_string())?;
+        pending.extend_from_slice(&chunk);
+        // Only parse up to the last newline; the tail is carried into the next slice
+        if let Some(last_newline) = pending.iter().rposition(|&b| b == b'\n') {
+            let tail = pending.split_off(last_newline + 1);
+            let lines = std::mem::replace(&mut pending, tail);
+            parse_lines(lines, end).await;
+        }
+        offset = end;
+    }
+    if !pending.is_empty() {
+        parse_lines(pending, size).await;
+    }
+    Ok(())
+}
+
+/// Polling period for auto-reloading files opened with the File System Access API.
+pub(crate) const AUTO_RELOAD_INTERVAL_MS: u32 = 2000;
+
+/// Show the File System Access API picker. Unlike `<input type="file">`, the
+/// returned handles can be re-read after the file changes on disk. Browsers
+/// without the API (Firefox, Safari) return an error.
+pub(crate) async fn pick_file_handles() -> Result<Vec<FileSystemFileHandle>, JsValue> {
+    let window = gloo::utils::window();
+    let picker: js_sys::Function = js_sys::Reflect::get(&window, &"showOpenFilePicker".into())?
+        .dyn_into()
+        .map_err(|_| JsValue::from_str("File System Access API is not supported"))?;
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"multiple".into(), &true.into())?;
+    let handles = JsFuture::from(js_sys::Promise::from(picker.call1(&window, &options)?)).await?;
+    Ok(js_sys::Array::from(&handles)
+        .iter()
+        .map(JsCast::unchecked_into)
+        .collect())
+}
+
+pub(crate) async fn read_file_handles(
+    handles: &[FileSystemFileHandle],
+) -> Result<Vec<web_sys::File>, JsValue> {
+    let mut files = Vec::new();
+    for handle in handles {
+        files.push(JsFuture::from(handle.get_file()).await?.unchecked_into());
+    }
+    Ok(files)
+}
+
+/// IndexedDB database caching recently loaded traces.
+const RECENT_DB_NAME: &str = "timeline-viewer";
+const RECENT_DB_VERSION: u32 = 1;
+/// Object store of `RecentTrace` metadata (JSON strings), keyed by name.
+const RECENT_STORE: &str = "recent";
+/// Object store of MessagePack-encoded snapshots, keyed by name.
+const RECENT_DATA_STORE: &str = "trace_data";
+const RECENT_TRACES_MAX: usize = 5;
+
+/// A cached trace listed under "Recent traces".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecentTrace {
+    pub(crate) name: String,
+    /// Milliseconds since the epoch
+    pub(crate) saved_at: f64,
+    pub(crate) snapshot_count: usize,
+}
+
+/// Resolve an IndexedDB request once its `success` or `error` event fires.
+async fn idb_request(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let req = request.clone();
+        let on_success = Closure::once_into_js(move |_: web_sys::Event| {
+            let _ = resolve.call1(&JsValue::NULL, &req.result().unwrap_or_default());
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        let req = request.clone();
+        let on_error = Closure::once_into_js(move |_: web_sys::Event| {
+            let error = req.error().ok().flatten().map(JsValue::from);
+            let _ = reject.call1(&JsValue::NULL, &error.unwrap_or_default());
+        });
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
+
+async fn open_recent_db() -> Result<IdbDatabase, JsValue> {
+    let factory = gloo::utils::window()
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB is not available"))?;
+    let request = factory.open_with_u32(RECENT_DB_NAME, RECENT_DB_VERSION)?;
+    let req = request.clone();
+    let on_upgrade = Closure::once_into_js(move |_: web_sys::Event| {
+        if let Ok(db) = req.result().and_then(JsCast::dyn_into::<IdbDatabase>) {
+            let _ = db.create_object_store(RECENT_STORE);
+            let _ = db.create_object_store(RECENT_DATA_STORE);
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+    Ok(idb_request(&request).await?.unchecked_into())
+}
+
+/// Recently cached traces, newest first.
+pub(crate) async fn list_recent_traces() -> Result<Vec<RecentTrace>, JsValue> {
+    let db = open_recent_db().await?;
+    let store = db
+        .transaction_with_str(RECENT_STORE)?
+        .object_store(RECENT_STORE)?;
+    let entries = idb_request(&store.get_all()?).await?;
+    let mut recent: Vec<RecentTrace> = js_sys::Array::from(&entries)
+        .iter()
+        .filter_map(|entry| serde_json::from_str(&entry.as_string()?).ok())
+        .collect();
+    recent.sort_by(|a, b| b.saved_at.total_cmp(&a.saved_at));
+    Ok(recent)
+}
+
+/// Cache a trace under `name`, evicting the oldest entries beyond `RECENT_TRACES_MAX`.
+pub(crate) async fn save_recent_trace(name: &str, snapshots: &[Snapshot]) -> Result<(), JsValue> {
+    let data = rmp_serde::to_vec_named(snapshots).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    let entry = RecentTrace {
+        name: name.to_string(),
+        saved_at: js_sys::Date::now(),
+        snapshot_count: snapshots.len(),
+    };
+    let evicted: Vec<String> = list_recent_traces()
+        .await?
+        .into_iter()
+        .filter(|recent| recent.name != name)
+        .skip(RECENT_TRACES_MAX - 1)
+        .map(|recent| recent.name)
+        .collect();
+
+    let db = open_recent_db().await?;
+    let stores = js_sys::Array::of2(&RECENT_STORE.into(), &RECENT_DATA_STORE.into());
+    let transaction =
+        db.transaction_with_str_sequence_and_mode(&stores, IdbTransactionMode::Readwrite)?;
+    let recent = transaction.object_store(RECENT_STORE)?;
+    let trace_data = transaction.object_store(RECENT_DATA_STORE)?;
+    let metadata = serde_json::to_string(&entry).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    recent.put_with_key(&metadata.into(), &name.into())?;
+    trace_data.put_with_key(&js_sys::Uint8Array::from(data.as_slice()), &name.into())?;
+    for name in evicted {
+        recent.delete(&name.as_str().into())?;
+        trace_data.delete(&name.as_str().into())?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn load_recent_trace(name: &str) -> Result<Vec<Snapshot>, JsValue> {
+    let db = open_recent_db().await?;
+    let store = db
+        .transaction_with_str(RECENT_DATA_STORE)?
+        .object_store(RECENT_DATA_STORE)?;
+    let data = idb_request(&store.get(&name.into())?).await?;
+    Ok(parse_msgpack_snapshots(
+        &js_sys::Uint8Array::new(&data).to_vec(),
+    ))
+}
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>, gloo::net::Error> {
+    let response = Request::get(url).send().await?;
+    if !response.ok() {
+        return Err(gloo::net::Error::GlooError(format!(
+            "HTTP {} {}",
+            response.status(),
+            response.status_text()
+        )));
+    }
+    response.binary().await
+}
+
+/// Open a live snapshot stream: a WebSocket for `ws://`/`wss://` URLs, otherwise a
+/// Server-Sent Events subscription. Each item holds one or more JSONL lines.
+fn open_live_stream(url: &str) -> Result<LocalBoxStream<'static, String>, String> {
+    if url.starts_with("ws://") || url.starts_with("wss://") {
+        let ws = WebSocket::open(url).map_err(|e| e.to_string())?;
+        let lines = ws.filter_map(|message| {
+            ready(match message {
+                Ok(Message::Text(text)) => Some(text),
+                Ok(Message::Bytes(bytes)) => Some(String::from_utf8_lossy(&bytes).into_owned()),
+                Err(e) => {
+                    report_error(format!("Live stream error: {}", e));
+                    None
+                }
+            })
+        });
+        Ok(lines.boxed_local())
+    } else {
+        let mut source = EventSource::new(url).map_err(|e| e.to_string())?;
+        let events = source.subscribe("message").map_err(|e| e.to_string())?;
+        let lines = events.filter_map(move |event| {
+            // The EventSource closes when dropped, so the stream keeps it alive
+            let _source = &source;
+            ready(match event {
+                Ok((_, message)) => message.data().as_string(),
+                // The browser retries dropped SSE connections on its own
+                Err(e) => {
+                    report_error(format!("Live stream error: {}", e));
+                    None
+                }
+            })
+        });
+        Ok(lines.boxed_local())
+    }
+}
@@ -0,0 +1,368 @@
+use crate::charts::{ChartLayout, DEFAULT_GPU_TEMP_WARNING_C, SMOOTHING_WINDOWS};
+use crate::settings::{
+    HeatmapColor, Palette, RowLayout, RowSort, Theme, TimelineView, ViewPreset, ViewState,
+    DEFAULT_ROW_WINDOW, PALETTE_STORAGE_KEY,
+};
+use gloo::storage::{LocalStorage, Storage};
+use std::cell::{Ref, RefCell};
+use std::collections::{BTreeSet, HashSet};
+use std::rc::Rc;
+use timeline_core::columns::TraceColumns;
+use timeline_core::model::{Snapshot, TimelineEvent};
+use yew::prelude::*;
+
+/// Which heatmap rows are shown and in what order.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RowOptions {
+    pub(crate) row_filter: String,
+    pub(crate) row_filter_regex: bool,
+    pub(crate) row_filter_invert: bool,
+    pub(crate) hide_idle_threads: bool,
+    pub(crate) processes_only: bool,
+    pub(crate) running_thread_rows: bool,
+    pub(crate) row_sort: RowSort,
+    /// Window of rows rendered, so huge traces stay responsive
+    pub(crate) row_window: usize,
+    pub(crate) row_offset: usize,
+}
+
+impl Default for RowOptions {
+    fn default() -> Self {
+        RowOptions {
+            row_filter: String::new(),
+            row_filter_regex: false,
+            row_filter_invert: false,
+            hide_idle_threads: false,
+            processes_only: false,
+            running_thread_rows: false,
+            row_sort: RowSort::Tree,
+            row_window: DEFAULT_ROW_WINDOW,
+            row_offset: 0,
+        }
+    }
+}
+
+/// A loaded trace, as parsed and compacted for the charts. Shared by pointer
+/// and appended to in place in live mode, so streaming never copies it.
+///
+/// Borrows of it must not be held across an await, where an append may land.
+#[derive(Debug, Default)]
+pub(crate) struct Trace {
+    snapshots: RefCell<Vec<Snapshot>>,
+    columns: RefCell<TraceColumns>,
+}
+
+impl Trace {
+    pub(crate) fn new(snapshots: Vec<Snapshot>) -> Self {
+        Trace {
+            columns: RefCell::new(TraceColumns::new(&snapshots)),
+            snapshots: RefCell::new(snapshots),
+        }
+    }
+
+    /// The trace as parsed, read by the views of single snapshots and exports.
+    pub(crate) fn snapshots(&self) -> Ref<'_, Vec<Snapshot>> {
+        self.snapshots.borrow()
+    }
+
+    /// The trace compacted, read by the charts.
+    pub(crate) fn columns(&self) -> Ref<'_, TraceColumns> {
+        self.columns.borrow()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.snapshots.borrow().len()
+    }
+
+    /// Appends streamed snapshots, with events streamed on their own going to
+    /// the newest snapshot. False if the trace is unchanged.
+    pub(crate) fn append(&self, events: Vec<TimelineEvent>, batch: Vec<Snapshot>) -> bool {
+        let mut snapshots = self.snapshots.borrow_mut();
+        let mut columns = self.columns.borrow_mut();
+        let attached = match snapshots.last_mut() {
+            Some(last) if !events.is_empty() => {
+                columns.extend_events(&events);
+                last.Events.extend(events);
+                true
+            }
+            _ => false,
+        };
+        let appended = !batch.is_empty();
+        columns.extend(&batch);
+        snapshots.extend(batch);
+        attached || appended
+    }
+}
+
+/// The loaded trace and how it is viewed, shared with the components through
+/// a `ViewerContext`.
+#[derive(Debug, Clone)]
+pub(crate) struct ViewerState {
+    pub(crate) trace: Rc<Trace>,
+    /// Snapshots in the trace as of this state; live mode grows the trace in
+    /// place, so this is what tells the states apart
+    pub(crate) trace_len: usize,
+    /// Bumped for every newly loaded trace, which the charts are drawn afresh for
+    pub(crate) trace_id: u32,
+    /// Selected range of snapshot indices
+    pub(crate) min_time: usize,
+    pub(crate) max_time: usize,
+    pub(crate) rows: RowOptions,
+    /// Collapsed container headings and process rows, by heading key or row label
+    pub(crate) collapsed_rows: HashSet<String>,
+    pub(crate) heatmap_color: HeatmapColor,
+    pub(crate) timeline_view: TimelineView,
+    pub(crate) theme: Theme,
+    /// None follows the theme's default colors
+    pub(crate) custom_palette: Option<Palette>,
+    pub(crate) chart_layout: ChartLayout,
+    pub(crate) row_layout: RowLayout,
+    /// Moving-average window of the GPU and CPU traces, in samples
+    pub(crate) smoothing: usize,
+    pub(crate) gpu_temp_warning: f64,
+}
+
+// Compared on every render of the context provider, so the trace is compared
+// by pointer rather than walked
+impl PartialEq for ViewerState {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.trace, &other.trace)
+            && self.trace_len == other.trace_len
+            && self.trace_id == other.trace_id
+            && self.min_time == other.min_time
+            && self.max_time == other.max_time
+            && self.rows == other.rows
+            && self.collapsed_rows == other.collapsed_rows
+            && self.heatmap_color == other.heatmap_color
+            && self.timeline_view == other.timeline_view
+            && self.theme == other.theme
+            && self.custom_palette == other.custom_palette
+            && self.chart_layout == other.chart_layout
+            && self.row_layout == other.row_layout
+            && self.smoothing == other.smoothing
+            && self.gpu_temp_warning == other.gpu_temp_warning
+    }
+}
+
+impl ViewerState {
+    /// An empty viewer with the settings remembered in localStorage.
+    pub(crate) fn load() -> Self {
+        ViewerState {
+            trace: Rc::default(),
+            trace_len: 0,
+            trace_id: 0,
+            min_time: 0,
+            max_time: 0,
+            rows: RowOptions::default(),
+            collapsed_rows: HashSet::new(),
+            heatmap_color: HeatmapColor::ThreadState,
+            timeline_view: TimelineView::Heatmap,
+            theme: Theme::load(),
+            custom_palette: LocalStorage::get(PALETTE_STORAGE_KEY).ok(),
+            chart_layout: ChartLayout::load(),
+            row_layout: RowLayout::default(),
+            smoothing: SMOOTHING_WINDOWS[0],
+            gpu_temp_warning: DEFAULT_GPU_TEMP_WARNING_C,
+        }
+    }
+
+    pub(crate) fn palette(&self) -> Palette {
+        self.custom_palette
+            .clone()
+            .unwrap_or_else(|| Palette::defaults(self.theme))
+    }
+
+    /// The settings a view preset would save.
+    pub(crate) fn preset(&self) -> ViewPreset {
+        ViewPreset {
+            row_filter: self.rows.row_filter.clone(),
+            row_filter_regex: self.rows.row_filter_regex,
+            row_filter_invert: self.rows.row_filter_invert,
+            hide_idle_threads: self.rows.hide_idle_threads,
+            processes_only: self.rows.processes_only,
+            running_thread_rows: self.rows.running_thread_rows,
+            row_sort: self.rows.row_sort,
+            timeline_view: self.timeline_view,
+            hidden_charts: self.chart_layout.hidden.clone(),
+            heatmap_color: self.heatmap_color,
+            theme: self.theme,
+            custom_palette: self.custom_palette.clone(),
+            smoothing: self.smoothing,
+        }
+    }
+
+    /// What a shared link to the current view holds.
+    pub(crate) fn view(&self) -> ViewState {
+        ViewState {
+            min_time: self.min_time,
+            max_time: self.max_time,
+            row_filter: self.rows.row_filter.clone(),
+            row_filter_regex: self.rows.row_filter_regex,
+            row_filter_invert: self.rows.row_filter_invert,
+            hide_idle_threads: self.rows.hide_idle_threads,
+            processes_only: self.rows.processes_only,
+            collapsed_rows: self
+                .collapsed_rows
+                .iter()
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            theme: Some(self.theme),
+        }
+    }
+}
+
+pub(crate) enum ViewerAction {
+    /// A newly loaded trace, shown whole
+    Load(Rc<Trace>),
+    /// The trace after snapshots were received in live mode, and whether the
+    /// range follows the newest one rather than staying pinned
+    Live(Rc<Trace>, bool),
+    SetTimeRange(usize, usize),
+    /// Center the current range width on a snapshot index
+    CenterOn(usize),
+    SetRowFilter(String),
+    SetRowFilterRegex(bool),
+    SetRowFilterInvert(bool),
+    SetHideIdleThreads(bool),
+    SetProcessesOnly(bool),
+    SetRunningThreadRows(bool),
+    SetRowSort(RowSort),
+    SetRowWindow(usize),
+    SetRowOffset(usize),
+    /// Collapse or expand a container heading key or process row label
+    ToggleRow(String),
+    SetHeatmapColor(HeatmapColor),
+    SetTimelineView(TimelineView),
+    SetTheme(Theme),
+    SetCustomPalette(Option<Palette>),
+    /// Show or hide a line chart by id
+    ShowChart(String, bool),
+    /// Swap the chart at an index with its neighbour `offset` places away
+    MoveChart(usize, isize),
+    SetRowLayout(RowLayout),
+    SetSmoothing(usize),
+    SetGpuTempWarning(f64),
+    ApplyPreset(ViewPreset),
+    /// The view of a shared link, clamped to the loaded trace
+    RestoreView(ViewState),
+}
+
+impl Reducible for ViewerState {
+    type Action = ViewerAction;
+
+    fn reduce(self: Rc<Self>, action: ViewerAction) -> Rc<Self> {
+        let mut state = (*self).clone();
+        let last = state.trace_len.saturating_sub(1);
+        match action {
+            ViewerAction::Load(trace) => {
+                state.trace_len = trace.len();
+                state.min_time = 0;
+                state.max_time = state.trace_len.saturating_sub(1);
+                state.trace = trace;
+                state.trace_id += 1;
+            }
+            ViewerAction::Live(trace, follow) => {
+                state.trace_len = trace.len();
+                if follow {
+                    state.max_time = state.trace_len.saturating_sub(1);
+                }
+                state.trace = trace;
+            }
+            ViewerAction::SetTimeRange(min, max) => {
+                state.min_time = min;
+                state.max_time = max;
+            }
+            ViewerAction::CenterOn(index) => {
+                let width = state.max_time - state.min_time;
+                let start = index
+                    .saturating_sub(width / 2)
+                    .min(last.saturating_sub(width));
+                state.min_time = start;
+                state.max_time = (start + width).min(last);
+            }
+            ViewerAction::SetRowFilter(filter) => state.rows.row_filter = filter,
+            ViewerAction::SetRowFilterRegex(regex) => state.rows.row_filter_regex = regex,
+            ViewerAction::SetRowFilterInvert(invert) => state.rows.row_filter_invert = invert,
+            ViewerAction::SetHideIdleThreads(hide) => state.rows.hide_idle_threads = hide,
+            ViewerAction::SetProcessesOnly(only) => state.rows.processes_only = only,
+            ViewerAction::SetRunningThreadRows(running) => state.rows.running_thread_rows = running,
+            ViewerAction::SetRowSort(sort) => state.rows.row_sort = sort,
+            ViewerAction::SetRowWindow(window) => state.rows.row_window = window,
+            ViewerAction::SetRowOffset(offset) => state.rows.row_offset = offset,
+            ViewerAction::ToggleRow(row) => {
+                if !state.collapsed_rows.remove(&row) {
+                    state.collapsed_rows.insert(row);
+                }
+            }
+            ViewerAction::SetHeatmapColor(color) => state.heatmap_color = color,
+            ViewerAction::SetTimelineView(view) => state.timeline_view = view,
+            ViewerAction::SetTheme(theme) => state.theme = theme,
+            ViewerAction::SetCustomPalette(palette) => state.custom_palette = palette,
+            ViewerAction::ShowChart(id, shown) => {
+                if shown {
+                    state.chart_layout.hidden.remove(&id);
+                } else {
+                    state.chart_layout.hidden.insert(id);
+                }
+            }
+            ViewerAction::MoveChart(index, offset) => {
+                state.chart_layout = state.chart_layout.moved(index, offset);
+            }
+            ViewerAction::SetRowLayout(layout) => state.row_layout = layout,
+            ViewerAction::SetSmoothing(window) => state.smoothing = window,
+            ViewerAction::SetGpuTempWarning(warning) => state.gpu_temp_warning = warning,
+            ViewerAction::ApplyPreset(preset) => {
+                state.rows = RowOptions {
+                    row_filter: preset.row_filter,
+                    row_filter_regex: preset.row_filter_regex,
+                    row_filter_invert: preset.row_filter_invert,
+                    hide_idle_threads: preset.hide_idle_threads,
+                    processes_only: preset.processes_only,
+                    running_thread_rows: preset.running_thread_rows,
+                    row_sort: preset.row_sort,
+                    ..state.rows
+                };
+                state.timeline_view = preset.timeline_view;
+                state.chart_layout.hidden = preset.hidden_charts;
+                state.heatmap_color = preset.heatmap_color;
+                state.theme = preset.theme;
+                state.custom_palette = preset.custom_palette;
+                state.smoothing = preset.smoothing;
+            }
+            ViewerAction::RestoreView(view) => {
+                state.min_time = view.min_time.min(last);
+                state.max_time = view.max_time.clamp(state.min_time, last);
+                state.rows.row_filter = view.row_filter;
+                state.rows.row_filter_regex = view.row_filter_regex;
+                state.rows.row_filter_invert = view.row_filter_invert;
+                state.rows.hide_idle_threads = view.hide_idle_threads;
+                state.rows.processes_only = view.processes_only;
+                state.collapsed_rows = view.collapsed_rows.into_iter().collect();
+                if let Some(theme) = view.theme {
+                    state.theme = theme;
+                }
+            }
+        }
+        Rc::new(state)
+    }
+}
+
+/// Handle to the shared `ViewerState`, provided by `App`.
+pub(crate) type ViewerContext = UseReducerHandle<ViewerState>;
+
+/// Callback dispatching the action made from its argument.
+pub(crate) fn dispatch_with<T: 'static>(
+    viewer: &ViewerContext,
+    action: impl Fn(T) -> ViewerAction + 'static,
+) -> Callback<T> {
+    let viewer = viewer.dispatcher();
+    Callback::from(move |value| viewer.dispatch(action(value)))
+}
+
+/// The shared viewer state; panics outside the `App` that provides it.
+#[hook]
+pub(crate) fn use_viewer() -> ViewerContext {
+    use_context::<ViewerContext>().expect("ViewerContext is provided by App")
+}
@@ -0,0 +1,45 @@
+use gloo::worker::oneshot::{oneshot, OneshotBridge};
+use gloo::worker::Spawnable;
+use serde::{Deserialize, Serialize};
+use timeline_core::parse::{parse_snapshot_part, parse_trace, ParseError, ParsedPart, TraceFormat};
+
+/// Bytes for the parser worker to decode.
+#[derive(Serialize, Deserialize)]
+pub(crate) enum ParseInput {
+    /// A whole file in any format
+    Trace(TraceFormat, Vec<u8>),
+    /// A run of complete JSONL lines of a file read in chunks
+    Lines(Vec<u8>),
+}
+
+/// Web worker that decodes and deserializes trace bytes off the main thread.
+#[oneshot]
+pub(crate) async fn ParseWorker(input: ParseInput) -> ParsedPart {
+    let parsed = match input {
+        ParseInput::Trace(format, bytes) => parse_trace(format, bytes),
+        ParseInput::Lines(bytes) => match String::from_utf8(bytes) {
+            Ok(lines) => return parse_snapshot_part(&lines),
+            Err(e) => Err(e.to_string()),
+        },
+    };
+    match parsed {
+        Ok((snapshots, errors)) => ParsedPart {
+            snapshots,
+            errors,
+            ..ParsedPart::default()
+        },
+        Err(e) => ParsedPart {
+            errors: vec![ParseError::new(
+                0,
+                format!("Failed to decode trace: {}", e),
+                "",
+            )],
+            ..ParsedPart::default()
+        },
+    }
+}
+
+/// Spawn a parser worker from the `parser_worker` binary emitted alongside the app.
+pub(crate) fn spawn_parser() -> OneshotBridge<ParseWorker> {
+    ParseWorker::spawner().spawn("parser_worker.js")
+}